@@ -7,8 +7,6 @@ import {
   PublicKey,
   SystemProgram,
   Keypair,
-  Transaction,
-  VersionedTransaction,
   LAMPORTS_PER_SOL,
 } from '@solana/web3.js'
 import * as anchor from '@coral-xyz/anchor'
@@ -18,14 +16,32 @@ import {
   getAssociatedTokenAddressSync,
   TOKEN_PROGRAM_ID,
 } from '@solana/spl-token'
-import idlJson from '../idl/konnect.json'
+import { IDL, Konnect } from '../idl'
 
 const PROGRAM_ID = new PublicKey(
   'mbLjS3jLDX74Ptza9EiiG4qcPPE9aPS7EzifCLZc5hJ'
 )
-const MARKETPLACE_PDA = new PublicKey(
-  '3DqwXpNUVu14PpGF6XVAFtmnZtCDJ2Zkapn2PaEWgcxN'
+// a marketplace is keyed by its authority and an id, so one operator can run several storefronts
+const MARKETPLACE_AUTHORITY = new PublicKey(
+  import.meta.env.VITE_MARKETPLACE_AUTHORITY ??
+    'GQMhmzTAN2g1DtWYZYcjh79EywANKfCgsgV7LTzDKiEK'
 )
+const MARKETPLACE_ID: string | undefined = import.meta.env.VITE_MARKETPLACE_ID
+// without an id this is the storefront created before ids existed, which keeps its
+// [marketplace, authority] address after migration and reads back as id 0
+const [MARKETPLACE_PDA] = PublicKey.findProgramAddressSync(
+  MARKETPLACE_ID === undefined
+    ? [Buffer.from('marketplace'), MARKETPLACE_AUTHORITY.toBuffer()]
+    : [
+        Buffer.from('marketplace'),
+        MARKETPLACE_AUTHORITY.toBuffer(),
+        new BN(Number(MARKETPLACE_ID)).toArrayLike(Buffer, 'le', 2),
+      ],
+  PROGRAM_ID
+)
+
+const pda = (...seeds: Buffer[]) =>
+  PublicKey.findProgramAddressSync(seeds, PROGRAM_ID)[0]
 
 function makeAnchorWallet(wallet: any): any {
   return {
@@ -44,7 +60,7 @@ export default function Marketplace() {
   const wallet = useWallet()
   const { setVisible } = useWalletModal()
 
-  const [program, setProgram] = useState<Program | null>(null)
+  const [program, setProgram] = useState<Program<Konnect> | null>(null)
   const [listings, setListings] = useState<any[]>([])
   const [loading, setLoading] = useState(false)
   const [status, setStatus] = useState('')
@@ -78,6 +94,7 @@ export default function Marketplace() {
         commitment: 'confirmed',
       })
       anchor.setProvider(provider)
+      setProgram(new Program(IDL, provider))
     } catch (err) {
       console.error('Program init error', err)
       setProgram(null)
@@ -114,6 +131,8 @@ export default function Marketplace() {
 
     try {
       const form = new FormData(e.target as HTMLFormElement)
+      const name = form.get('name') as string
+      const imageUrl = (form.get('imageUrl') as string) ?? ''
       const price = parseFloat(form.get('price') as string)
       const qty = parseInt(form.get('quantity') as string, 10)
 
@@ -132,35 +151,47 @@ export default function Marketplace() {
       )
 
       // verify merchant exists
+      let merchant
       try {
-        await program.account.merchant.fetch(merchantPda)
+        merchant = await program.account.merchant.fetch(merchantPda)
       } catch {
         throw new Error('Register as a merchant first')
       }
 
-      if (!listings.length) throw new Error('No mint available')
-      const mint = listings[0].account.mint
+      // storefronts price in the marketplace's default mint
+      const marketplace = await program.account.marketplace.fetch(MARKETPLACE_PDA)
+      const mint = marketplace.metadata.defaultMint
+      if (mint.equals(PublicKey.default)) throw new Error('No mint available')
 
-      // listing PDA
-      const [listingPda] = PublicKey.findProgramAddressSync(
-        [
-          Buffer.from('listing'),
-          MARKETPLACE_PDA.toBuffer(),
-          merchantPda.toBuffer(),
-          mint.toBuffer(),
-        ],
-        PROGRAM_ID
+      // listing PDA, numbered per merchant
+      const listingPda = pda(
+        Buffer.from('listing'),
+        merchantPda.toBuffer(),
+        merchant.nextNonce.toArrayLike(Buffer, 'le', 8)
       )
+      const bondPda = pda(Buffer.from('bond'), merchantPda.toBuffer())
+      const bond = await connection.getAccountInfo(bondPda)
 
       // create
       const sig = await program.methods
-        .createListing(new BN(price * LAMPORTS_PER_SOL), qty, false, name, imageUrl)
-        .accounts({
+        .createListing(
+          new BN(price * LAMPORTS_PER_SOL),
+          qty,
+          false,
+          name,
+          imageUrl,
+          null,
+          { category: 0, tags: [], uri: '', contentHash: Array(32).fill(0), images: [] },
+          { saleStart: null, saleEnd: null },
+          { maxPerWallet: null, allowlistRoot: null }
+        )
+        .accountsPartial({
           marketplace: MARKETPLACE_PDA,
           merchant: merchantPda,
           listing: listingPda,
           owner: wallet.publicKey,
           mint,
+          bond: bond ? bondPda : null,
           systemProgram: SystemProgram.programId,
         })
         .rpc()
@@ -185,31 +216,52 @@ export default function Marketplace() {
     setStatus('Buying…')
 
     try {
+      const l = listing.account
+      if (l.priceFeed || l.variantCount > 0 || l.limits.maxPerWallet !== null) {
+        throw new Error('This listing needs the full checkout')
+      }
+
       const reference = Keypair.generate().publicKey
-      const sellerAta = getAssociatedTokenAddressSync(
-        listing.account.mint,
-        listing.account.seller
-      )
-      const buyerAta = getAssociatedTokenAddressSync(
-        listing.account.mint,
-        wallet.publicKey
-      )
-      const treasuryAta = getAssociatedTokenAddressSync(
-        listing.account.mint,
-        MARKETPLACE_PDA
+      const sellerAta = getAssociatedTokenAddressSync(l.mint, l.seller)
+      const buyerAta = getAssociatedTokenAddressSync(l.mint, wallet.publicKey)
+      // fees go to the marketplace's program-owned treasury for this mint
+      const treasury = pda(
+        Buffer.from('treasury'),
+        MARKETPLACE_PDA.toBuffer(),
+        l.mint.toBuffer()
       )
+      const treasuryVault = getAssociatedTokenAddressSync(l.mint, treasury, true)
 
+      // fails if the seller changed the listing or the price since it was shown
       const sig = await program.methods
-        .buyNow(1, reference)
-        .accounts({
+        .buyNow(1, reference, null, l.revision, l.price, null, null)
+        .accountsPartial({
           listing: listing.publicKey,
           marketplace: MARKETPLACE_PDA,
           buyer: wallet.publicKey,
+          signer: wallet.publicKey,
+          session: null,
+          payer: wallet.publicKey,
           buyerAta,
           sellerAta,
-          treasuryAta,
-          mint: listing.account.mint,
+          merchant: pda(Buffer.from('merchant'), MARKETPLACE_PDA.toBuffer(), l.seller.toBuffer()),
+          treasury,
+          treasuryVault,
+          mint: l.mint,
+          priceFeed: null,
+          reservation: null,
+          reservationRentPayer: null,
+          variant: null,
+          purchaseRecord: null,
+          // kept so the buyer can review the order or ask for a return
+          receipt: pda(Buffer.from('receipt'), reference.toBuffer()),
+          buyerProfile: pda(
+            Buffer.from('buyer'),
+            MARKETPLACE_PDA.toBuffer(),
+            wallet.publicKey.toBuffer()
+          ),
           tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
         })
         .remainingAccounts([
           { pubkey: reference, isWritable: false, isSigner: false },
@@ -268,6 +320,8 @@ export default function Marketplace() {
       <div>
         <h2>Create Listing (Demo)</h2>
         <form onSubmit={handleCreateListing} style={{ display: 'flex', gap: '10px', marginTop: '10px' }}>
+          <input name="name" placeholder="Name" required />
+          <input name="imageUrl" placeholder="Image URL" />
           <input
             name="price"
             type="number"
@@ -297,11 +351,14 @@ export default function Marketplace() {
       ) : (
         <div>
           {listings.map((l) => (
-            <div>
+            <div key={l.publicKey.toString()}>
               <p>
                 {l.publicKey.toString()}
               </p>
               <div>
+                <div>
+                  <strong>{l.account.name}</strong>
+                </div>
                 <div>
                   <strong>Seller:</strong> {l.account.seller.toString().slice(0, 8)}...
                 </div>
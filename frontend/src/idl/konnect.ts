@@ -1,47 +1,8701 @@
+/**
+ * Program IDL in camelCase format in order to be used in JS/TS.
+ *
+ * Note that this is only a type helper and is not the actual IDL. The original
+ * IDL can be found at `konnect.json`.
+ */
 export type Konnect = {
-  "version": "0.1.0",
-  "name": "konnect",
+  "address": "mbLjS3jLDX74Ptza9EiiG4qcPPE9aPS7EzifCLZc5hJ",
+  "metadata": {
+    "name": "konnect",
+    "version": "0.1.0",
+    "spec": "0.1.0",
+    "description": "Created with Anchor"
+  },
   "instructions": [
     {
-      "name": "initMarketplace",
+      "name": "addCatalogEntry",
+      "discriminator": [
+        236,
+        136,
+        157,
+        222,
+        69,
+        166,
+        70,
+        32
+      ],
       "accounts": [
         {
           "name": "marketplace",
-          "isMut": true,
-          "isSigner": false
+          "relations": [
+            "merchant"
+          ]
         },
         {
-          "name": "authority",
-          "isMut": true,
-          "isSigner": true
+          "name": "merchant",
+          "relations": [
+            "catalog"
+          ]
+        },
+        {
+          "name": "catalog",
+          "writable": true
+        },
+        {
+          "name": "owner",
+          "signer": true,
+          "relations": [
+            "merchant"
+          ]
+        },
+        {
+          "name": "bond",
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  111,
+                  110,
+                  100
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "merchant"
+              }
+            ]
+          }
+        }
+      ],
+      "args": [
+        {
+          "name": "price",
+          "type": "u64"
+        },
+        {
+          "name": "quantity",
+          "type": "u32"
+        },
+        {
+          "name": "contentHash",
+          "type": {
+            "array": [
+              "u8",
+              32
+            ]
+          }
+        }
+      ]
+    },
+    {
+      "name": "addVariant",
+      "discriminator": [
+        117,
+        243,
+        228,
+        95,
+        92,
+        103,
+        247,
+        12
+      ],
+      "accounts": [
+        {
+          "name": "listing",
+          "writable": true
+        },
+        {
+          "name": "seller",
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "listing"
+          ]
+        },
+        {
+          "name": "variant",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  118,
+                  97,
+                  114,
+                  105,
+                  97,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "listing"
+              },
+              {
+                "kind": "arg",
+                "path": "id"
+              }
+            ]
+          }
         },
         {
           "name": "systemProgram",
-          "isMut": false,
-          "isSigner": false
+          "address": "11111111111111111111111111111111"
         }
       ],
       "args": [
         {
-          "name": "feeBps",
+          "name": "id",
           "type": "u16"
+        },
+        {
+          "name": "price",
+          "type": "u64"
+        },
+        {
+          "name": "quantity",
+          "type": "u32"
         }
       ]
-    }
-  ],
-  "accounts": [
+    },
+    {
+      "name": "awardBadge",
+      "discriminator": [
+        163,
+        255,
+        101,
+        118,
+        140,
+        5,
+        179,
+        99
+      ],
+      "accounts": [
+        {
+          "name": "marketplace",
+          "relations": [
+            "badge"
+          ]
+        },
+        {
+          "name": "badge",
+          "writable": true
+        },
+        {
+          "name": "badgeMint",
+          "writable": true
+        },
+        {
+          "name": "user"
+        },
+        {
+          "name": "badgeAward",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  97,
+                  100,
+                  103,
+                  101,
+                  95,
+                  97,
+                  119,
+                  97,
+                  114,
+                  100
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "badge"
+              },
+              {
+                "kind": "account",
+                "path": "user"
+              }
+            ]
+          }
+        },
+        {
+          "name": "badgeAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "user"
+              },
+              {
+                "kind": "account",
+                "path": "tokenProgram"
+              },
+              {
+                "kind": "account",
+                "path": "badgeMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "buyerProfile",
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  117,
+                  121,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "marketplace"
+              },
+              {
+                "kind": "account",
+                "path": "user"
+              }
+            ]
+          }
+        },
+        {
+          "name": "merchant",
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  101,
+                  114,
+                  99,
+                  104,
+                  97,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "marketplace"
+              },
+              {
+                "kind": "account",
+                "path": "user"
+              }
+            ]
+          }
+        },
+        {
+          "name": "payer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb"
+        },
+        {
+          "name": "associatedTokenProgram",
+          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "buyCatalogEntry",
+      "discriminator": [
+        146,
+        143,
+        250,
+        125,
+        61,
+        239,
+        204,
+        140
+      ],
+      "accounts": [
+        {
+          "name": "catalog",
+          "writable": true
+        },
+        {
+          "name": "marketplace",
+          "writable": true,
+          "relations": [
+            "catalog"
+          ]
+        },
+        {
+          "name": "merchant",
+          "writable": true,
+          "relations": [
+            "catalog"
+          ]
+        },
+        {
+          "name": "buyer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "buyerAta",
+          "writable": true
+        },
+        {
+          "name": "sellerAta",
+          "writable": true
+        },
+        {
+          "name": "treasury",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  101,
+                  97,
+                  115,
+                  117,
+                  114,
+                  121
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "marketplace"
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "treasuryVault",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "treasury"
+              },
+              {
+                "kind": "const",
+                "value": [
+                  6,
+                  221,
+                  246,
+                  225,
+                  215,
+                  101,
+                  161,
+                  147,
+                  217,
+                  203,
+                  225,
+                  70,
+                  206,
+                  235,
+                  121,
+                  172,
+                  28,
+                  180,
+                  133,
+                  237,
+                  95,
+                  91,
+                  55,
+                  145,
+                  58,
+                  140,
+                  245,
+                  133,
+                  126,
+                  255,
+                  0,
+                  169
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "mint",
+          "relations": [
+            "catalog"
+          ]
+        },
+        {
+          "name": "buyerProfile",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  117,
+                  121,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "marketplace"
+              },
+              {
+                "kind": "account",
+                "path": "buyer"
+              }
+            ]
+          }
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "index",
+          "type": "u32"
+        },
+        {
+          "name": "quantity",
+          "type": "u32"
+        },
+        {
+          "name": "reference",
+          "type": "pubkey"
+        },
+        {
+          "name": "expectedRevision",
+          "type": {
+            "option": "u32"
+          }
+        },
+        {
+          "name": "maxTotal",
+          "type": {
+            "option": "u64"
+          }
+        }
+      ]
+    },
+    {
+      "name": "buyCompressedListing",
+      "discriminator": [
+        156,
+        210,
+        42,
+        225,
+        63,
+        70,
+        83,
+        87
+      ],
+      "accounts": [
+        {
+          "name": "listingTree"
+        },
+        {
+          "name": "merkleTree",
+          "writable": true,
+          "relations": [
+            "listingTree"
+          ]
+        },
+        {
+          "name": "marketplace",
+          "writable": true,
+          "relations": [
+            "listingTree"
+          ]
+        },
+        {
+          "name": "merchant",
+          "writable": true,
+          "relations": [
+            "listingTree"
+          ]
+        },
+        {
+          "name": "buyer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "buyerAta",
+          "writable": true
+        },
+        {
+          "name": "sellerAta",
+          "writable": true
+        },
+        {
+          "name": "treasury",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  101,
+                  97,
+                  115,
+                  117,
+                  114,
+                  121
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "marketplace"
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "treasuryVault",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "treasury"
+              },
+              {
+                "kind": "const",
+                "value": [
+                  6,
+                  221,
+                  246,
+                  225,
+                  215,
+                  101,
+                  161,
+                  147,
+                  217,
+                  203,
+                  225,
+                  70,
+                  206,
+                  235,
+                  121,
+                  172,
+                  28,
+                  180,
+                  133,
+                  237,
+                  95,
+                  91,
+                  55,
+                  145,
+                  58,
+                  140,
+                  245,
+                  133,
+                  126,
+                  255,
+                  0,
+                  169
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "buyerProfile",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  117,
+                  121,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "marketplace"
+              },
+              {
+                "kind": "account",
+                "path": "buyer"
+              }
+            ]
+          }
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        },
+        {
+          "name": "compressionProgram",
+          "address": "cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK"
+        },
+        {
+          "name": "noopProgram",
+          "address": "noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV"
+        }
+      ],
+      "args": [
+        {
+          "name": "root",
+          "type": {
+            "array": [
+              "u8",
+              32
+            ]
+          }
+        },
+        {
+          "name": "listing",
+          "type": {
+            "defined": {
+              "name": "compressedListing"
+            }
+          }
+        },
+        {
+          "name": "quantity",
+          "type": "u32"
+        },
+        {
+          "name": "reference",
+          "type": "pubkey"
+        },
+        {
+          "name": "maxTotal",
+          "type": {
+            "option": "u64"
+          }
+        }
+      ]
+    },
+    {
+      "name": "buyNow",
+      "discriminator": [
+        242,
+        42,
+        184,
+        77,
+        133,
+        152,
+        118,
+        204
+      ],
+      "accounts": [
+        {
+          "name": "listing",
+          "writable": true,
+          "relations": [
+            "reservation",
+            "variant"
+          ]
+        },
+        {
+          "name": "marketplace",
+          "writable": true,
+          "relations": [
+            "listing"
+          ]
+        },
+        {
+          "name": "buyer",
+          "relations": [
+            "reservation"
+          ]
+        },
+        {
+          "name": "signer",
+          "signer": true
+        },
+        {
+          "name": "session",
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  115,
+                  101,
+                  115,
+                  115,
+                  105,
+                  111,
+                  110
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "buyer"
+              },
+              {
+                "kind": "account",
+                "path": "signer"
+              }
+            ]
+          }
+        },
+        {
+          "name": "payer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "buyerAta",
+          "writable": true
+        },
+        {
+          "name": "sellerAta",
+          "writable": true
+        },
+        {
+          "name": "merchant",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  101,
+                  114,
+                  99,
+                  104,
+                  97,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "marketplace"
+              },
+              {
+                "kind": "account",
+                "path": "listing.seller",
+                "account": "listing"
+              }
+            ]
+          }
+        },
+        {
+          "name": "treasury",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  101,
+                  97,
+                  115,
+                  117,
+                  114,
+                  121
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "marketplace"
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "treasuryVault",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "treasury"
+              },
+              {
+                "kind": "const",
+                "value": [
+                  6,
+                  221,
+                  246,
+                  225,
+                  215,
+                  101,
+                  161,
+                  147,
+                  217,
+                  203,
+                  225,
+                  70,
+                  206,
+                  235,
+                  121,
+                  172,
+                  28,
+                  180,
+                  133,
+                  237,
+                  95,
+                  91,
+                  55,
+                  145,
+                  58,
+                  140,
+                  245,
+                  133,
+                  126,
+                  255,
+                  0,
+                  169
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "mint",
+          "relations": [
+            "listing"
+          ]
+        },
+        {
+          "name": "priceFeed",
+          "optional": true
+        },
+        {
+          "name": "reservation",
+          "writable": true,
+          "optional": true
+        },
+        {
+          "name": "reservationRentPayer",
+          "writable": true,
+          "optional": true
+        },
+        {
+          "name": "variant",
+          "writable": true,
+          "optional": true
+        },
+        {
+          "name": "purchaseRecord",
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  117,
+                  114,
+                  99,
+                  104,
+                  97,
+                  115,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "listing"
+              },
+              {
+                "kind": "account",
+                "path": "buyer"
+              }
+            ]
+          }
+        },
+        {
+          "name": "receipt",
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  99,
+                  101,
+                  105,
+                  112,
+                  116
+                ]
+              },
+              {
+                "kind": "arg",
+                "path": "reference"
+              }
+            ]
+          }
+        },
+        {
+          "name": "buyerProfile",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  117,
+                  121,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "marketplace"
+              },
+              {
+                "kind": "account",
+                "path": "buyer"
+              }
+            ]
+          }
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "quantity",
+          "type": "u32"
+        },
+        {
+          "name": "reference",
+          "type": "pubkey"
+        },
+        {
+          "name": "priceGuard",
+          "type": {
+            "option": {
+              "defined": {
+                "name": "priceGuard"
+              }
+            }
+          }
+        },
+        {
+          "name": "expectedRevision",
+          "type": {
+            "option": "u32"
+          }
+        },
+        {
+          "name": "maxTotal",
+          "type": {
+            "option": "u64"
+          }
+        },
+        {
+          "name": "variantId",
+          "type": {
+            "option": "u16"
+          }
+        },
+        {
+          "name": "allowlistProof",
+          "type": {
+            "option": {
+              "vec": {
+                "array": [
+                  "u8",
+                  32
+                ]
+              }
+            }
+          }
+        }
+      ]
+    },
+    {
+      "name": "cancelServiceOrder",
+      "discriminator": [
+        45,
+        9,
+        92,
+        49,
+        137,
+        82,
+        165,
+        90
+      ],
+      "accounts": [
+        {
+          "name": "escrow",
+          "writable": true
+        },
+        {
+          "name": "marketplace",
+          "writable": true,
+          "relations": [
+            "escrow"
+          ]
+        },
+        {
+          "name": "listing",
+          "writable": true,
+          "relations": [
+            "escrow"
+          ]
+        },
+        {
+          "name": "payer",
+          "signer": true
+        },
+        {
+          "name": "rentPayer",
+          "writable": true
+        },
+        {
+          "name": "refundAta",
+          "writable": true
+        },
+        {
+          "name": "treasury",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  101,
+                  97,
+                  115,
+                  117,
+                  114,
+                  121
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "marketplace"
+              },
+              {
+                "kind": "account",
+                "path": "escrow.mint",
+                "account": "escrow"
+              }
+            ]
+          }
+        },
+        {
+          "name": "vault",
+          "writable": true
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "checkoutCart",
+      "discriminator": [
+        170,
+        4,
+        228,
+        173,
+        144,
+        44,
+        196,
+        160
+      ],
+      "accounts": [
+        {
+          "name": "marketplace",
+          "writable": true
+        },
+        {
+          "name": "buyer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "buyerAta",
+          "writable": true
+        },
+        {
+          "name": "treasury",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  101,
+                  97,
+                  115,
+                  117,
+                  114,
+                  121
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "marketplace"
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "treasuryVault",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "treasury"
+              },
+              {
+                "kind": "const",
+                "value": [
+                  6,
+                  221,
+                  246,
+                  225,
+                  215,
+                  101,
+                  161,
+                  147,
+                  217,
+                  203,
+                  225,
+                  70,
+                  206,
+                  235,
+                  121,
+                  172,
+                  28,
+                  180,
+                  133,
+                  237,
+                  95,
+                  91,
+                  55,
+                  145,
+                  58,
+                  140,
+                  245,
+                  133,
+                  126,
+                  255,
+                  0,
+                  169
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "buyerProfile",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  117,
+                  121,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "marketplace"
+              },
+              {
+                "kind": "account",
+                "path": "buyer"
+              }
+            ]
+          }
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "items",
+          "type": {
+            "vec": {
+              "defined": {
+                "name": "cartItem"
+              }
+            }
+          }
+        },
+        {
+          "name": "reference",
+          "type": "pubkey"
+        }
+      ]
+    },
+    {
+      "name": "closeCatalog",
+      "discriminator": [
+        95,
+        47,
+        97,
+        137,
+        77,
+        57,
+        251,
+        204
+      ],
+      "accounts": [
+        {
+          "name": "catalog",
+          "writable": true
+        },
+        {
+          "name": "seller",
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "catalog"
+          ]
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "closeEscrow",
+      "discriminator": [
+        139,
+        171,
+        94,
+        146,
+        191,
+        91,
+        144,
+        50
+      ],
+      "accounts": [
+        {
+          "name": "escrow",
+          "writable": true
+        },
+        {
+          "name": "buyer",
+          "signer": true,
+          "relations": [
+            "escrow"
+          ]
+        },
+        {
+          "name": "rentPayer",
+          "writable": true
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "closeListing",
+      "discriminator": [
+        33,
+        15,
+        192,
+        81,
+        78,
+        175,
+        159,
+        97
+      ],
+      "accounts": [
+        {
+          "name": "listing",
+          "writable": true
+        },
+        {
+          "name": "seller",
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "listing"
+          ]
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "closeReceipt",
+      "discriminator": [
+        126,
+        254,
+        244,
+        203,
+        124,
+        164,
+        134,
+        89
+      ],
+      "accounts": [
+        {
+          "name": "receipt",
+          "writable": true
+        },
+        {
+          "name": "buyer",
+          "signer": true,
+          "relations": [
+            "receipt"
+          ]
+        },
+        {
+          "name": "rentPayer",
+          "writable": true,
+          "relations": [
+            "receipt"
+          ]
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "createBadge",
+      "discriminator": [
+        25,
+        191,
+        79,
+        235,
+        98,
+        119,
+        184,
+        59
+      ],
+      "accounts": [
+        {
+          "name": "marketplace"
+        },
+        {
+          "name": "badge",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  97,
+                  100,
+                  103,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "marketplace"
+              },
+              {
+                "kind": "arg",
+                "path": "id"
+              }
+            ]
+          }
+        },
+        {
+          "name": "badgeMint",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  97,
+                  100,
+                  103,
+                  101,
+                  95,
+                  109,
+                  105,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "badge"
+              }
+            ]
+          }
+        },
+        {
+          "name": "authority",
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "marketplace"
+          ]
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "id",
+          "type": "u16"
+        },
+        {
+          "name": "name",
+          "type": "string"
+        },
+        {
+          "name": "kind",
+          "type": {
+            "defined": {
+              "name": "badgeKind"
+            }
+          }
+        },
+        {
+          "name": "threshold",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "createCompressedListing",
+      "discriminator": [
+        133,
+        112,
+        196,
+        239,
+        189,
+        157,
+        26,
+        146
+      ],
+      "accounts": [
+        {
+          "name": "marketplace",
+          "relations": [
+            "merchant"
+          ]
+        },
+        {
+          "name": "merchant",
+          "relations": [
+            "listingTree"
+          ]
+        },
+        {
+          "name": "listingTree",
+          "writable": true
+        },
+        {
+          "name": "merkleTree",
+          "writable": true,
+          "relations": [
+            "listingTree"
+          ]
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "owner",
+          "signer": true,
+          "relations": [
+            "merchant"
+          ]
+        },
+        {
+          "name": "bond",
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  111,
+                  110,
+                  100
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "merchant"
+              }
+            ]
+          }
+        },
+        {
+          "name": "compressionProgram",
+          "address": "cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK"
+        },
+        {
+          "name": "noopProgram",
+          "address": "noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV"
+        }
+      ],
+      "args": [
+        {
+          "name": "price",
+          "type": "u64"
+        },
+        {
+          "name": "quantity",
+          "type": "u32"
+        },
+        {
+          "name": "metadataHash",
+          "type": {
+            "array": [
+              "u8",
+              32
+            ]
+          }
+        }
+      ]
+    },
+    {
+      "name": "createListing",
+      "discriminator": [
+        18,
+        168,
+        45,
+        24,
+        191,
+        31,
+        117,
+        54
+      ],
+      "accounts": [
+        {
+          "name": "marketplace",
+          "writable": true,
+          "relations": [
+            "merchant"
+          ]
+        },
+        {
+          "name": "merchant",
+          "writable": true
+        },
+        {
+          "name": "owner",
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "merchant"
+          ]
+        },
+        {
+          "name": "listing",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  108,
+                  105,
+                  115,
+                  116,
+                  105,
+                  110,
+                  103
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "merchant"
+              },
+              {
+                "kind": "account",
+                "path": "merchant.next_nonce",
+                "account": "merchant"
+              }
+            ]
+          }
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "bond",
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  111,
+                  110,
+                  100
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "merchant"
+              }
+            ]
+          }
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "price",
+          "type": "u64"
+        },
+        {
+          "name": "quantity",
+          "type": "u32"
+        },
+        {
+          "name": "isService",
+          "type": "bool"
+        },
+        {
+          "name": "name",
+          "type": "string"
+        },
+        {
+          "name": "imageUrl",
+          "type": "string"
+        },
+        {
+          "name": "priceFeed",
+          "type": {
+            "option": "pubkey"
+          }
+        },
+        {
+          "name": "metadata",
+          "type": {
+            "defined": {
+              "name": "listingMetadata"
+            }
+          }
+        },
+        {
+          "name": "saleWindow",
+          "type": {
+            "defined": {
+              "name": "saleWindow"
+            }
+          }
+        },
+        {
+          "name": "limits",
+          "type": {
+            "defined": {
+              "name": "purchaseLimits"
+            }
+          }
+        }
+      ]
+    },
+    {
+      "name": "createServiceOrder",
+      "discriminator": [
+        68,
+        151,
+        172,
+        157,
+        90,
+        1,
+        232,
+        70
+      ],
+      "accounts": [
+        {
+          "name": "marketplace",
+          "writable": true,
+          "relations": [
+            "listing"
+          ]
+        },
+        {
+          "name": "listing",
+          "writable": true
+        },
+        {
+          "name": "buyer"
+        },
+        {
+          "name": "signer",
+          "signer": true
+        },
+        {
+          "name": "session",
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  115,
+                  101,
+                  115,
+                  115,
+                  105,
+                  111,
+                  110
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "buyer"
+              },
+              {
+                "kind": "account",
+                "path": "signer"
+              }
+            ]
+          }
+        },
+        {
+          "name": "payer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "buyerAta",
+          "writable": true
+        },
+        {
+          "name": "escrow",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  101,
+                  115,
+                  99,
+                  114,
+                  111,
+                  119
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "listing"
+              },
+              {
+                "kind": "account",
+                "path": "buyer"
+              }
+            ]
+          }
+        },
+        {
+          "name": "vault",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "escrow"
+              },
+              {
+                "kind": "const",
+                "value": [
+                  6,
+                  221,
+                  246,
+                  225,
+                  215,
+                  101,
+                  161,
+                  147,
+                  217,
+                  203,
+                  225,
+                  70,
+                  206,
+                  235,
+                  121,
+                  172,
+                  28,
+                  180,
+                  133,
+                  237,
+                  95,
+                  91,
+                  55,
+                  145,
+                  58,
+                  140,
+                  245,
+                  133,
+                  126,
+                  255,
+                  0,
+                  169
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "treasury",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  101,
+                  97,
+                  115,
+                  117,
+                  114,
+                  121
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "marketplace"
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "mint",
+          "relations": [
+            "listing"
+          ]
+        },
+        {
+          "name": "priceFeed",
+          "optional": true
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        },
+        {
+          "name": "associatedTokenProgram",
+          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "reference",
+          "type": "pubkey"
+        },
+        {
+          "name": "priceGuard",
+          "type": {
+            "option": {
+              "defined": {
+                "name": "priceGuard"
+              }
+            }
+          }
+        },
+        {
+          "name": "expectedRevision",
+          "type": {
+            "option": "u32"
+          }
+        },
+        {
+          "name": "maxTotal",
+          "type": {
+            "option": "u64"
+          }
+        }
+      ]
+    },
+    {
+      "name": "createSessionKey",
+      "discriminator": [
+        137,
+        204,
+        246,
+        242,
+        200,
+        143,
+        215,
+        56
+      ],
+      "accounts": [
+        {
+          "name": "buyer",
+          "signer": true
+        },
+        {
+          "name": "payer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "session",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  115,
+                  101,
+                  115,
+                  115,
+                  105,
+                  111,
+                  110
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "buyer"
+              },
+              {
+                "kind": "arg",
+                "path": "sessionSigner"
+              }
+            ]
+          }
+        },
+        {
+          "name": "buyerAta",
+          "writable": true
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "sessionSigner",
+          "type": "pubkey"
+        },
+        {
+          "name": "spendLimit",
+          "type": "u64"
+        },
+        {
+          "name": "expiresAt",
+          "type": "i64"
+        }
+      ]
+    },
+    {
+      "name": "crossRegisterMerchant",
+      "discriminator": [
+        179,
+        74,
+        235,
+        103,
+        164,
+        71,
+        215,
+        173
+      ],
+      "accounts": [
+        {
+          "name": "sourceMerchant"
+        },
+        {
+          "name": "sourceMarketplace"
+        },
+        {
+          "name": "marketplace",
+          "writable": true
+        },
+        {
+          "name": "merchant",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  101,
+                  114,
+                  99,
+                  104,
+                  97,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "marketplace"
+              },
+              {
+                "kind": "account",
+                "path": "owner"
+              }
+            ]
+          }
+        },
+        {
+          "name": "owner",
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "sourceMerchant"
+          ]
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "depositBond",
+      "discriminator": [
+        120,
+        89,
+        18,
+        253,
+        112,
+        125,
+        87,
+        255
+      ],
+      "accounts": [
+        {
+          "name": "marketplace",
+          "relations": [
+            "merchant"
+          ]
+        },
+        {
+          "name": "merchant"
+        },
+        {
+          "name": "bond",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  111,
+                  110,
+                  100
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "merchant"
+              }
+            ]
+          }
+        },
+        {
+          "name": "vault",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "bond"
+              },
+              {
+                "kind": "const",
+                "value": [
+                  6,
+                  221,
+                  246,
+                  225,
+                  215,
+                  101,
+                  161,
+                  147,
+                  217,
+                  203,
+                  225,
+                  70,
+                  206,
+                  235,
+                  121,
+                  172,
+                  28,
+                  180,
+                  133,
+                  237,
+                  95,
+                  91,
+                  55,
+                  145,
+                  58,
+                  140,
+                  245,
+                  133,
+                  126,
+                  255,
+                  0,
+                  169
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "owner",
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "merchant"
+          ]
+        },
+        {
+          "name": "ownerAta",
+          "writable": true
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        },
+        {
+          "name": "associatedTokenProgram",
+          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "distributeFees",
+      "discriminator": [
+        120,
+        56,
+        27,
+        7,
+        53,
+        176,
+        113,
+        186
+      ],
+      "accounts": [
+        {
+          "name": "treasury",
+          "writable": true
+        },
+        {
+          "name": "treasuryVault",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "treasury"
+              },
+              {
+                "kind": "const",
+                "value": [
+                  6,
+                  221,
+                  246,
+                  225,
+                  215,
+                  101,
+                  161,
+                  147,
+                  217,
+                  203,
+                  225,
+                  70,
+                  206,
+                  235,
+                  121,
+                  172,
+                  28,
+                  180,
+                  133,
+                  237,
+                  95,
+                  91,
+                  55,
+                  145,
+                  58,
+                  140,
+                  245,
+                  133,
+                  126,
+                  255,
+                  0,
+                  169
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "treasury.mint",
+                "account": "treasury"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "growCatalog",
+      "discriminator": [
+        2,
+        214,
+        121,
+        77,
+        152,
+        185,
+        140,
+        180
+      ],
+      "accounts": [
+        {
+          "name": "catalog",
+          "writable": true
+        },
+        {
+          "name": "seller",
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "catalog"
+          ]
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "additional",
+          "type": "u32"
+        }
+      ]
+    },
+    {
+      "name": "initCatalog",
+      "discriminator": [
+        92,
+        191,
+        123,
+        159,
+        184,
+        89,
+        222,
+        156
+      ],
+      "accounts": [
+        {
+          "name": "marketplace",
+          "relations": [
+            "merchant"
+          ]
+        },
+        {
+          "name": "merchant"
+        },
+        {
+          "name": "catalog",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  99,
+                  97,
+                  116,
+                  97,
+                  108,
+                  111,
+                  103
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "merchant"
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "owner",
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "merchant"
+          ]
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "capacity",
+          "type": "u32"
+        },
+        {
+          "name": "baseUri",
+          "type": "string"
+        }
+      ]
+    },
+    {
+      "name": "initListingTree",
+      "discriminator": [
+        35,
+        7,
+        78,
+        38,
+        58,
+        145,
+        71,
+        9
+      ],
+      "accounts": [
+        {
+          "name": "marketplace",
+          "relations": [
+            "merchant"
+          ]
+        },
+        {
+          "name": "merchant"
+        },
+        {
+          "name": "listingTree",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  108,
+                  105,
+                  115,
+                  116,
+                  105,
+                  110,
+                  103,
+                  95,
+                  116,
+                  114,
+                  101,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "merkleTree"
+              }
+            ]
+          }
+        },
+        {
+          "name": "merkleTree",
+          "writable": true
+        },
+        {
+          "name": "owner",
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "merchant"
+          ]
+        },
+        {
+          "name": "compressionProgram",
+          "address": "cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK"
+        },
+        {
+          "name": "noopProgram",
+          "address": "noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "maxDepth",
+          "type": "u32"
+        },
+        {
+          "name": "maxBufferSize",
+          "type": "u32"
+        }
+      ]
+    },
+    {
+      "name": "initMarketplace",
+      "discriminator": [
+        242,
+        23,
+        212,
+        134,
+        238,
+        95,
+        82,
+        161
+      ],
+      "accounts": [
+        {
+          "name": "marketplace",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  97,
+                  114,
+                  107,
+                  101,
+                  116,
+                  112,
+                  108,
+                  97,
+                  99,
+                  101
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "authority"
+              },
+              {
+                "kind": "arg",
+                "path": "id"
+              }
+            ]
+          }
+        },
+        {
+          "name": "authority",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "id",
+          "type": "u16"
+        },
+        {
+          "name": "feeBps",
+          "type": "u16"
+        },
+        {
+          "name": "metadata",
+          "type": {
+            "defined": {
+              "name": "marketplaceMetadata"
+            }
+          }
+        }
+      ]
+    },
+    {
+      "name": "initPointsMint",
+      "discriminator": [
+        246,
+        213,
+        10,
+        163,
+        166,
+        141,
+        164,
+        240
+      ],
+      "accounts": [
+        {
+          "name": "marketplace"
+        },
+        {
+          "name": "pointsMint",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  111,
+                  105,
+                  110,
+                  116,
+                  115,
+                  95,
+                  109,
+                  105,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "marketplace"
+              }
+            ]
+          }
+        },
+        {
+          "name": "authority",
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "marketplace"
+          ]
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "initTreasury",
+      "discriminator": [
+        105,
+        152,
+        173,
+        51,
+        158,
+        151,
+        49,
+        14
+      ],
+      "accounts": [
+        {
+          "name": "marketplace"
+        },
+        {
+          "name": "authority",
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "marketplace"
+          ]
+        },
+        {
+          "name": "treasury",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  101,
+                  97,
+                  115,
+                  117,
+                  114,
+                  121
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "marketplace"
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ]
+          }
+        },
+        {
+          "name": "treasuryVault",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "treasury"
+              },
+              {
+                "kind": "const",
+                "value": [
+                  6,
+                  221,
+                  246,
+                  225,
+                  215,
+                  101,
+                  161,
+                  147,
+                  217,
+                  203,
+                  225,
+                  70,
+                  206,
+                  235,
+                  121,
+                  172,
+                  28,
+                  180,
+                  133,
+                  237,
+                  95,
+                  91,
+                  55,
+                  145,
+                  58,
+                  140,
+                  245,
+                  133,
+                  126,
+                  255,
+                  0,
+                  169
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "mint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        },
+        {
+          "name": "associatedTokenProgram",
+          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "migrateEscrow",
+      "discriminator": [
+        65,
+        111,
+        186,
+        119,
+        58,
+        11,
+        81,
+        209
+      ],
+      "accounts": [
+        {
+          "name": "account",
+          "writable": true
+        },
+        {
+          "name": "payer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "migrateListing",
+      "discriminator": [
+        202,
+        10,
+        210,
+        83,
+        249,
+        147,
+        116,
+        173
+      ],
+      "accounts": [
+        {
+          "name": "account",
+          "writable": true
+        },
+        {
+          "name": "payer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "migrateMarketplace",
+      "discriminator": [
+        166,
+        56,
+        109,
+        180,
+        190,
+        99,
+        237,
+        175
+      ],
+      "accounts": [
+        {
+          "name": "account",
+          "writable": true
+        },
+        {
+          "name": "payer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "migrateMerchant",
+      "discriminator": [
+        74,
+        230,
+        161,
+        163,
+        14,
+        34,
+        101,
+        166
+      ],
+      "accounts": [
+        {
+          "name": "account",
+          "writable": true
+        },
+        {
+          "name": "payer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "redeemPoints",
+      "discriminator": [
+        178,
+        79,
+        85,
+        218,
+        121,
+        101,
+        34,
+        12
+      ],
+      "accounts": [
+        {
+          "name": "buyerProfile",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  117,
+                  121,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "buyer_profile.marketplace",
+                "account": "buyerProfile"
+              },
+              {
+                "kind": "account",
+                "path": "buyer"
+              }
+            ]
+          }
+        },
+        {
+          "name": "buyer",
+          "signer": true,
+          "relations": [
+            "buyerProfile"
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "points",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "refundOrder",
+      "discriminator": [
+        164,
+        168,
+        47,
+        144,
+        154,
+        1,
+        241,
+        255
+      ],
+      "accounts": [
+        {
+          "name": "receipt",
+          "writable": true,
+          "relations": [
+            "returnRequest"
+          ]
+        },
+        {
+          "name": "returnRequest",
+          "writable": true
+        },
+        {
+          "name": "marketplace",
+          "relations": [
+            "receipt"
+          ]
+        },
+        {
+          "name": "merchant",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  101,
+                  114,
+                  99,
+                  104,
+                  97,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "marketplace"
+              },
+              {
+                "kind": "account",
+                "path": "seller"
+              }
+            ]
+          }
+        },
+        {
+          "name": "buyerProfile",
+          "writable": true,
+          "optional": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  117,
+                  121,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "marketplace"
+              },
+              {
+                "kind": "account",
+                "path": "receipt.buyer",
+                "account": "receipt"
+              }
+            ]
+          }
+        },
+        {
+          "name": "listing",
+          "writable": true,
+          "optional": true
+        },
+        {
+          "name": "variant",
+          "writable": true,
+          "optional": true
+        },
+        {
+          "name": "seller",
+          "signer": true,
+          "relations": [
+            "receipt"
+          ]
+        },
+        {
+          "name": "buyer",
+          "writable": true,
+          "relations": [
+            "receipt"
+          ]
+        },
+        {
+          "name": "sellerAta",
+          "writable": true
+        },
+        {
+          "name": "refundAta",
+          "writable": true
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        }
+      ],
+      "args": [
+        {
+          "name": "restock",
+          "type": "bool"
+        }
+      ]
+    },
+    {
+      "name": "registerMerchant",
+      "discriminator": [
+        238,
+        245,
+        77,
+        132,
+        161,
+        88,
+        216,
+        248
+      ],
+      "accounts": [
+        {
+          "name": "marketplace",
+          "writable": true
+        },
+        {
+          "name": "merchant",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  101,
+                  114,
+                  99,
+                  104,
+                  97,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "marketplace"
+              },
+              {
+                "kind": "account",
+                "path": "owner"
+              }
+            ]
+          }
+        },
+        {
+          "name": "owner",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "rejectReturn",
+      "discriminator": [
+        49,
+        254,
+        167,
+        145,
+        243,
+        200,
+        86,
+        19
+      ],
+      "accounts": [
+        {
+          "name": "receipt",
+          "writable": true,
+          "relations": [
+            "returnRequest"
+          ]
+        },
+        {
+          "name": "returnRequest",
+          "writable": true
+        },
+        {
+          "name": "seller",
+          "signer": true,
+          "relations": [
+            "receipt"
+          ]
+        },
+        {
+          "name": "buyer",
+          "writable": true,
+          "relations": [
+            "receipt"
+          ]
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "releaseReservation",
+      "discriminator": [
+        57,
+        15,
+        101,
+        135,
+        113,
+        21,
+        168,
+        247
+      ],
+      "accounts": [
+        {
+          "name": "reservation",
+          "writable": true
+        },
+        {
+          "name": "listing",
+          "writable": true,
+          "relations": [
+            "reservation"
+          ]
+        },
+        {
+          "name": "rentPayer",
+          "writable": true,
+          "relations": [
+            "reservation"
+          ]
+        },
+        {
+          "name": "caller",
+          "signer": true
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "releaseServiceOrder",
+      "discriminator": [
+        160,
+        33,
+        98,
+        120,
+        13,
+        105,
+        249,
+        205
+      ],
+      "accounts": [
+        {
+          "name": "escrow",
+          "writable": true
+        },
+        {
+          "name": "marketplace",
+          "writable": true,
+          "relations": [
+            "escrow"
+          ]
+        },
+        {
+          "name": "listing",
+          "writable": true,
+          "relations": [
+            "escrow"
+          ]
+        },
+        {
+          "name": "merchant",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  101,
+                  114,
+                  99,
+                  104,
+                  97,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "marketplace"
+              },
+              {
+                "kind": "account",
+                "path": "escrow.seller",
+                "account": "escrow"
+              }
+            ]
+          }
+        },
+        {
+          "name": "payer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "receipt",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  99,
+                  101,
+                  105,
+                  112,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "escrow"
+              },
+              {
+                "kind": "account",
+                "path": "escrow.reference",
+                "account": "escrow"
+              }
+            ]
+          }
+        },
+        {
+          "name": "rentPayer",
+          "writable": true
+        },
+        {
+          "name": "sellerAta",
+          "writable": true
+        },
+        {
+          "name": "treasury",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  116,
+                  114,
+                  101,
+                  97,
+                  115,
+                  117,
+                  114,
+                  121
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "marketplace"
+              },
+              {
+                "kind": "account",
+                "path": "escrow.mint",
+                "account": "escrow"
+              }
+            ]
+          }
+        },
+        {
+          "name": "treasuryVault",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "treasury"
+              },
+              {
+                "kind": "const",
+                "value": [
+                  6,
+                  221,
+                  246,
+                  225,
+                  215,
+                  101,
+                  161,
+                  147,
+                  217,
+                  203,
+                  225,
+                  70,
+                  206,
+                  235,
+                  121,
+                  172,
+                  28,
+                  180,
+                  133,
+                  237,
+                  95,
+                  91,
+                  55,
+                  145,
+                  58,
+                  140,
+                  245,
+                  133,
+                  126,
+                  255,
+                  0,
+                  169
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "escrow.mint",
+                "account": "escrow"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "vault",
+          "writable": true
+        },
+        {
+          "name": "mint"
+        },
+        {
+          "name": "buyerProfile",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  117,
+                  121,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "marketplace"
+              },
+              {
+                "kind": "account",
+                "path": "escrow.buyer",
+                "account": "escrow"
+              }
+            ]
+          }
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "removeCatalogEntry",
+      "discriminator": [
+        106,
+        33,
+        227,
+        142,
+        185,
+        122,
+        164,
+        20
+      ],
+      "accounts": [
+        {
+          "name": "catalog",
+          "writable": true
+        },
+        {
+          "name": "seller",
+          "signer": true,
+          "relations": [
+            "catalog"
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "index",
+          "type": "u32"
+        }
+      ]
+    },
+    {
+      "name": "removeVariant",
+      "discriminator": [
+        4,
+        175,
+        101,
+        253,
+        212,
+        96,
+        27,
+        127
+      ],
+      "accounts": [
+        {
+          "name": "variant",
+          "writable": true
+        },
+        {
+          "name": "listing",
+          "writable": true,
+          "relations": [
+            "variant"
+          ]
+        },
+        {
+          "name": "seller",
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "listing"
+          ]
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "requestBondWithdrawal",
+      "discriminator": [
+        66,
+        235,
+        186,
+        164,
+        34,
+        105,
+        80,
+        166
+      ],
+      "accounts": [
+        {
+          "name": "bond",
+          "writable": true
+        },
+        {
+          "name": "merchant",
+          "relations": [
+            "bond"
+          ]
+        },
+        {
+          "name": "marketplace",
+          "relations": [
+            "merchant"
+          ]
+        },
+        {
+          "name": "owner",
+          "signer": true,
+          "relations": [
+            "bond"
+          ]
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "requestReturn",
+      "discriminator": [
+        74,
+        176,
+        238,
+        73,
+        206,
+        45,
+        244,
+        133
+      ],
+      "accounts": [
+        {
+          "name": "receipt",
+          "writable": true
+        },
+        {
+          "name": "marketplace",
+          "relations": [
+            "receipt"
+          ]
+        },
+        {
+          "name": "returnRequest",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  116,
+                  117,
+                  114,
+                  110
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "receipt"
+              }
+            ]
+          }
+        },
+        {
+          "name": "buyer",
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "receipt"
+          ]
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "reasonHash",
+          "type": {
+            "array": [
+              "u8",
+              32
+            ]
+          }
+        }
+      ]
+    },
+    {
+      "name": "reserveInventory",
+      "discriminator": [
+        176,
+        77,
+        133,
+        114,
+        118,
+        212,
+        11,
+        21
+      ],
+      "accounts": [
+        {
+          "name": "listing",
+          "writable": true
+        },
+        {
+          "name": "buyer",
+          "signer": true
+        },
+        {
+          "name": "payer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "reservation",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  115,
+                  101,
+                  114,
+                  118,
+                  97,
+                  116,
+                  105,
+                  111,
+                  110
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "listing"
+              },
+              {
+                "kind": "account",
+                "path": "buyer"
+              }
+            ]
+          }
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "quantity",
+          "type": "u32"
+        },
+        {
+          "name": "ttl",
+          "type": "i64"
+        },
+        {
+          "name": "allowlistProof",
+          "type": {
+            "option": {
+              "vec": {
+                "array": [
+                  "u8",
+                  32
+                ]
+              }
+            }
+          }
+        }
+      ]
+    },
+    {
+      "name": "respondToReview",
+      "discriminator": [
+        72,
+        223,
+        211,
+        188,
+        48,
+        223,
+        39,
+        67
+      ],
+      "accounts": [
+        {
+          "name": "review",
+          "writable": true
+        },
+        {
+          "name": "seller",
+          "signer": true,
+          "relations": [
+            "review"
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "responseHash",
+          "type": {
+            "array": [
+              "u8",
+              32
+            ]
+          }
+        }
+      ]
+    },
+    {
+      "name": "revokeSessionKey",
+      "discriminator": [
+        81,
+        192,
+        32,
+        110,
+        104,
+        116,
+        144,
+        151
+      ],
+      "accounts": [
+        {
+          "name": "session",
+          "writable": true
+        },
+        {
+          "name": "buyer",
+          "signer": true,
+          "relations": [
+            "session"
+          ]
+        },
+        {
+          "name": "rentPayer",
+          "writable": true,
+          "relations": [
+            "session"
+          ]
+        },
+        {
+          "name": "buyerAta",
+          "writable": true
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "setBadgeStatus",
+      "discriminator": [
+        227,
+        140,
+        153,
+        11,
+        202,
+        209,
+        175,
+        238
+      ],
+      "accounts": [
+        {
+          "name": "badge",
+          "writable": true
+        },
+        {
+          "name": "marketplace",
+          "relations": [
+            "badge"
+          ]
+        },
+        {
+          "name": "authority",
+          "signer": true,
+          "relations": [
+            "marketplace"
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "active",
+          "type": "bool"
+        }
+      ]
+    },
+    {
+      "name": "setFeeSplits",
+      "discriminator": [
+        128,
+        192,
+        6,
+        243,
+        95,
+        243,
+        227,
+        114
+      ],
+      "accounts": [
+        {
+          "name": "marketplace",
+          "relations": [
+            "treasury"
+          ]
+        },
+        {
+          "name": "treasury",
+          "writable": true
+        },
+        {
+          "name": "treasuryAuthority",
+          "signer": true,
+          "relations": [
+            "marketplace"
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "splits",
+          "type": {
+            "vec": {
+              "defined": {
+                "name": "feeSplit"
+              }
+            }
+          }
+        }
+      ]
+    },
+    {
+      "name": "setMerchantStatus",
+      "discriminator": [
+        76,
+        172,
+        99,
+        200,
+        233,
+        226,
+        212,
+        102
+      ],
+      "accounts": [
+        {
+          "name": "merchant",
+          "writable": true
+        },
+        {
+          "name": "marketplace",
+          "relations": [
+            "merchant"
+          ]
+        },
+        {
+          "name": "authority",
+          "signer": true
+        }
+      ],
+      "args": [
+        {
+          "name": "verified",
+          "type": "bool"
+        }
+      ]
+    },
+    {
+      "name": "slashBond",
+      "discriminator": [
+        143,
+        246,
+        51,
+        243,
+        88,
+        198,
+        217,
+        48
+      ],
+      "accounts": [
+        {
+          "name": "bond",
+          "writable": true
+        },
+        {
+          "name": "merchant",
+          "relations": [
+            "bond"
+          ]
+        },
+        {
+          "name": "marketplace",
+          "relations": [
+            "merchant"
+          ]
+        },
+        {
+          "name": "arbiter",
+          "signer": true,
+          "relations": [
+            "marketplace"
+          ]
+        },
+        {
+          "name": "vault",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "bond"
+              },
+              {
+                "kind": "const",
+                "value": [
+                  6,
+                  221,
+                  246,
+                  225,
+                  215,
+                  101,
+                  161,
+                  147,
+                  217,
+                  203,
+                  225,
+                  70,
+                  206,
+                  235,
+                  121,
+                  172,
+                  28,
+                  180,
+                  133,
+                  237,
+                  95,
+                  91,
+                  55,
+                  145,
+                  58,
+                  140,
+                  245,
+                  133,
+                  126,
+                  255,
+                  0,
+                  169
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "bond.mint",
+                "account": "merchantBond"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "recipientAta",
+          "writable": true
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        }
+      ],
+      "args": [
+        {
+          "name": "amount",
+          "type": "u64"
+        },
+        {
+          "name": "reference",
+          "type": "pubkey"
+        }
+      ]
+    },
+    {
+      "name": "submitReview",
+      "discriminator": [
+        106,
+        30,
+        50,
+        83,
+        89,
+        46,
+        213,
+        239
+      ],
+      "accounts": [
+        {
+          "name": "receipt"
+        },
+        {
+          "name": "listing",
+          "writable": true,
+          "relations": [
+            "receipt"
+          ]
+        },
+        {
+          "name": "merchant",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  109,
+                  101,
+                  114,
+                  99,
+                  104,
+                  97,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "listing.marketplace",
+                "account": "listing"
+              },
+              {
+                "kind": "account",
+                "path": "listing.seller",
+                "account": "listing"
+              }
+            ]
+          }
+        },
+        {
+          "name": "review",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  114,
+                  101,
+                  118,
+                  105,
+                  101,
+                  119
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "receipt"
+              }
+            ]
+          }
+        },
+        {
+          "name": "buyer",
+          "signer": true,
+          "relations": [
+            "receipt"
+          ]
+        },
+        {
+          "name": "payer",
+          "writable": true,
+          "signer": true
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "rating",
+          "type": "u8"
+        },
+        {
+          "name": "contentHash",
+          "type": {
+            "array": [
+              "u8",
+              32
+            ]
+          }
+        }
+      ]
+    },
+    {
+      "name": "syncPoints",
+      "discriminator": [
+        35,
+        58,
+        58,
+        50,
+        186,
+        1,
+        252,
+        250
+      ],
+      "accounts": [
+        {
+          "name": "marketplace"
+        },
+        {
+          "name": "buyerProfile",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  98,
+                  117,
+                  121,
+                  101,
+                  114
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "marketplace"
+              },
+              {
+                "kind": "account",
+                "path": "buyer"
+              }
+            ]
+          }
+        },
+        {
+          "name": "pointsMint",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "const",
+                "value": [
+                  112,
+                  111,
+                  105,
+                  110,
+                  116,
+                  115,
+                  95,
+                  109,
+                  105,
+                  110,
+                  116
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "marketplace"
+              }
+            ]
+          }
+        },
+        {
+          "name": "pointsAccount",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "buyer"
+              },
+              {
+                "kind": "account",
+                "path": "tokenProgram"
+              },
+              {
+                "kind": "account",
+                "path": "pointsMint"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "buyer",
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "buyerProfile"
+          ]
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb"
+        },
+        {
+          "name": "associatedTokenProgram",
+          "address": "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL"
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": []
+    },
+    {
+      "name": "updateCatalogEntry",
+      "discriminator": [
+        228,
+        253,
+        155,
+        55,
+        42,
+        69,
+        93,
+        82
+      ],
+      "accounts": [
+        {
+          "name": "catalog",
+          "writable": true
+        },
+        {
+          "name": "seller",
+          "signer": true,
+          "relations": [
+            "catalog"
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "index",
+          "type": "u32"
+        },
+        {
+          "name": "newPrice",
+          "type": {
+            "option": "u64"
+          }
+        },
+        {
+          "name": "newQuantity",
+          "type": {
+            "option": "u32"
+          }
+        },
+        {
+          "name": "active",
+          "type": {
+            "option": "bool"
+          }
+        },
+        {
+          "name": "newContentHash",
+          "type": {
+            "option": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          }
+        }
+      ]
+    },
+    {
+      "name": "updateCompressedListing",
+      "discriminator": [
+        210,
+        105,
+        225,
+        144,
+        30,
+        114,
+        45,
+        13
+      ],
+      "accounts": [
+        {
+          "name": "listingTree"
+        },
+        {
+          "name": "merkleTree",
+          "writable": true,
+          "relations": [
+            "listingTree"
+          ]
+        },
+        {
+          "name": "seller",
+          "signer": true,
+          "relations": [
+            "listingTree"
+          ]
+        },
+        {
+          "name": "compressionProgram",
+          "address": "cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK"
+        },
+        {
+          "name": "noopProgram",
+          "address": "noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV"
+        }
+      ],
+      "args": [
+        {
+          "name": "root",
+          "type": {
+            "array": [
+              "u8",
+              32
+            ]
+          }
+        },
+        {
+          "name": "listing",
+          "type": {
+            "defined": {
+              "name": "compressedListing"
+            }
+          }
+        },
+        {
+          "name": "newPrice",
+          "type": {
+            "option": "u64"
+          }
+        },
+        {
+          "name": "newQuantity",
+          "type": {
+            "option": "u32"
+          }
+        },
+        {
+          "name": "active",
+          "type": {
+            "option": "bool"
+          }
+        }
+      ]
+    },
+    {
+      "name": "updateListing",
+      "discriminator": [
+        192,
+        174,
+        210,
+        68,
+        116,
+        40,
+        242,
+        253
+      ],
+      "accounts": [
+        {
+          "name": "listing",
+          "writable": true
+        },
+        {
+          "name": "seller",
+          "signer": true,
+          "relations": [
+            "listing"
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "newPrice",
+          "type": {
+            "option": "u64"
+          }
+        },
+        {
+          "name": "newQuantity",
+          "type": {
+            "option": "u32"
+          }
+        },
+        {
+          "name": "active",
+          "type": {
+            "option": "bool"
+          }
+        },
+        {
+          "name": "newSaleWindow",
+          "type": {
+            "option": {
+              "defined": {
+                "name": "saleWindow"
+              }
+            }
+          }
+        },
+        {
+          "name": "newLimits",
+          "type": {
+            "option": {
+              "defined": {
+                "name": "purchaseLimits"
+              }
+            }
+          }
+        },
+        {
+          "name": "newPriceFeed",
+          "type": {
+            "option": "pubkey"
+          }
+        }
+      ]
+    },
+    {
+      "name": "updateListingMetadata",
+      "discriminator": [
+        122,
+        85,
+        222,
+        173,
+        192,
+        184,
+        127,
+        202
+      ],
+      "accounts": [
+        {
+          "name": "listing",
+          "writable": true
+        },
+        {
+          "name": "seller",
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "listing"
+          ]
+        },
+        {
+          "name": "systemProgram",
+          "address": "11111111111111111111111111111111"
+        }
+      ],
+      "args": [
+        {
+          "name": "name",
+          "type": {
+            "option": "string"
+          }
+        },
+        {
+          "name": "imageUrl",
+          "type": {
+            "option": "string"
+          }
+        },
+        {
+          "name": "metadata",
+          "type": {
+            "option": {
+              "defined": {
+                "name": "listingMetadata"
+              }
+            }
+          }
+        }
+      ]
+    },
+    {
+      "name": "updateMarketplace",
+      "discriminator": [
+        72,
+        12,
+        22,
+        71,
+        86,
+        113,
+        79,
+        167
+      ],
+      "accounts": [
+        {
+          "name": "marketplace",
+          "writable": true
+        },
+        {
+          "name": "authority",
+          "signer": true,
+          "relations": [
+            "marketplace"
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "newFeeBps",
+          "type": {
+            "option": "u16"
+          }
+        },
+        {
+          "name": "newAuthority",
+          "type": {
+            "option": "pubkey"
+          }
+        },
+        {
+          "name": "newReturnWindowSecs",
+          "type": {
+            "option": "i64"
+          }
+        },
+        {
+          "name": "newFeeRefundPolicy",
+          "type": {
+            "option": {
+              "defined": {
+                "name": "feeRefundPolicy"
+              }
+            }
+          }
+        },
+        {
+          "name": "newArbiter",
+          "type": {
+            "option": "pubkey"
+          }
+        },
+        {
+          "name": "newBond",
+          "type": {
+            "option": {
+              "defined": {
+                "name": "bondConfig"
+              }
+            }
+          }
+        },
+        {
+          "name": "newTreasuryAuthority",
+          "type": {
+            "option": "pubkey"
+          }
+        },
+        {
+          "name": "newPointsBps",
+          "type": {
+            "option": "u16"
+          }
+        },
+        {
+          "name": "newOracleProgram",
+          "type": {
+            "option": "pubkey"
+          }
+        }
+      ]
+    },
+    {
+      "name": "updateMarketplaceMetadata",
+      "discriminator": [
+        44,
+        32,
+        102,
+        137,
+        57,
+        52,
+        53,
+        7
+      ],
+      "accounts": [
+        {
+          "name": "marketplace",
+          "writable": true
+        },
+        {
+          "name": "authority",
+          "signer": true,
+          "relations": [
+            "marketplace"
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "metadata",
+          "type": {
+            "defined": {
+              "name": "marketplaceMetadata"
+            }
+          }
+        }
+      ]
+    },
+    {
+      "name": "updateVariant",
+      "discriminator": [
+        151,
+        43,
+        249,
+        63,
+        114,
+        250,
+        82,
+        80
+      ],
+      "accounts": [
+        {
+          "name": "variant",
+          "writable": true
+        },
+        {
+          "name": "listing",
+          "writable": true,
+          "relations": [
+            "variant"
+          ]
+        },
+        {
+          "name": "seller",
+          "signer": true,
+          "relations": [
+            "listing"
+          ]
+        }
+      ],
+      "args": [
+        {
+          "name": "newPrice",
+          "type": {
+            "option": "u64"
+          }
+        },
+        {
+          "name": "newQuantity",
+          "type": {
+            "option": "u32"
+          }
+        },
+        {
+          "name": "active",
+          "type": {
+            "option": "bool"
+          }
+        }
+      ]
+    },
+    {
+      "name": "withdrawBond",
+      "discriminator": [
+        222,
+        199,
+        141,
+        31,
+        188,
+        93,
+        155,
+        40
+      ],
+      "accounts": [
+        {
+          "name": "bond",
+          "writable": true
+        },
+        {
+          "name": "vault",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "bond"
+              },
+              {
+                "kind": "const",
+                "value": [
+                  6,
+                  221,
+                  246,
+                  225,
+                  215,
+                  101,
+                  161,
+                  147,
+                  217,
+                  203,
+                  225,
+                  70,
+                  206,
+                  235,
+                  121,
+                  172,
+                  28,
+                  180,
+                  133,
+                  237,
+                  95,
+                  91,
+                  55,
+                  145,
+                  58,
+                  140,
+                  245,
+                  133,
+                  126,
+                  255,
+                  0,
+                  169
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "bond.mint",
+                "account": "merchantBond"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "owner",
+          "signer": true,
+          "relations": [
+            "bond"
+          ]
+        },
+        {
+          "name": "ownerAta",
+          "writable": true
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        }
+      ],
+      "args": [
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "withdrawFees",
+      "discriminator": [
+        198,
+        212,
+        171,
+        109,
+        144,
+        215,
+        174,
+        89
+      ],
+      "accounts": [
+        {
+          "name": "marketplace",
+          "relations": [
+            "treasury"
+          ]
+        },
+        {
+          "name": "treasury",
+          "writable": true
+        },
+        {
+          "name": "treasuryVault",
+          "writable": true,
+          "pda": {
+            "seeds": [
+              {
+                "kind": "account",
+                "path": "treasury"
+              },
+              {
+                "kind": "const",
+                "value": [
+                  6,
+                  221,
+                  246,
+                  225,
+                  215,
+                  101,
+                  161,
+                  147,
+                  217,
+                  203,
+                  225,
+                  70,
+                  206,
+                  235,
+                  121,
+                  172,
+                  28,
+                  180,
+                  133,
+                  237,
+                  95,
+                  91,
+                  55,
+                  145,
+                  58,
+                  140,
+                  245,
+                  133,
+                  126,
+                  255,
+                  0,
+                  169
+                ]
+              },
+              {
+                "kind": "account",
+                "path": "treasury.mint",
+                "account": "treasury"
+              }
+            ],
+            "program": {
+              "kind": "const",
+              "value": [
+                140,
+                151,
+                37,
+                143,
+                78,
+                36,
+                137,
+                241,
+                187,
+                61,
+                16,
+                41,
+                20,
+                142,
+                13,
+                131,
+                11,
+                90,
+                19,
+                153,
+                218,
+                255,
+                16,
+                132,
+                4,
+                142,
+                123,
+                216,
+                219,
+                233,
+                248,
+                89
+              ]
+            }
+          }
+        },
+        {
+          "name": "treasuryAuthority",
+          "signer": true,
+          "relations": [
+            "marketplace"
+          ]
+        },
+        {
+          "name": "destination",
+          "writable": true
+        },
+        {
+          "name": "tokenProgram",
+          "address": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        }
+      ],
+      "args": [
+        {
+          "name": "amount",
+          "type": "u64"
+        }
+      ]
+    },
+    {
+      "name": "withdrawReturn",
+      "discriminator": [
+        105,
+        224,
+        94,
+        228,
+        142,
+        49,
+        174,
+        32
+      ],
+      "accounts": [
+        {
+          "name": "receipt",
+          "writable": true,
+          "relations": [
+            "returnRequest"
+          ]
+        },
+        {
+          "name": "returnRequest",
+          "writable": true
+        },
+        {
+          "name": "buyer",
+          "writable": true,
+          "signer": true,
+          "relations": [
+            "receipt"
+          ]
+        }
+      ],
+      "args": []
+    }
+  ],
+  "accounts": [
+    {
+      "name": "badgeAward",
+      "discriminator": [
+        93,
+        2,
+        59,
+        238,
+        84,
+        60,
+        10,
+        145
+      ]
+    },
+    {
+      "name": "badgeDefinition",
+      "discriminator": [
+        207,
+        115,
+        83,
+        49,
+        25,
+        203,
+        35,
+        89
+      ]
+    },
+    {
+      "name": "buyerProfile",
+      "discriminator": [
+        69,
+        227,
+        31,
+        30,
+        144,
+        3,
+        90,
+        252
+      ]
+    },
+    {
+      "name": "catalog",
+      "discriminator": [
+        175,
+        32,
+        23,
+        106,
+        77,
+        71,
+        227,
+        200
+      ]
+    },
+    {
+      "name": "escrow",
+      "discriminator": [
+        31,
+        213,
+        123,
+        187,
+        186,
+        22,
+        218,
+        155
+      ]
+    },
+    {
+      "name": "listing",
+      "discriminator": [
+        218,
+        32,
+        50,
+        73,
+        43,
+        134,
+        26,
+        58
+      ]
+    },
+    {
+      "name": "listingTree",
+      "discriminator": [
+        224,
+        136,
+        132,
+        135,
+        37,
+        177,
+        10,
+        207
+      ]
+    },
+    {
+      "name": "marketplace",
+      "discriminator": [
+        70,
+        222,
+        41,
+        62,
+        78,
+        3,
+        32,
+        174
+      ]
+    },
+    {
+      "name": "merchant",
+      "discriminator": [
+        71,
+        235,
+        30,
+        40,
+        231,
+        21,
+        32,
+        64
+      ]
+    },
+    {
+      "name": "merchantBond",
+      "discriminator": [
+        9,
+        89,
+        5,
+        121,
+        153,
+        205,
+        65,
+        181
+      ]
+    },
+    {
+      "name": "purchaseRecord",
+      "discriminator": [
+        239,
+        38,
+        40,
+        199,
+        4,
+        96,
+        209,
+        2
+      ]
+    },
+    {
+      "name": "receipt",
+      "discriminator": [
+        39,
+        154,
+        73,
+        106,
+        80,
+        102,
+        145,
+        153
+      ]
+    },
+    {
+      "name": "reservation",
+      "discriminator": [
+        188,
+        235,
+        0,
+        111,
+        208,
+        253,
+        247,
+        212
+      ]
+    },
+    {
+      "name": "returnRequest",
+      "discriminator": [
+        122,
+        236,
+        203,
+        189,
+        203,
+        56,
+        206,
+        191
+      ]
+    },
+    {
+      "name": "review",
+      "discriminator": [
+        124,
+        63,
+        203,
+        215,
+        226,
+        30,
+        222,
+        15
+      ]
+    },
+    {
+      "name": "sessionKey",
+      "discriminator": [
+        93,
+        186,
+        163,
+        139,
+        160,
+        255,
+        81,
+        112
+      ]
+    },
+    {
+      "name": "treasury",
+      "discriminator": [
+        238,
+        239,
+        123,
+        238,
+        89,
+        1,
+        168,
+        253
+      ]
+    },
+    {
+      "name": "variant",
+      "discriminator": [
+        135,
+        73,
+        120,
+        10,
+        36,
+        51,
+        146,
+        253
+      ]
+    }
+  ],
+  "events": [
+    {
+      "discriminator": [
+        153,
+        87,
+        158,
+        115,
+        220,
+        200,
+        52,
+        1
+      ],
+      "name": "badgeAwarded"
+    },
+    {
+      "discriminator": [
+        59,
+        7,
+        252,
+        195,
+        234,
+        156,
+        42,
+        54
+      ],
+      "name": "bondSlashed"
+    },
+    {
+      "discriminator": [
+        187,
+        125,
+        20,
+        209,
+        107,
+        250,
+        110,
+        153
+      ],
+      "name": "cartCompleted"
+    },
+    {
+      "discriminator": [
+        100,
+        8,
+        150,
+        189,
+        169,
+        86,
+        54,
+        115
+      ],
+      "name": "catalogClosed"
+    },
+    {
+      "discriminator": [
+        2,
+        208,
+        238,
+        89,
+        132,
+        54,
+        10,
+        229
+      ],
+      "name": "catalogCreated"
+    },
+    {
+      "discriminator": [
+        103,
+        123,
+        40,
+        102,
+        19,
+        64,
+        6,
+        99
+      ],
+      "name": "catalogEntryRemoved"
+    },
+    {
+      "discriminator": [
+        251,
+        11,
+        126,
+        247,
+        191,
+        71,
+        180,
+        223
+      ],
+      "name": "catalogEntryUpdated"
+    },
+    {
+      "discriminator": [
+        123,
+        8,
+        63,
+        213,
+        16,
+        19,
+        244,
+        4
+      ],
+      "name": "catalogOrderCompleted"
+    },
+    {
+      "discriminator": [
+        118,
+        129,
+        164,
+        232,
+        135,
+        76,
+        96,
+        129
+      ],
+      "name": "compressedListingUpdated"
+    },
+    {
+      "discriminator": [
+        209,
+        24,
+        174,
+        200,
+        236,
+        90,
+        154,
+        55
+      ],
+      "name": "feesDistributed"
+    },
+    {
+      "discriminator": [
+        234,
+        15,
+        0,
+        119,
+        148,
+        241,
+        40,
+        21
+      ],
+      "name": "feesWithdrawn"
+    },
+    {
+      "discriminator": [
+        86,
+        219,
+        253,
+        196,
+        184,
+        194,
+        176,
+        78
+      ],
+      "name": "listingClosed"
+    },
+    {
+      "discriminator": [
+        94,
+        164,
+        167,
+        255,
+        246,
+        186,
+        12,
+        96
+      ],
+      "name": "listingCreated"
+    },
+    {
+      "discriminator": [
+        161,
+        108,
+        149,
+        211,
+        142,
+        214,
+        95,
+        147
+      ],
+      "name": "listingStockChanged"
+    },
+    {
+      "discriminator": [
+        190,
+        215,
+        199,
+        138,
+        255,
+        248,
+        98,
+        62
+      ],
+      "name": "listingUpdated"
+    },
+    {
+      "discriminator": [
+        22,
+        167,
+        42,
+        34,
+        172,
+        55,
+        155,
+        14
+      ],
+      "name": "marketplaceInitialized"
+    },
+    {
+      "discriminator": [
+        202,
+        61,
+        140,
+        95,
+        139,
+        239,
+        17,
+        83
+      ],
+      "name": "merchantRegistered"
+    },
+    {
+      "discriminator": [
+        17,
+        58,
+        131,
+        180,
+        84,
+        58,
+        244,
+        14
+      ],
+      "name": "merchantStatusChanged"
+    },
+    {
+      "discriminator": [
+        90,
+        77,
+        52,
+        248,
+        56,
+        233,
+        110,
+        197
+      ],
+      "name": "orderCompleted"
+    },
+    {
+      "discriminator": [
+        120,
+        155,
+        10,
+        169,
+        7,
+        98,
+        202,
+        187
+      ],
+      "name": "orderRefunded"
+    },
+    {
+      "discriminator": [
+        241,
+        128,
+        189,
+        4,
+        146,
+        30,
+        110,
+        90
+      ],
+      "name": "pointsUpdated"
+    },
+    {
+      "discriminator": [
+        31,
+        168,
+        135,
+        155,
+        108,
+        40,
+        74,
+        250
+      ],
+      "name": "returnResolved"
+    },
+    {
+      "discriminator": [
+        103,
+        154,
+        181,
+        61,
+        120,
+        249,
+        164,
+        142
+      ],
+      "name": "reviewResponded"
+    },
+    {
+      "discriminator": [
+        249,
+        106,
+        213,
+        117,
+        243,
+        187,
+        214,
+        166
+      ],
+      "name": "reviewSubmitted"
+    },
+    {
+      "discriminator": [
+        144,
+        186,
+        209,
+        141,
+        214,
+        85,
+        56,
+        120
+      ],
+      "name": "serviceOrderCancelled"
+    },
+    {
+      "discriminator": [
+        62,
+        230,
+        63,
+        96,
+        3,
+        143,
+        203,
+        90
+      ],
+      "name": "serviceOrderCreated"
+    },
+    {
+      "discriminator": [
+        16,
+        205,
+        163,
+        82,
+        88,
+        114,
+        217,
+        83
+      ],
+      "name": "serviceOrderReleased"
+    },
+    {
+      "discriminator": [
+        242,
+        227,
+        114,
+        120,
+        164,
+        30,
+        58,
+        114
+      ],
+      "name": "sessionKeyCreated"
+    },
+    {
+      "discriminator": [
+        18,
+        208,
+        143,
+        205,
+        85,
+        72,
+        180,
+        176
+      ],
+      "name": "sessionKeyRevoked"
+    },
+    {
+      "discriminator": [
+        201,
+        204,
+        188,
+        45,
+        38,
+        208,
+        203,
+        108
+      ],
+      "name": "variantAdded"
+    },
+    {
+      "discriminator": [
+        16,
+        27,
+        119,
+        6,
+        72,
+        241,
+        141,
+        62
+      ],
+      "name": "variantRemoved"
+    },
+    {
+      "discriminator": [
+        164,
+        249,
+        215,
+        58,
+        126,
+        219,
+        193,
+        234
+      ],
+      "name": "variantUpdated"
+    }
+  ],
+  "errors": [
+    {
+      "code": 6000,
+      "name": "feeTooHigh",
+      "msg": "Fee too high (max 10%)"
+    },
+    {
+      "code": 6001,
+      "name": "invalidAmount",
+      "msg": "Invalid amount"
+    },
+    {
+      "code": 6002,
+      "name": "invalidQuantity",
+      "msg": "Invalid quantity"
+    },
+    {
+      "code": 6003,
+      "name": "listingInactive",
+      "msg": "Listing is inactive"
+    },
+    {
+      "code": 6004,
+      "name": "wrongMarketplace",
+      "msg": "Wrong marketplace"
+    },
+    {
+      "code": 6005,
+      "name": "mathOverflow",
+      "msg": "Math overflow"
+    },
+    {
+      "code": 6006,
+      "name": "alreadyReleased",
+      "msg": "Already released"
+    },
+    {
+      "code": 6007,
+      "name": "wrongFlowForGoods",
+      "msg": "Use service escrow flow"
+    },
+    {
+      "code": 6008,
+      "name": "wrongFlowForService",
+      "msg": "Use goods buy-now flow"
+    },
+    {
+      "code": 6009,
+      "name": "invalidAccount",
+      "msg": "Invalid Account"
+    },
+    {
+      "code": 6010,
+      "name": "missingReference",
+      "msg": "Missing Solana Pay reference in remaining_accounts"
+    },
+    {
+      "code": 6011,
+      "name": "wrongReference",
+      "msg": "Provided reference does not match remaining_accounts entry"
+    },
+    {
+      "code": 6012,
+      "name": "listingStillActive",
+      "msg": "Listing must be inactive before it can be closed"
+    },
+    {
+      "code": 6013,
+      "name": "openEscrowsRemaining",
+      "msg": "Listing still has open escrows"
+    },
+    {
+      "code": 6014,
+      "name": "invalidCart",
+      "msg": "Cart is empty, too large or does not match remaining_accounts"
+    },
+    {
+      "code": 6015,
+      "name": "unsupportedInCart",
+      "msg": "Listing requires a flow not supported by cart checkout"
+    },
+    {
+      "code": 6016,
+      "name": "wrongPriceFeed",
+      "msg": "Price feed account missing or does not match listing"
+    },
+    {
+      "code": 6017,
+      "name": "invalidPriceFeed",
+      "msg": "Price feed account is malformed or not trading"
+    },
+    {
+      "code": 6018,
+      "name": "stalePrice",
+      "msg": "Price feed is stale"
+    },
+    {
+      "code": 6019,
+      "name": "untrustedPriceFeed",
+      "msg": "Price feed is not owned by the marketplace's oracle program"
+    },
+    {
+      "code": 6020,
+      "name": "priceTooUncertain",
+      "msg": "Price feed confidence interval is too wide"
+    },
+    {
+      "code": 6021,
+      "name": "missingPriceGuard",
+      "msg": "Oracle-priced listings require a price guard"
+    },
+    {
+      "code": 6022,
+      "name": "slippageExceeded",
+      "msg": "Price moved beyond allowed slippage"
+    },
+    {
+      "code": 6023,
+      "name": "listingChanged",
+      "msg": "Listing changed since it was quoted to the buyer"
+    },
+    {
+      "code": 6024,
+      "name": "maxTotalExceeded",
+      "msg": "Order total exceeds buyer's maximum"
+    },
+    {
+      "code": 6025,
+      "name": "invalidReservationTtl",
+      "msg": "Reservation TTL out of range"
+    },
+    {
+      "code": 6026,
+      "name": "reservationExpired",
+      "msg": "Reservation has expired"
+    },
+    {
+      "code": 6027,
+      "name": "reservationNotExpired",
+      "msg": "Reservation has not expired yet"
+    },
+    {
+      "code": 6028,
+      "name": "outstandingReservations",
+      "msg": "Listing still has outstanding reservations"
+    },
+    {
+      "code": 6029,
+      "name": "variantRequired",
+      "msg": "Listing has variants, a variant must be selected"
+    },
+    {
+      "code": 6030,
+      "name": "wrongVariant",
+      "msg": "Variant does not match the listing or requested id"
+    },
+    {
+      "code": 6031,
+      "name": "variantInactive",
+      "msg": "Variant is inactive"
+    },
+    {
+      "code": 6032,
+      "name": "unsupportedForVariants",
+      "msg": "Not supported for listings with variants"
+    },
+    {
+      "code": 6033,
+      "name": "variantsRemaining",
+      "msg": "Listing still has variants"
+    },
+    {
+      "code": 6034,
+      "name": "nameTooLong",
+      "msg": "Listing name is too long"
+    },
+    {
+      "code": 6035,
+      "name": "imageUrlTooLong",
+      "msg": "Image URL is too long"
+    },
+    {
+      "code": 6036,
+      "name": "metadataUriTooLong",
+      "msg": "Metadata URI is too long"
+    },
+    {
+      "code": 6037,
+      "name": "tooManyTags",
+      "msg": "Too many tags"
+    },
+    {
+      "code": 6038,
+      "name": "invalidTag",
+      "msg": "Tags must be non-empty and within the length limit"
+    },
+    {
+      "code": 6039,
+      "name": "tooManyImages",
+      "msg": "Too many images"
+    },
+    {
+      "code": 6040,
+      "name": "invalidName",
+      "msg": "Listing name must be non-empty printable text"
+    },
+    {
+      "code": 6041,
+      "name": "unsupportedUrl",
+      "msg": "URL must use https://, ipfs:// or ar:// and contain no whitespace"
+    },
+    {
+      "code": 6042,
+      "name": "invalidSaleWindow",
+      "msg": "Sale window start must be before its end"
+    },
+    {
+      "code": 6043,
+      "name": "saleNotStarted",
+      "msg": "Sale has not started yet"
+    },
+    {
+      "code": 6044,
+      "name": "saleEnded",
+      "msg": "Sale has ended"
+    },
+    {
+      "code": 6045,
+      "name": "notAllowlisted",
+      "msg": "Wallet is not on the listing allowlist"
+    },
+    {
+      "code": 6046,
+      "name": "walletLimitExceeded",
+      "msg": "Purchase exceeds the per-wallet limit"
+    },
+    {
+      "code": 6047,
+      "name": "purchaseRecordRequired",
+      "msg": "Listing has a per-wallet limit, purchase record account required"
+    },
+    {
+      "code": 6048,
+      "name": "escrowNotReleased",
+      "msg": "Escrow has not been released"
+    },
+    {
+      "code": 6049,
+      "name": "invalidRating",
+      "msg": "Rating must be between 1 and 5"
+    },
+    {
+      "code": 6050,
+      "name": "orderProofRequired",
+      "msg": "Provide exactly one of a released escrow or a receipt"
+    },
+    {
+      "code": 6051,
+      "name": "invalidReceiptStatus",
+      "msg": "Receipt is not in the required state"
+    },
+    {
+      "code": 6052,
+      "name": "returnWindowClosed",
+      "msg": "Return window has closed or returns are disabled"
+    },
+    {
+      "code": 6053,
+      "name": "returnPending",
+      "msg": "Receipt has a pending return request"
+    },
+    {
+      "code": 6054,
+      "name": "bondRequired",
+      "msg": "Unverified merchant must hold an active bond of at least the marketplace minimum"
+    },
+    {
+      "code": 6055,
+      "name": "bondLocked",
+      "msg": "Bond is still in its withdrawal cooldown"
+    },
+    {
+      "code": 6056,
+      "name": "invalidFeeSplits",
+      "msg": "Fee splits must sum to 10000 bps and match the recipients provided"
+    },
+    {
+      "code": 6057,
+      "name": "insufficientPoints",
+      "msg": "Not enough loyalty points"
+    },
+    {
+      "code": 6058,
+      "name": "badgeInactive",
+      "msg": "Badge is not active"
+    },
+    {
+      "code": 6059,
+      "name": "badgeNotEarned",
+      "msg": "User has not reached the badge threshold"
+    },
+    {
+      "code": 6060,
+      "name": "alreadyMigrated",
+      "msg": "Account is not in a legacy layout"
+    },
+    {
+      "code": 6061,
+      "name": "invalidCatalogEntry",
+      "msg": "Catalog entry index out of range"
+    },
+    {
+      "code": 6062,
+      "name": "catalogFull",
+      "msg": "Catalog has no free entry slots, grow it first"
+    },
+    {
+      "code": 6063,
+      "name": "catalogTooLarge",
+      "msg": "Catalog size change exceeds the per-instruction limit"
+    },
+    {
+      "code": 6064,
+      "name": "unauthorizedSigner",
+      "msg": "Signer is neither the buyer nor one of its session keys"
+    },
+    {
+      "code": 6065,
+      "name": "invalidSession",
+      "msg": "Session key is expired or does not match"
+    },
+    {
+      "code": 6066,
+      "name": "sessionLimitExceeded",
+      "msg": "Session key spending cap exceeded"
+    },
+    {
+      "code": 6067,
+      "name": "buyerProfileRequired",
+      "msg": "The buyer's profile is required to reverse loyalty points"
+    },
+    {
+      "code": 6068,
+      "name": "unknownAccountLayout",
+      "msg": "Account size matches no known layout"
+    },
+    {
+      "code": 6069,
+      "name": "redemptionUnsupported",
+      "msg": "Staged points can only be redeemed through buy_now"
+    }
+  ],
+  "types": [
+    {
+      "name": "badgeAward",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "badge",
+            "type": "pubkey"
+          },
+          {
+            "name": "user",
+            "type": "pubkey"
+          },
+          {
+            "name": "awardedAt",
+            "type": "i64"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "badgeAwarded",
+      "type": {
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "badge",
+            "type": "pubkey"
+          },
+          {
+            "name": "id",
+            "type": "u16"
+          },
+          {
+            "name": "user",
+            "type": "pubkey"
+          },
+          {
+            "name": "awardedAt",
+            "type": "i64"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "badgeDefinition",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "id",
+            "type": "u16"
+          },
+          {
+            "name": "name",
+            "type": "string"
+          },
+          {
+            "name": "kind",
+            "type": {
+              "defined": {
+                "name": "badgeKind"
+              }
+            }
+          },
+          {
+            "name": "threshold",
+            "type": "u64"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "mintBump",
+            "type": "u8"
+          },
+          {
+            "name": "active",
+            "type": "bool"
+          },
+          {
+            "name": "awardedCount",
+            "type": "u32"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "badgeKind",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "buyerOrders"
+          },
+          {
+            "name": "buyerServices"
+          },
+          {
+            "name": "merchantSales"
+          },
+          {
+            "name": "merchantVolume"
+          }
+        ]
+      }
+    },
+    {
+      "name": "bondConfig",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "minAmount",
+            "type": "u64"
+          },
+          {
+            "name": "cooldownSecs",
+            "type": "i64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "bondSlashed",
+      "type": {
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "merchant",
+            "type": "pubkey"
+          },
+          {
+            "name": "recipient",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          },
+          {
+            "name": "remaining",
+            "type": "u64"
+          },
+          {
+            "name": "reference",
+            "type": "pubkey"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "buyerProfile",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "points",
+            "type": "u64"
+          },
+          {
+            "name": "pendingDiscount",
+            "type": "u64"
+          },
+          {
+            "name": "lifetimePoints",
+            "type": "u64"
+          },
+          {
+            "name": "minted",
+            "type": "u64"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          },
+          {
+            "name": "ordersCompleted",
+            "type": "u32"
+          },
+          {
+            "name": "servicesCompleted",
+            "type": "u32"
+          }
+        ]
+      }
+    },
+    {
+      "name": "cartCompleted",
+      "type": {
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "itemCount",
+            "type": "u8"
+          },
+          {
+            "name": "totalAmount",
+            "type": "u64"
+          },
+          {
+            "name": "fee",
+            "type": "u64"
+          },
+          {
+            "name": "reference",
+            "type": "pubkey"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "cartItem",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "listing",
+            "type": "pubkey"
+          },
+          {
+            "name": "quantity",
+            "type": "u32"
+          }
+        ]
+      }
+    },
+    {
+      "name": "catalog",
+      "serialization": "bytemuck",
+      "repr": {
+        "kind": "c"
+      },
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "merchant",
+            "type": "pubkey"
+          },
+          {
+            "name": "seller",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "entryCount",
+            "type": "u32"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          },
+          {
+            "name": "version",
+            "type": "u8"
+          },
+          {
+            "name": "baseUriLen",
+            "type": "u8"
+          },
+          {
+            "name": "padding",
+            "type": {
+              "array": [
+                "u8",
+                1
+              ]
+            }
+          },
+          {
+            "name": "baseUri",
+            "type": {
+              "array": [
+                "u8",
+                128
+              ]
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "catalogClosed",
+      "type": {
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "catalog",
+            "type": "pubkey"
+          },
+          {
+            "name": "seller",
+            "type": "pubkey"
+          },
+          {
+            "name": "entryCount",
+            "type": "u32"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "catalogCreated",
+      "type": {
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "catalog",
+            "type": "pubkey"
+          },
+          {
+            "name": "seller",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "capacity",
+            "type": "u32"
+          },
+          {
+            "name": "baseUri",
+            "type": "string"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "catalogEntryRemoved",
+      "type": {
+        "fields": [
+          {
+            "name": "catalog",
+            "type": "pubkey"
+          },
+          {
+            "name": "index",
+            "type": "u32"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "catalogEntryUpdated",
+      "type": {
+        "fields": [
+          {
+            "name": "catalog",
+            "type": "pubkey"
+          },
+          {
+            "name": "index",
+            "type": "u32"
+          },
+          {
+            "name": "price",
+            "type": "u64"
+          },
+          {
+            "name": "quantity",
+            "type": "u32"
+          },
+          {
+            "name": "active",
+            "type": "bool"
+          },
+          {
+            "name": "revision",
+            "type": "u32"
+          },
+          {
+            "name": "contentHash",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "catalogOrderCompleted",
+      "type": {
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "catalog",
+            "type": "pubkey"
+          },
+          {
+            "name": "index",
+            "type": "u32"
+          },
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "seller",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "quantity",
+            "type": "u32"
+          },
+          {
+            "name": "totalAmount",
+            "type": "u64"
+          },
+          {
+            "name": "reference",
+            "type": "pubkey"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "compressedListing",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "listingTree",
+            "type": "pubkey"
+          },
+          {
+            "name": "seller",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "index",
+            "type": "u32"
+          },
+          {
+            "name": "price",
+            "type": "u64"
+          },
+          {
+            "name": "quantity",
+            "type": "u32"
+          },
+          {
+            "name": "active",
+            "type": "bool"
+          },
+          {
+            "name": "metadataHash",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "compressedListingUpdated",
+      "type": {
+        "fields": [
+          {
+            "name": "listing",
+            "type": {
+              "defined": {
+                "name": "compressedListing"
+              }
+            }
+          },
+          {
+            "name": "leaf",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "escrow",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "listing",
+            "type": "pubkey"
+          },
+          {
+            "name": "seller",
+            "type": "pubkey"
+          },
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          },
+          {
+            "name": "reference",
+            "type": "pubkey"
+          },
+          {
+            "name": "released",
+            "type": "bool"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          },
+          {
+            "name": "version",
+            "type": "u8"
+          },
+          {
+            "name": "rentPayer",
+            "type": "pubkey"
+          },
+          {
+            "name": "fundingAccount",
+            "type": "pubkey"
+          },
+          {
+            "name": "padding",
+            "type": {
+              "array": [
+                "u8",
+                64
+              ]
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "feeRefundPolicy",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "retainFee"
+          },
+          {
+            "name": "sellerCoversFee"
+          }
+        ]
+      }
+    },
+    {
+      "name": "feeSplit",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "recipient",
+            "type": "pubkey"
+          },
+          {
+            "name": "bps",
+            "type": "u16"
+          }
+        ]
+      }
+    },
+    {
+      "name": "feesDistributed",
+      "type": {
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "feesWithdrawn",
+      "type": {
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "destination",
+            "type": "pubkey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "listing",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "seller",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "price",
+            "type": "u64"
+          },
+          {
+            "name": "quantity",
+            "type": "u32"
+          },
+          {
+            "name": "isService",
+            "type": "bool"
+          },
+          {
+            "name": "active",
+            "type": "bool"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          },
+          {
+            "name": "name",
+            "type": "string"
+          },
+          {
+            "name": "imageUrl",
+            "type": "string"
+          },
+          {
+            "name": "nonce",
+            "type": "u64"
+          },
+          {
+            "name": "openEscrows",
+            "type": "u32"
+          },
+          {
+            "name": "priceFeed",
+            "type": {
+              "option": "pubkey"
+            }
+          },
+          {
+            "name": "revision",
+            "type": "u32"
+          },
+          {
+            "name": "reserved",
+            "type": "u32"
+          },
+          {
+            "name": "variantCount",
+            "type": "u16"
+          },
+          {
+            "name": "metadata",
+            "type": {
+              "defined": {
+                "name": "listingMetadata"
+              }
+            }
+          },
+          {
+            "name": "saleWindow",
+            "type": {
+              "defined": {
+                "name": "saleWindow"
+              }
+            }
+          },
+          {
+            "name": "limits",
+            "type": {
+              "defined": {
+                "name": "purchaseLimits"
+              }
+            }
+          },
+          {
+            "name": "ratingSum",
+            "type": "u64"
+          },
+          {
+            "name": "ratingCount",
+            "type": "u32"
+          },
+          {
+            "name": "version",
+            "type": "u8"
+          },
+          {
+            "name": "padding",
+            "type": {
+              "array": [
+                "u8",
+                64
+              ]
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "listingClosed",
+      "type": {
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "listing",
+            "type": "pubkey"
+          },
+          {
+            "name": "seller",
+            "type": "pubkey"
+          },
+          {
+            "name": "revision",
+            "type": "u32"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "listingCreated",
+      "type": {
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "listing",
+            "type": "pubkey"
+          },
+          {
+            "name": "seller",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "nonce",
+            "type": "u64"
+          },
+          {
+            "name": "price",
+            "type": "u64"
+          },
+          {
+            "name": "quantity",
+            "type": "u32"
+          },
+          {
+            "name": "isService",
+            "type": "bool"
+          },
+          {
+            "name": "name",
+            "type": "string"
+          },
+          {
+            "name": "imageUrl",
+            "type": "string"
+          },
+          {
+            "name": "priceFeed",
+            "type": {
+              "option": "pubkey"
+            }
+          },
+          {
+            "name": "metadata",
+            "type": {
+              "defined": {
+                "name": "listingMetadata"
+              }
+            }
+          },
+          {
+            "name": "saleWindow",
+            "type": {
+              "defined": {
+                "name": "saleWindow"
+              }
+            }
+          },
+          {
+            "name": "limits",
+            "type": {
+              "defined": {
+                "name": "purchaseLimits"
+              }
+            }
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "listingMetadata",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "category",
+            "type": "u16"
+          },
+          {
+            "name": "tags",
+            "type": {
+              "vec": "string"
+            }
+          },
+          {
+            "name": "uri",
+            "type": "string"
+          },
+          {
+            "name": "contentHash",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          },
+          {
+            "name": "images",
+            "type": {
+              "vec": "string"
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "listingStockChanged",
+      "type": {
+        "fields": [
+          {
+            "name": "listing",
+            "type": "pubkey"
+          },
+          {
+            "name": "variant",
+            "type": {
+              "option": "u16"
+            }
+          },
+          {
+            "name": "oldQuantity",
+            "type": "u32"
+          },
+          {
+            "name": "newQuantity",
+            "type": "u32"
+          },
+          {
+            "name": "oldActive",
+            "type": "bool"
+          },
+          {
+            "name": "newActive",
+            "type": "bool"
+          },
+          {
+            "name": "reserved",
+            "type": "u32"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "listingTree",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "merchant",
+            "type": "pubkey"
+          },
+          {
+            "name": "seller",
+            "type": "pubkey"
+          },
+          {
+            "name": "merkleTree",
+            "type": "pubkey"
+          },
+          {
+            "name": "leafCount",
+            "type": "u64"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "listingUpdated",
+      "type": {
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "listing",
+            "type": "pubkey"
+          },
+          {
+            "name": "seller",
+            "type": "pubkey"
+          },
+          {
+            "name": "revision",
+            "type": "u32"
+          },
+          {
+            "name": "oldPrice",
+            "type": "u64"
+          },
+          {
+            "name": "newPrice",
+            "type": "u64"
+          },
+          {
+            "name": "oldQuantity",
+            "type": "u32"
+          },
+          {
+            "name": "newQuantity",
+            "type": "u32"
+          },
+          {
+            "name": "oldActive",
+            "type": "bool"
+          },
+          {
+            "name": "newActive",
+            "type": "bool"
+          },
+          {
+            "name": "oldPriceFeed",
+            "type": {
+              "option": "pubkey"
+            }
+          },
+          {
+            "name": "newPriceFeed",
+            "type": {
+              "option": "pubkey"
+            }
+          },
+          {
+            "name": "oldName",
+            "type": "string"
+          },
+          {
+            "name": "newName",
+            "type": "string"
+          },
+          {
+            "name": "oldImageUrl",
+            "type": "string"
+          },
+          {
+            "name": "newImageUrl",
+            "type": "string"
+          },
+          {
+            "name": "oldSaleWindow",
+            "type": {
+              "defined": {
+                "name": "saleWindow"
+              }
+            }
+          },
+          {
+            "name": "newSaleWindow",
+            "type": {
+              "defined": {
+                "name": "saleWindow"
+              }
+            }
+          },
+          {
+            "name": "oldLimits",
+            "type": {
+              "defined": {
+                "name": "purchaseLimits"
+              }
+            }
+          },
+          {
+            "name": "newLimits",
+            "type": {
+              "defined": {
+                "name": "purchaseLimits"
+              }
+            }
+          },
+          {
+            "name": "oldMetadata",
+            "type": {
+              "option": {
+                "defined": {
+                  "name": "listingMetadata"
+                }
+              }
+            }
+          },
+          {
+            "name": "newMetadata",
+            "type": {
+              "option": {
+                "defined": {
+                  "name": "listingMetadata"
+                }
+              }
+            }
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "marketplace",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "id",
+            "type": "u16"
+          },
+          {
+            "name": "metadata",
+            "type": {
+              "defined": {
+                "name": "marketplaceMetadata"
+              }
+            }
+          },
+          {
+            "name": "authority",
+            "type": "pubkey"
+          },
+          {
+            "name": "feeBps",
+            "type": "u16"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          },
+          {
+            "name": "returnWindowSecs",
+            "type": "i64"
+          },
+          {
+            "name": "feeRefundPolicy",
+            "type": {
+              "defined": {
+                "name": "feeRefundPolicy"
+              }
+            }
+          },
+          {
+            "name": "arbiter",
+            "type": "pubkey"
+          },
+          {
+            "name": "bond",
+            "type": {
+              "defined": {
+                "name": "bondConfig"
+              }
+            }
+          },
+          {
+            "name": "treasuryAuthority",
+            "type": "pubkey"
+          },
+          {
+            "name": "merchantCount",
+            "type": "u32"
+          },
+          {
+            "name": "orderCount",
+            "type": "u64"
+          },
+          {
+            "name": "openEscrowCount",
+            "type": "u32"
+          },
+          {
+            "name": "pointsBps",
+            "type": "u16"
+          },
+          {
+            "name": "version",
+            "type": "u8"
+          },
+          {
+            "name": "oracleProgram",
+            "type": "pubkey"
+          },
+          {
+            "name": "padding",
+            "type": {
+              "array": [
+                "u8",
+                64
+              ]
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "marketplaceInitialized",
+      "type": {
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "authority",
+            "type": "pubkey"
+          },
+          {
+            "name": "id",
+            "type": "u16"
+          },
+          {
+            "name": "feeBps",
+            "type": "u16"
+          },
+          {
+            "name": "name",
+            "type": "string"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "marketplaceMetadata",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "name",
+            "type": "string"
+          },
+          {
+            "name": "uri",
+            "type": "string"
+          },
+          {
+            "name": "defaultMint",
+            "type": "pubkey"
+          }
+        ]
+      }
+    },
+    {
+      "name": "merchant",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "owner",
+            "type": "pubkey"
+          },
+          {
+            "name": "verified",
+            "type": "bool"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          },
+          {
+            "name": "nextNonce",
+            "type": "u64"
+          },
+          {
+            "name": "ratingSum",
+            "type": "u64"
+          },
+          {
+            "name": "ratingCount",
+            "type": "u32"
+          },
+          {
+            "name": "salesCount",
+            "type": "u64"
+          },
+          {
+            "name": "salesVolume",
+            "type": "u64"
+          },
+          {
+            "name": "version",
+            "type": "u8"
+          },
+          {
+            "name": "padding",
+            "type": {
+              "array": [
+                "u8",
+                64
+              ]
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "merchantBond",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "merchant",
+            "type": "pubkey"
+          },
+          {
+            "name": "owner",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          },
+          {
+            "name": "unlockAt",
+            "type": {
+              "option": "i64"
+            }
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "merchantRegistered",
+      "type": {
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "merchant",
+            "type": "pubkey"
+          },
+          {
+            "name": "owner",
+            "type": "pubkey"
+          },
+          {
+            "name": "source",
+            "type": {
+              "option": "pubkey"
+            }
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "merchantStatusChanged",
+      "type": {
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "merchant",
+            "type": "pubkey"
+          },
+          {
+            "name": "owner",
+            "type": "pubkey"
+          },
+          {
+            "name": "wasVerified",
+            "type": "bool"
+          },
+          {
+            "name": "verified",
+            "type": "bool"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "orderCompleted",
+      "type": {
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "listing",
+            "type": "pubkey"
+          },
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "payer",
+            "type": "pubkey"
+          },
+          {
+            "name": "seller",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "quantity",
+            "type": "u32"
+          },
+          {
+            "name": "totalAmount",
+            "type": "u64"
+          },
+          {
+            "name": "reference",
+            "type": "pubkey"
+          },
+          {
+            "name": "quote",
+            "type": {
+              "option": {
+                "defined": {
+                  "name": "priceQuote"
+                }
+              }
+            }
+          },
+          {
+            "name": "variant",
+            "type": {
+              "option": "u16"
+            }
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "orderRefunded",
+      "type": {
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "listing",
+            "type": "pubkey"
+          },
+          {
+            "name": "receipt",
+            "type": "pubkey"
+          },
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "seller",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          },
+          {
+            "name": "feeRetained",
+            "type": "u64"
+          },
+          {
+            "name": "restocked",
+            "type": "u32"
+          },
+          {
+            "name": "pointsClawedBack",
+            "type": "u64"
+          },
+          {
+            "name": "pointsRestored",
+            "type": "u64"
+          },
+          {
+            "name": "reference",
+            "type": "pubkey"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "pointsUpdated",
+      "type": {
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "earned",
+            "type": "u64"
+          },
+          {
+            "name": "redeemed",
+            "type": "u64"
+          },
+          {
+            "name": "balance",
+            "type": "u64"
+          },
+          {
+            "name": "reference",
+            "type": "pubkey"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "priceGuard",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "expectedRate",
+            "type": "i64"
+          },
+          {
+            "name": "maxSlippageBps",
+            "type": "u16"
+          }
+        ]
+      }
+    },
+    {
+      "name": "priceQuote",
+      "type": {
+        "fields": [
+          {
+            "name": "quotePrice",
+            "type": "u64"
+          },
+          {
+            "name": "rate",
+            "type": "i64"
+          },
+          {
+            "name": "expo",
+            "type": "i32"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "purchaseLimits",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "maxPerWallet",
+            "type": {
+              "option": "u32"
+            }
+          },
+          {
+            "name": "allowlistRoot",
+            "type": {
+              "option": {
+                "array": [
+                  "u8",
+                  32
+                ]
+              }
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "purchaseRecord",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "listing",
+            "type": "pubkey"
+          },
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "purchased",
+            "type": "u32"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "receipt",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "listing",
+            "type": "pubkey"
+          },
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "seller",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "quantity",
+            "type": "u32"
+          },
+          {
+            "name": "variant",
+            "type": {
+              "option": "u16"
+            }
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          },
+          {
+            "name": "fee",
+            "type": "u64"
+          },
+          {
+            "name": "pointsRedeemed",
+            "type": "u64"
+          },
+          {
+            "name": "pointsEarned",
+            "type": "u64"
+          },
+          {
+            "name": "timestamp",
+            "type": "i64"
+          },
+          {
+            "name": "reference",
+            "type": "pubkey"
+          },
+          {
+            "name": "status",
+            "type": {
+              "defined": {
+                "name": "receiptStatus"
+              }
+            }
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          },
+          {
+            "name": "rentPayer",
+            "type": "pubkey"
+          },
+          {
+            "name": "fundingAccount",
+            "type": "pubkey"
+          },
+          {
+            "name": "isService",
+            "type": "bool"
+          }
+        ]
+      }
+    },
+    {
+      "name": "receiptStatus",
+      "type": {
+        "kind": "enum",
+        "variants": [
+          {
+            "name": "completed"
+          },
+          {
+            "name": "returnRequested"
+          },
+          {
+            "name": "refunded"
+          },
+          {
+            "name": "returnRejected"
+          }
+        ]
+      }
+    },
+    {
+      "name": "reservation",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "listing",
+            "type": "pubkey"
+          },
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "quantity",
+            "type": "u32"
+          },
+          {
+            "name": "expiresAt",
+            "type": "i64"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          },
+          {
+            "name": "rentPayer",
+            "type": "pubkey"
+          }
+        ]
+      }
+    },
+    {
+      "name": "returnRequest",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "receipt",
+            "type": "pubkey"
+          },
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "seller",
+            "type": "pubkey"
+          },
+          {
+            "name": "reasonHash",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          },
+          {
+            "name": "requestedAt",
+            "type": "i64"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "returnResolved",
+      "type": {
+        "fields": [
+          {
+            "name": "receipt",
+            "type": "pubkey"
+          },
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "seller",
+            "type": "pubkey"
+          },
+          {
+            "name": "rejected",
+            "type": "bool"
+          },
+          {
+            "name": "reference",
+            "type": "pubkey"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "review",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "listing",
+            "type": "pubkey"
+          },
+          {
+            "name": "seller",
+            "type": "pubkey"
+          },
+          {
+            "name": "reviewer",
+            "type": "pubkey"
+          },
+          {
+            "name": "reference",
+            "type": "pubkey"
+          },
+          {
+            "name": "rating",
+            "type": "u8"
+          },
+          {
+            "name": "contentHash",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          },
+          {
+            "name": "responseHash",
+            "type": {
+              "option": {
+                "array": [
+                  "u8",
+                  32
+                ]
+              }
+            }
+          },
+          {
+            "name": "createdAt",
+            "type": "i64"
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          }
+        ]
+      }
+    },
+    {
+      "name": "reviewResponded",
+      "type": {
+        "fields": [
+          {
+            "name": "review",
+            "type": "pubkey"
+          },
+          {
+            "name": "seller",
+            "type": "pubkey"
+          },
+          {
+            "name": "responseHash",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "reviewSubmitted",
+      "type": {
+        "fields": [
+          {
+            "name": "review",
+            "type": "pubkey"
+          },
+          {
+            "name": "listing",
+            "type": "pubkey"
+          },
+          {
+            "name": "seller",
+            "type": "pubkey"
+          },
+          {
+            "name": "reviewer",
+            "type": "pubkey"
+          },
+          {
+            "name": "reference",
+            "type": "pubkey"
+          },
+          {
+            "name": "rating",
+            "type": "u8"
+          },
+          {
+            "name": "contentHash",
+            "type": {
+              "array": [
+                "u8",
+                32
+              ]
+            }
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "saleWindow",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "saleStart",
+            "type": {
+              "option": "i64"
+            }
+          },
+          {
+            "name": "saleEnd",
+            "type": {
+              "option": "i64"
+            }
+          }
+        ]
+      }
+    },
+    {
+      "name": "serviceOrderCancelled",
+      "type": {
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "escrow",
+            "type": "pubkey"
+          },
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "refundAccount",
+            "type": "pubkey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          },
+          {
+            "name": "reference",
+            "type": "pubkey"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
     {
-      "name": "marketplace",
+      "name": "serviceOrderCreated",
+      "type": {
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "listing",
+            "type": "pubkey"
+          },
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "payer",
+            "type": "pubkey"
+          },
+          {
+            "name": "seller",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          },
+          {
+            "name": "reference",
+            "type": "pubkey"
+          },
+          {
+            "name": "escrow",
+            "type": "pubkey"
+          },
+          {
+            "name": "quote",
+            "type": {
+              "option": {
+                "defined": {
+                  "name": "priceQuote"
+                }
+              }
+            }
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "serviceOrderReleased",
+      "type": {
+        "fields": [
+          {
+            "name": "marketplace",
+            "type": "pubkey"
+          },
+          {
+            "name": "escrow",
+            "type": "pubkey"
+          },
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "seller",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "amount",
+            "type": "u64"
+          },
+          {
+            "name": "reference",
+            "type": "pubkey"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "sessionKey",
       "type": {
         "kind": "struct",
         "fields": [
           {
-            "name": "authority",
+            "name": "buyer",
             "type": "pubkey"
           },
           {
-            "name": "feeBps",
-            "type": "u16"
+            "name": "signer",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "spendLimit",
+            "type": "u64"
+          },
+          {
+            "name": "spent",
+            "type": "u64"
+          },
+          {
+            "name": "expiresAt",
+            "type": "i64"
+          },
+          {
+            "name": "rentPayer",
+            "type": "pubkey"
           },
           {
             "name": "bump",
@@ -51,7 +8705,55 @@ export type Konnect = {
       }
     },
     {
-      "name": "listing",
+      "name": "sessionKeyCreated",
+      "type": {
+        "fields": [
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "signer",
+            "type": "pubkey"
+          },
+          {
+            "name": "mint",
+            "type": "pubkey"
+          },
+          {
+            "name": "spendLimit",
+            "type": "u64"
+          },
+          {
+            "name": "expiresAt",
+            "type": "i64"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "sessionKeyRevoked",
+      "type": {
+        "fields": [
+          {
+            "name": "buyer",
+            "type": "pubkey"
+          },
+          {
+            "name": "signer",
+            "type": "pubkey"
+          },
+          {
+            "name": "spent",
+            "type": "u64"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "treasury",
       "type": {
         "kind": "struct",
         "fields": [
@@ -60,13 +8762,59 @@ export type Konnect = {
             "type": "pubkey"
           },
           {
-            "name": "seller",
+            "name": "mint",
             "type": "pubkey"
           },
           {
-            "name": "mint",
+            "name": "totalCollected",
+            "type": "u64"
+          },
+          {
+            "name": "totalWithdrawn",
+            "type": "u64"
+          },
+          {
+            "name": "totalDistributed",
+            "type": "u64"
+          },
+          {
+            "name": "splits",
+            "type": {
+              "vec": {
+                "defined": {
+                  "name": "feeSplit"
+                }
+              }
+            }
+          },
+          {
+            "name": "bump",
+            "type": "u8"
+          },
+          {
+            "name": "volume",
+            "type": "u64"
+          },
+          {
+            "name": "openEscrowAmount",
+            "type": "u64"
+          }
+        ]
+      }
+    },
+    {
+      "name": "variant",
+      "type": {
+        "kind": "struct",
+        "fields": [
+          {
+            "name": "listing",
             "type": "pubkey"
           },
+          {
+            "name": "id",
+            "type": "u16"
+          },
           {
             "name": "price",
             "type": "u64"
@@ -75,10 +8823,6 @@ export type Konnect = {
             "name": "quantity",
             "type": "u32"
           },
-          {
-            "name": "isService",
-            "type": "bool"
-          },
           {
             "name": "active",
             "type": "bool"
@@ -89,15 +8833,126 @@ export type Konnect = {
           }
         ]
       }
+    },
+    {
+      "name": "variantAdded",
+      "type": {
+        "fields": [
+          {
+            "name": "listing",
+            "type": "pubkey"
+          },
+          {
+            "name": "variant",
+            "type": "pubkey"
+          },
+          {
+            "name": "id",
+            "type": "u16"
+          },
+          {
+            "name": "price",
+            "type": "u64"
+          },
+          {
+            "name": "quantity",
+            "type": "u32"
+          },
+          {
+            "name": "listingRevision",
+            "type": "u32"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "variantRemoved",
+      "type": {
+        "fields": [
+          {
+            "name": "listing",
+            "type": "pubkey"
+          },
+          {
+            "name": "variant",
+            "type": "pubkey"
+          },
+          {
+            "name": "id",
+            "type": "u16"
+          },
+          {
+            "name": "oldPrice",
+            "type": "u64"
+          },
+          {
+            "name": "oldQuantity",
+            "type": "u32"
+          },
+          {
+            "name": "oldActive",
+            "type": "bool"
+          },
+          {
+            "name": "listingRevision",
+            "type": "u32"
+          }
+        ],
+        "kind": "struct"
+      }
+    },
+    {
+      "name": "variantUpdated",
+      "type": {
+        "fields": [
+          {
+            "name": "listing",
+            "type": "pubkey"
+          },
+          {
+            "name": "variant",
+            "type": "pubkey"
+          },
+          {
+            "name": "id",
+            "type": "u16"
+          },
+          {
+            "name": "oldPrice",
+            "type": "u64"
+          },
+          {
+            "name": "newPrice",
+            "type": "u64"
+          },
+          {
+            "name": "oldQuantity",
+            "type": "u32"
+          },
+          {
+            "name": "newQuantity",
+            "type": "u32"
+          },
+          {
+            "name": "oldActive",
+            "type": "bool"
+          },
+          {
+            "name": "newActive",
+            "type": "bool"
+          },
+          {
+            "name": "listingRevision",
+            "type": "u32"
+          }
+        ],
+        "kind": "struct"
+      }
     }
   ]
-}
-
-export const IDL: Konnect = {
-  "version": "0.1.0",
-  "name": "konnect",
-  "instructions": [],
-  "accounts": []
-}
+};
 
+import idl from './konnect.json'
 
+export const IDL = idl as unknown as Konnect
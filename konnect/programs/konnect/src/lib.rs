@@ -1,26 +1,112 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::solana_program::program_option::COption;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{self, CloseAccount, Mint, Token, TokenAccount, Transfer};
+// anchor_lang 0.32 stopped re-exporting solana_program::hash, anchor_spl still carries it
+use anchor_spl::associated_token::spl_associated_token_account::solana_program::hash::hashv;
+use anchor_spl::token::{self, Approve, CloseAccount, Mint, Revoke, Token, TokenAccount, Transfer};
+use anchor_spl::token_2022::spl_token_2022::extension::ExtensionType;
+use anchor_spl::token_2022::{self, Token2022};
+use anchor_spl::token_interface;
 
 declare_id!("mbLjS3jLDX74Ptza9EiiG4qcPPE9aPS7EzifCLZc5hJ");
 
+pub const MAX_CART_ITEMS: usize = 10;
+// oracle-priced listings store `price` in quote units with this many decimals (e.g. micro-USD)
+pub const QUOTE_DECIMALS: u32 = 6;
+pub const MAX_PRICE_AGE_SECS: i64 = 60;
+// widest confidence interval accepted, relative to the price
+pub const MAX_PRICE_CONF_BPS: u64 = 200;
+pub const MAX_RESERVATION_TTL_SECS: i64 = 15 * 60;
+pub const DEFAULT_BOND_COOLDOWN_SECS: i64 = 7 * 24 * 60 * 60;
+pub const MAX_FEE_SPLITS: usize = 5;
+// merchant volume and loyalty points add up across mints in units with this many decimals, so a
+// marketplace is expected to accept mints of similar value (e.g. USD stablecoins)
+pub const NORMALIZED_DECIMALS: u8 = 6;
+
+// listing text limits (bytes), Listing::SIZE reserves room for the two fixed strings
+pub const MAX_NAME_LEN: usize = 100;
+pub const MAX_IMAGE_URL_LEN: usize = 200;
+pub const MAX_METADATA_URI_LEN: usize = 200;
+pub const MAX_TAGS: usize = 8;
+pub const MAX_TAG_LEN: usize = 32;
+pub const MAX_EXTRA_IMAGES: usize = 5;
+pub const MAX_BADGE_NAME_LEN: usize = 32;
+pub const MAX_MARKETPLACE_NAME_LEN: usize = 64;
+
+// layout version written to Marketplace, Merchant, Listing and Escrow; accounts created before
+// versioning are version 0 and are upgraded in place by the migrate_* instructions. Accounts
+// that have grown since carry their own VERSION
+pub const ACCOUNT_VERSION: u8 = 1;
+// spare bytes at the end of versioned accounts so new fields don't require a realloc
+pub const ACCOUNT_PADDING_LEN: usize = 64;
+pub const MAX_CATALOG_URI_LEN: usize = 128;
+pub const MAX_SESSION_SECS: i64 = 7 * 24 * 60 * 60;
+
+// SPL account compression (concurrent merkle trees) and the noop program it logs through
+pub const ACCOUNT_COMPRESSION_PROGRAM_ID: Pubkey =
+    pubkey!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+pub const NOOP_PROGRAM_ID: Pubkey = pubkey!("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+
 #[program]
 pub mod konnect {
     use super::*;
 
-    pub fn init_marketplace(ctx: Context<InitMarketplace>, fee_bps: u16) -> Result<()> {
+    // one authority can run several marketplaces (e.g. regional storefronts), told apart by id
+    pub fn init_marketplace(
+        ctx: Context<InitMarketplace>,
+        id: u16,
+        fee_bps: u16,
+        metadata: MarketplaceMetadata,
+    ) -> Result<()> {
         require!(fee_bps <= 1_000, MarketplaceError::FeeTooHigh); // max 10%
+        metadata.validate()?;
         let mp = &mut ctx.accounts.marketplace;
+        mp.id = id;
+        mp.metadata = metadata;
         mp.authority = ctx.accounts.authority.key();
         mp.fee_bps = fee_bps;
         mp.bump = ctx.bumps.marketplace;
+        mp.version = Marketplace::VERSION;
+        mp.return_window_secs = 0;
+        mp.fee_refund_policy = FeeRefundPolicy::RetainFee;
+        mp.arbiter = mp.authority;
+        mp.treasury_authority = mp.authority;
+        mp.merchant_count = 0;
+        mp.order_count = 0;
+        mp.open_escrow_count = 0;
+        mp.points_bps = 0;
+        mp.oracle_program = Pubkey::default();
+        mp.bond = BondConfig {
+            mint: Pubkey::default(),
+            min_amount: 0,
+            cooldown_secs: DEFAULT_BOND_COOLDOWN_SECS,
+        };
+
+        emit!(MarketplaceInitialized {
+            marketplace: mp.key(),
+            authority: mp.authority,
+            id,
+            fee_bps,
+            name: mp.metadata.name.clone(),
+        });
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn update_marketplace(
         ctx: Context<UpdateMarketplace>,
         new_fee_bps: Option<u16>,
         new_authority: Option<Pubkey>,
+        new_return_window_secs: Option<i64>,
+        new_fee_refund_policy: Option<FeeRefundPolicy>,
+        new_arbiter: Option<Pubkey>,
+        new_bond: Option<BondConfig>,
+        new_treasury_authority: Option<Pubkey>,
+        new_points_bps: Option<u16>,
+        new_oracle_program: Option<Pubkey>,
     ) -> Result<()> {
         if let Some(bps) = new_fee_bps {
             require!(bps <= 1_000, MarketplaceError::FeeTooHigh);
@@ -29,6 +115,155 @@ pub mod konnect {
         if let Some(auth) = new_authority {
             ctx.accounts.marketplace.authority = auth;
         }
+        if let Some(window) = new_return_window_secs {
+            require!(window >= 0, MarketplaceError::InvalidAmount);
+            ctx.accounts.marketplace.return_window_secs = window;
+        }
+        if let Some(policy) = new_fee_refund_policy {
+            ctx.accounts.marketplace.fee_refund_policy = policy;
+        }
+        if let Some(arbiter) = new_arbiter {
+            ctx.accounts.marketplace.arbiter = arbiter;
+        }
+        if let Some(bond) = new_bond {
+            require!(bond.cooldown_secs >= 0, MarketplaceError::InvalidAmount);
+            ctx.accounts.marketplace.bond = bond;
+        }
+        if let Some(treasury_authority) = new_treasury_authority {
+            ctx.accounts.marketplace.treasury_authority = treasury_authority;
+        }
+        if let Some(bps) = new_points_bps {
+            require!(bps <= 10_000, MarketplaceError::InvalidAmount);
+            ctx.accounts.marketplace.points_bps = bps;
+        }
+        if let Some(oracle_program) = new_oracle_program {
+            ctx.accounts.marketplace.oracle_program = oracle_program;
+        }
+        Ok(())
+    }
+
+    pub fn update_marketplace_metadata(
+        ctx: Context<UpdateMarketplace>,
+        metadata: MarketplaceMetadata,
+    ) -> Result<()> {
+        metadata.validate()?;
+        ctx.accounts.marketplace.metadata = metadata;
+        Ok(())
+    }
+
+    // Treasury (program-owned fee vault per mint)
+    pub fn init_treasury(ctx: Context<InitTreasury>) -> Result<()> {
+        let t = &mut ctx.accounts.treasury;
+        t.marketplace = ctx.accounts.marketplace.key();
+        t.mint = ctx.accounts.mint.key();
+        t.total_collected = 0;
+        t.total_withdrawn = 0;
+        t.total_distributed = 0;
+        t.splits = Vec::new();
+        t.bump = ctx.bumps.treasury;
+        t.volume = 0;
+        t.open_escrow_amount = 0;
+        Ok(())
+    }
+
+    pub fn set_fee_splits(ctx: Context<ManageTreasury>, splits: Vec<FeeSplit>) -> Result<()> {
+        FeeSplit::validate(&splits)?;
+        ctx.accounts.treasury.splits = splits;
+        Ok(())
+    }
+
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+        require!(
+            amount > 0 && amount <= ctx.accounts.treasury_vault.amount,
+            MarketplaceError::InvalidAmount
+        );
+
+        let t = &ctx.accounts.treasury;
+        let seeds: &[&[u8]] = &[b"treasury", t.marketplace.as_ref(), t.mint.as_ref(), &[t.bump]];
+        let signer = &[seeds];
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.treasury_vault.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: ctx.accounts.treasury.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+        )?;
+
+        let t = &mut ctx.accounts.treasury;
+        t.total_withdrawn = t
+            .total_withdrawn
+            .checked_add(amount)
+            .ok_or(MarketplaceError::MathOverflow)?;
+
+        emit!(FeesWithdrawn {
+            marketplace: t.marketplace,
+            mint: t.mint,
+            destination: ctx.accounts.destination.key(),
+            amount,
+        });
+        Ok(())
+    }
+
+    // permissionless crank: pays the vault balance out to the configured splits,
+    // remaining_accounts = recipient token accounts in split order
+    pub fn distribute_fees<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DistributeFees<'info>>,
+    ) -> Result<()> {
+        let t = &ctx.accounts.treasury;
+        require!(!t.splits.is_empty(), MarketplaceError::InvalidFeeSplits);
+        require!(
+            ctx.remaining_accounts.len() == t.splits.len(),
+            MarketplaceError::InvalidFeeSplits
+        );
+
+        let balance = ctx.accounts.treasury_vault.amount;
+        let seeds: &[&[u8]] = &[b"treasury", t.marketplace.as_ref(), t.mint.as_ref(), &[t.bump]];
+        let signer = &[seeds];
+
+        let mut distributed: u64 = 0;
+        for (split, recipient_info) in t.splits.iter().zip(ctx.remaining_accounts.iter()) {
+            let recipient = Account::<TokenAccount>::try_from(recipient_info)?;
+            require!(
+                recipient.owner == split.recipient && recipient.mint == t.mint,
+                MarketplaceError::InvalidAccount
+            );
+
+            let share = split.share_of(balance);
+            if share == 0 {
+                continue;
+            }
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.treasury_vault.to_account_info(),
+                        to: recipient_info.clone(),
+                        authority: ctx.accounts.treasury.to_account_info(),
+                    },
+                    signer,
+                ),
+                share,
+            )?;
+            distributed += share;
+        }
+
+        let t = &mut ctx.accounts.treasury;
+        t.total_distributed = t
+            .total_distributed
+            .checked_add(distributed)
+            .ok_or(MarketplaceError::MathOverflow)?;
+
+        emit!(FeesDistributed {
+            marketplace: t.marketplace,
+            mint: t.mint,
+            amount: distributed,
+        });
         Ok(())
     }
 
@@ -38,16 +273,192 @@ pub mod konnect {
         m.owner = ctx.accounts.owner.key();
         m.verified = false;
         m.bump = ctx.bumps.merchant;
+        m.version = ACCOUNT_VERSION;
+        m.next_nonce = 0;
+        m.rating_sum = 0;
+        m.rating_count = 0;
+        m.sales_count = 0;
+        m.sales_volume = 0;
+
+        let mp = &mut ctx.accounts.marketplace;
+        mp.merchant_count = mp
+            .merchant_count
+            .checked_add(1)
+            .ok_or(MarketplaceError::MathOverflow)?;
+
+        emit!(MerchantRegistered {
+            marketplace: m.marketplace,
+            merchant: m.key(),
+            owner: m.owner,
+            source: None,
+        });
+        Ok(())
+    }
+
+    // join another marketplace from an existing merchant account: verification carries over when
+    // both marketplaces are run by the same authority; ratings and sales stats start fresh, since
+    // reviews can only be left on this marketplace's orders
+    pub fn cross_register_merchant(ctx: Context<CrossRegisterMerchant>) -> Result<()> {
+        let source = &ctx.accounts.source_merchant;
+        let same_operator =
+            ctx.accounts.source_marketplace.authority == ctx.accounts.marketplace.authority;
+
+        let m = &mut ctx.accounts.merchant;
+        m.marketplace = ctx.accounts.marketplace.key();
+        m.owner = ctx.accounts.owner.key();
+        m.verified = source.verified && same_operator;
+        m.bump = ctx.bumps.merchant;
+        m.version = ACCOUNT_VERSION;
         m.next_nonce = 0;
+        m.rating_sum = 0;
+        m.rating_count = 0;
+        m.sales_count = 0;
+        m.sales_volume = 0;
+
+        let mp = &mut ctx.accounts.marketplace;
+        mp.merchant_count = mp
+            .merchant_count
+            .checked_add(1)
+            .ok_or(MarketplaceError::MathOverflow)?;
+
+        emit!(MerchantRegistered {
+            marketplace: m.marketplace,
+            merchant: m.key(),
+            owner: m.owner,
+            source: Some(source.key()),
+        });
         Ok(())
     }
 
     pub fn set_merchant_status(ctx: Context<SetMerchantStatus>, verified: bool) -> Result<()> {
-        ctx.accounts.merchant.verified = verified;
+        let m = &mut ctx.accounts.merchant;
+        let was_verified = m.verified;
+        m.verified = verified;
+
+        emit!(MerchantStatusChanged {
+            marketplace: m.marketplace,
+            merchant: m.key(),
+            owner: m.owner,
+            was_verified,
+            verified,
+        });
         Ok(())
     }
 
     
+    // Merchant bonds
+    pub fn deposit_bond(ctx: Context<DepositBond>, amount: u64) -> Result<()> {
+        require!(amount > 0, MarketplaceError::InvalidAmount);
+        require!(
+            ctx.accounts.mint.key()
+                == ctx.accounts.bond.deposit_mint(&ctx.accounts.marketplace.bond),
+            MarketplaceError::InvalidAccount
+        );
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.owner_ata.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let b = &mut ctx.accounts.bond;
+        if b.merchant == Pubkey::default() {
+            b.merchant = ctx.accounts.merchant.key();
+            b.owner = ctx.accounts.owner.key();
+            b.bump = ctx.bumps.bond;
+        }
+        b.mint = ctx.accounts.mint.key();
+        b.amount = b
+            .amount
+            .checked_add(amount)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        // topping up cancels a pending withdrawal, so the bond counts for listing again
+        b.unlock_at = None;
+        Ok(())
+    }
+
+    // starts the cooldown; from now on the bond doesn't count for listing, a new deposit
+    // cancels the request
+    pub fn request_bond_withdrawal(ctx: Context<RequestBondWithdrawal>) -> Result<()> {
+        let unlock_at = Clock::get()?
+            .unix_timestamp
+            .checked_add(ctx.accounts.marketplace.bond.cooldown_secs)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        ctx.accounts.bond.unlock_at = Some(unlock_at);
+        Ok(())
+    }
+
+    pub fn withdraw_bond(ctx: Context<WithdrawBond>, amount: u64) -> Result<()> {
+        let b = &ctx.accounts.bond;
+        b.check_withdrawable(Clock::get()?.unix_timestamp, amount)?;
+
+        let merchant_key = b.merchant;
+        let seeds: &[&[u8]] = &[b"bond", merchant_key.as_ref(), &[b.bump]];
+        let signer = &[seeds];
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.owner_ata.to_account_info(),
+                    authority: ctx.accounts.bond.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+        )?;
+
+        let b = &mut ctx.accounts.bond;
+        b.amount -= amount;
+        b.unlock_at = None;
+        Ok(())
+    }
+
+    // arbiter compensates a buyer out of the merchant's bond after a lost dispute
+    pub fn slash_bond(ctx: Context<SlashBond>, amount: u64, reference: Pubkey) -> Result<()> {
+        let b = &ctx.accounts.bond;
+        require!(amount > 0 && amount <= b.amount, MarketplaceError::InvalidAmount);
+
+        let merchant_key = b.merchant;
+        let seeds: &[&[u8]] = &[b"bond", merchant_key.as_ref(), &[b.bump]];
+        let signer = &[seeds];
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.recipient_ata.to_account_info(),
+                    authority: ctx.accounts.bond.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+        )?;
+
+        let b = &mut ctx.accounts.bond;
+        b.amount -= amount;
+
+        emit!(BondSlashed {
+            marketplace: ctx.accounts.marketplace.key(),
+            merchant: b.merchant,
+            recipient: ctx.accounts.recipient_ata.owner,
+            mint: b.mint,
+            amount,
+            remaining: b.amount,
+            reference,
+        });
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn create_listing(
         ctx: Context<CreateListing>,
         price: u64,
@@ -55,8 +466,20 @@ pub mod konnect {
         is_service: bool,
         name: String,
         image_url: String,
+        price_feed: Option<Pubkey>,
+        metadata: ListingMetadata,
+        sale_window: SaleWindow,
+        limits: PurchaseLimits,
     ) -> Result<()> {
         require!(price > 0, MarketplaceError::InvalidAmount);
+        sale_window.validate()?;
+        Listing::validate_name(&name)?;
+        Listing::validate_image_url(&image_url)?;
+        metadata.validate()?;
+
+        ctx.accounts
+            .merchant
+            .check_can_list(&ctx.accounts.marketplace.bond, ctx.accounts.bond.as_deref())?;
 
         let merchant = &mut ctx.accounts.merchant;
         let listing = &mut ctx.accounts.listing;
@@ -76,10 +499,37 @@ pub mod konnect {
         listing.is_service = is_service;
         listing.active = true;
         listing.bump = ctx.bumps.listing;
+        listing.version = ACCOUNT_VERSION;
         listing.name = name;
         listing.image_url = image_url;
         listing.nonce = nonce;
+        listing.open_escrows = 0;
+        listing.price_feed = price_feed;
+        listing.revision = 0;
+        listing.reserved = 0;
+        listing.variant_count = 0;
+        listing.metadata = metadata;
+        listing.sale_window = sale_window;
+        listing.limits = limits;
+        listing.rating_sum = 0;
+        listing.rating_count = 0;
 
+        emit!(ListingCreated {
+            marketplace: listing.marketplace,
+            listing: listing.key(),
+            seller: listing.seller,
+            mint: listing.mint,
+            nonce,
+            price,
+            quantity,
+            is_service,
+            name: listing.name.clone(),
+            image_url: listing.image_url.clone(),
+            price_feed,
+            metadata: listing.metadata.clone(),
+            sale_window,
+            limits,
+        });
         Ok(())
     }
     
@@ -89,8 +539,13 @@ pub mod konnect {
         new_price: Option<u64>,
         new_quantity: Option<u32>,
         active: Option<bool>,
+        new_sale_window: Option<SaleWindow>,
+        new_limits: Option<PurchaseLimits>,
+        // Pubkey::default() switches the listing back to a fixed token price
+        new_price_feed: Option<Pubkey>,
     ) -> Result<()> {
         let l = &mut ctx.accounts.listing;
+        let before = Listing::clone(l);
         if let Some(p) = new_price {
             require!(p > 0, MarketplaceError::InvalidAmount);
             l.price = p;
@@ -101,310 +556,4803 @@ pub mod konnect {
         if let Some(a) = active {
             l.active = a;
         }
+        if let Some(w) = new_sale_window {
+            w.validate()?;
+            l.sale_window = w;
+        }
+        if let Some(limits) = new_limits {
+            l.limits = limits;
+        }
+        if let Some(feed) = new_price_feed {
+            l.price_feed = (feed != Pubkey::default()).then_some(feed);
+        }
+        l.bump_revision()?;
+
+        emit!(ListingUpdated::new(l.key(), &before, l));
         Ok(())
     }
 
-    // buy now flow
-    pub fn buy_now(ctx: Context<BuyNow>, quantity: u32, reference: Pubkey) -> Result<()> {
+    // edit name, image and extended metadata, resizing the account when metadata changes
+    pub fn update_listing_metadata(
+        ctx: Context<UpdateListingMetadata>,
+        name: Option<String>,
+        image_url: Option<String>,
+        metadata: Option<ListingMetadata>,
+    ) -> Result<()> {
         let l = &mut ctx.accounts.listing;
+        let before = Listing::clone(l);
+        if let Some(n) = &name {
+            Listing::validate_name(n)?;
+            l.name = n.clone();
+        }
+        if let Some(url) = &image_url {
+            Listing::validate_image_url(url)?;
+            l.image_url = url.clone();
+        }
+        if let Some(m) = &metadata {
+            m.validate()?;
+            l.metadata = m.clone();
+        }
+        l.bump_revision()?;
 
-        require!(l.active, MarketplaceError::ListingInactive);
-        require!(!l.is_service, MarketplaceError::WrongFlowForService);
-        require!(quantity > 0 && quantity <= l.quantity, MarketplaceError::InvalidQuantity);
+        let mut event = ListingUpdated::new(l.key(), &before, l);
+        if metadata.is_some() {
+            event.old_metadata = Some(before.metadata);
+            event.new_metadata = metadata;
+        }
+        emit!(event);
+        Ok(())
+    }
 
-        let reference_account = ctx
-            .remaining_accounts
-            .get(0)
-            .ok_or(MarketplaceError::MissingReference)?;
-        require!(reference_account.key() == reference, MarketplaceError::WrongReference);
+    // reclaim rent for a delisted or sold-out listing, rent goes back to the seller
+    pub fn close_listing(ctx: Context<CloseListing>) -> Result<()> {
+        let l = &ctx.accounts.listing;
+        require!(!l.active, MarketplaceError::ListingStillActive);
+        require!(l.open_escrows == 0, MarketplaceError::OpenEscrowsRemaining);
+        require!(l.reserved == 0, MarketplaceError::OutstandingReservations);
+        require!(l.variant_count == 0, MarketplaceError::VariantsRemaining);
 
-        require!(
-            ctx.accounts.seller_ata.owner == l.seller && ctx.accounts.seller_ata.mint == l.mint,
-            MarketplaceError::InvalidAccount
-        );
+        emit!(ListingClosed {
+            marketplace: l.marketplace,
+            listing: l.key(),
+            seller: l.seller,
+            revision: l.revision,
+        });
+        Ok(())
+    }
 
-        let total_price = l
-            .price
-            .checked_mul(quantity as u64)
-            .ok_or(MarketplaceError::MathOverflow)?;
-        let fee = total_price
-            .checked_mul(l.get_fee_bps(&ctx.accounts.marketplace)? as u64)
-            .ok_or(MarketplaceError::MathOverflow)?
-            / 10_000;
-        let seller_amount = total_price
-            .checked_sub(fee)
+    // Variants (size/color SKUs of a goods listing)
+    pub fn add_variant(ctx: Context<AddVariant>, id: u16, price: u64, quantity: u32) -> Result<()> {
+        require!(price > 0, MarketplaceError::InvalidAmount);
+
+        let l = &mut ctx.accounts.listing;
+        require!(!l.is_service, MarketplaceError::WrongFlowForService);
+        l.variant_count = l
+            .variant_count
+            .checked_add(1)
             .ok_or(MarketplaceError::MathOverflow)?;
+        l.bump_revision()?;
 
-        token::transfer(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.buyer_ata.to_account_info(),
-                    to: ctx.accounts.seller_ata.to_account_info(),
-                    authority: ctx.accounts.buyer.to_account_info(),
-                },
-            ),
-            seller_amount,
-        )?;
+        let v = &mut ctx.accounts.variant;
+        v.listing = l.key();
+        v.id = id;
+        v.price = price;
+        v.quantity = quantity;
+        v.active = true;
+        v.bump = ctx.bumps.variant;
 
-        if fee > 0 {
-            token::transfer(
-                CpiContext::new(
-                    ctx.accounts.token_program.to_account_info(),
-                    Transfer {
-                        from: ctx.accounts.buyer_ata.to_account_info(),
-                        to: ctx.accounts.treasury_ata.to_account_info(),
-                        authority: ctx.accounts.buyer.to_account_info(),
-                    },
-                ),
-                fee,
-            )?;
+        emit!(VariantAdded {
+            listing: l.key(),
+            variant: v.key(),
+            id,
+            price,
+            quantity,
+            listing_revision: l.revision,
+        });
+        Ok(())
+    }
+
+    pub fn update_variant(
+        ctx: Context<UpdateVariant>,
+        new_price: Option<u64>,
+        new_quantity: Option<u32>,
+        active: Option<bool>,
+    ) -> Result<()> {
+        let v = &mut ctx.accounts.variant;
+        let (old_price, old_quantity, old_active) = (v.price, v.quantity, v.active);
+        if let Some(p) = new_price {
+            require!(p > 0, MarketplaceError::InvalidAmount);
+            v.price = p;
+        }
+        if let Some(q) = new_quantity {
+            v.quantity = q;
         }
+        if let Some(a) = active {
+            v.active = a;
+        }
+        ctx.accounts.listing.bump_revision()?;
 
-        l.quantity = l
-            .quantity
-            .checked_sub(quantity)
+        emit!(VariantUpdated {
+            listing: v.listing,
+            variant: v.key(),
+            id: v.id,
+            old_price,
+            new_price: v.price,
+            old_quantity,
+            new_quantity: v.quantity,
+            old_active,
+            new_active: v.active,
+            listing_revision: ctx.accounts.listing.revision,
+        });
+        Ok(())
+    }
+
+    pub fn remove_variant(ctx: Context<RemoveVariant>) -> Result<()> {
+        let l = &mut ctx.accounts.listing;
+        l.variant_count = l
+            .variant_count
+            .checked_sub(1)
             .ok_or(MarketplaceError::MathOverflow)?;
-        if l.quantity == 0 {
-            l.active = false;
-        }
+        l.bump_revision()?;
 
-        emit!(OrderCompleted {
-            marketplace: ctx.accounts.marketplace.key(),
+        let v = &ctx.accounts.variant;
+        emit!(VariantRemoved {
             listing: l.key(),
-            buyer: ctx.accounts.buyer.key(),
-            seller: l.seller,
-            mint: ctx.accounts.mint.key(),
-            quantity,
-            total_amount: total_price,
-            reference,
+            variant: v.key(),
+            id: v.id,
+            old_price: v.price,
+            old_quantity: v.quantity,
+            old_active: v.active,
+            listing_revision: l.revision,
         });
-
         Ok(())
     }
 
-    // Services (escrow)
-    pub fn create_service_order(ctx: Context<CreateServiceOrder>, reference: Pubkey) -> Result<()> {
-        let l = &ctx.accounts.listing;
-        require!(l.active, MarketplaceError::ListingInactive);
-        require!(l.is_service, MarketplaceError::WrongFlowForGoods);
+    // hold stock for a buyer while they complete checkout
+    pub fn reserve_inventory(
+        ctx: Context<ReserveInventory>,
+        quantity: u32,
+        ttl: i64,
+        allowlist_proof: Option<Vec<[u8; 32]>>,
+    ) -> Result<()> {
+        require!(
+            ttl > 0 && ttl <= MAX_RESERVATION_TTL_SECS,
+            MarketplaceError::InvalidReservationTtl
+        );
+
+        let l = &mut ctx.accounts.listing;
+        l.check_on_sale()?;
+        require!(!l.is_service, MarketplaceError::WrongFlowForService);
+        require!(l.variant_count == 0, MarketplaceError::UnsupportedForVariants);
+        require!(quantity > 0 && quantity <= l.quantity, MarketplaceError::InvalidQuantity);
+        if let Some(max) = l.limits.max_per_wallet {
+            require!(quantity <= max, MarketplaceError::WalletLimitExceeded);
+        }
+        // otherwise a wallet outside a presale could hold its stock away from allowlisted buyers
+        l.limits.check_allowlist(allowlist_proof.as_deref(), &ctx.accounts.buyer.key())?;
+
+        let old_quantity = l.quantity;
+        l.quantity -= quantity;
+        l.reserved = l
+            .reserved
+            .checked_add(quantity)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        emit!(ListingStockChanged {
+            listing: l.key(),
+            variant: None,
+            old_quantity,
+            new_quantity: l.quantity,
+            old_active: l.active,
+            new_active: l.active,
+            reserved: l.reserved,
+        });
+
+        let r = &mut ctx.accounts.reservation;
+        r.listing = l.key();
+        r.buyer = ctx.accounts.buyer.key();
+        r.quantity = quantity;
+        r.expires_at = Clock::get()?
+            .unix_timestamp
+            .checked_add(ttl)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        r.bump = ctx.bumps.reservation;
+        r.rent_payer = ctx.accounts.payer.key();
+        Ok(())
+    }
+
+    // buyer can drop their hold at any time, anyone can clean up once it has expired
+    pub fn release_reservation(ctx: Context<ReleaseReservation>) -> Result<()> {
+        let r = &ctx.accounts.reservation;
+        require!(
+            ctx.accounts.caller.key() == r.buyer
+                || Clock::get()?.unix_timestamp >= r.expires_at,
+            MarketplaceError::ReservationNotExpired
+        );
+        let l = &mut ctx.accounts.listing;
+        let old_quantity = l.quantity;
+        l.restock_reserved(r.quantity)?;
+        emit!(ListingStockChanged {
+            listing: l.key(),
+            variant: None,
+            old_quantity,
+            new_quantity: l.quantity,
+            old_active: l.active,
+            new_active: l.active,
+            reserved: l.reserved,
+        });
+        Ok(())
+    }
+
+    // buy now flow
+    #[allow(clippy::too_many_arguments)]
+    pub fn buy_now(
+        ctx: Context<BuyNow>,
+        quantity: u32,
+        reference: Pubkey,
+        price_guard: Option<PriceGuard>,
+        expected_revision: Option<u32>,
+        max_total: Option<u64>,
+        variant_id: Option<u16>,
+        allowlist_proof: Option<Vec<[u8; 32]>>,
+    ) -> Result<()> {
+        let l = &mut ctx.accounts.listing;
+        let (old_quantity, old_active) = match ctx.accounts.variant.as_ref() {
+            Some(v) => (v.quantity, v.active),
+            None => (l.quantity, l.active),
+        };
+
+        l.check_on_sale()?;
+        require!(!l.is_service, MarketplaceError::WrongFlowForService);
+
+        // a held reservation goes back into stock first; the purchase then draws from it
+        // and the reservation account is closed to its rent payer on exit
+        if let Some(r) = &ctx.accounts.reservation {
+            require!(
+                Clock::get()?.unix_timestamp < r.expires_at,
+                MarketplaceError::ReservationExpired
+            );
+            require!(quantity <= r.quantity, MarketplaceError::InvalidQuantity);
+            l.restock_reserved(r.quantity)?;
+        }
+
+        // listings with variants sell price and stock per variant
+        let unit_price = match (variant_id, ctx.accounts.variant.as_ref()) {
+            (None, None) => {
+                require!(l.variant_count == 0, MarketplaceError::VariantRequired);
+                require!(quantity > 0 && quantity <= l.quantity, MarketplaceError::InvalidQuantity);
+                l.price
+            }
+            (Some(id), Some(v)) => {
+                require!(v.id == id, MarketplaceError::WrongVariant);
+                require!(v.active, MarketplaceError::VariantInactive);
+                require!(quantity > 0 && quantity <= v.quantity, MarketplaceError::InvalidQuantity);
+                v.price
+            }
+            _ => return err!(MarketplaceError::WrongVariant),
+        };
+
+        // drop protections: allowlist proof and per-wallet cap tracked in the purchase record
+        let buyer_key = ctx.accounts.buyer.key();
+        l.limits.check_allowlist(allowlist_proof.as_deref(), &buyer_key)?;
+        match ctx.accounts.purchase_record.as_mut() {
+            Some(record) => {
+                if record.buyer == Pubkey::default() {
+                    record.listing = l.key();
+                    record.buyer = buyer_key;
+                    record.bump = ctx.bumps.purchase_record.unwrap_or_default();
+                }
+                record.purchased = record
+                    .purchased
+                    .checked_add(quantity)
+                    .ok_or(MarketplaceError::MathOverflow)?;
+                if let Some(max) = l.limits.max_per_wallet {
+                    require!(record.purchased <= max, MarketplaceError::WalletLimitExceeded);
+                }
+            }
+            None => require!(
+                l.limits.max_per_wallet.is_none(),
+                MarketplaceError::PurchaseRecordRequired
+            ),
+        }
 
-        // enforce reference presence in transaction metas for Solana Pay correlation
         let reference_account = ctx
             .remaining_accounts
-            .get(0)
+            .first()
             .ok_or(MarketplaceError::MissingReference)?;
         require!(reference_account.key() == reference, MarketplaceError::WrongReference);
 
-        let total_price = l.price;
+        require!(
+            ctx.accounts.seller_ata.owner == l.seller && ctx.accounts.seller_ata.mint == l.mint,
+            MarketplaceError::InvalidAccount
+        );
 
-        token::transfer(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.buyer_ata.to_account_info(),
-                    to: ctx.accounts.vault.to_account_info(),
-                    authority: ctx.accounts.buyer.to_account_info(),
-                },
-            ),
-            total_price,
+        let (total_price, quote) = l.settle_total(
+            unit_price,
+            quantity,
+            ctx.accounts.price_feed.as_deref(),
+            ctx.accounts.marketplace.oracle_program,
+            price_guard,
+            ctx.accounts.mint.decimals,
         )?;
+        l.check_buyer_view(expected_revision, total_price, max_total)?;
+        let fee = total_price
+            .checked_mul(l.get_fee_bps(&ctx.accounts.marketplace)? as u64)
+            .ok_or(MarketplaceError::MathOverflow)?
+            / 10_000;
+        let seller_amount = total_price
+            .checked_sub(fee)
+            .ok_or(MarketplaceError::MathOverflow)?;
 
-        // Initialize escrow account state
-        let e = &mut ctx.accounts.escrow;
-        e.marketplace = ctx.accounts.marketplace.key();
-        e.listing = l.key();
-        e.seller = l.seller;
-        e.buyer = ctx.accounts.buyer.key();
-        e.mint = ctx.accounts.mint.key();
-        e.amount = total_price;
-        e.reference = reference;
-        e.released = false;
-        e.bump = ctx.bumps.escrow;
+        // staged points come off the marketplace fee so the seller is still paid in full
+        let p = &mut ctx.accounts.buyer_profile;
+        p.init_if_new(ctx.accounts.marketplace.key(), buyer_key, ctx.bumps.buyer_profile);
+        p.record_orders(1, false)?;
+        let (points_redeemed, discount) = p.take_discount(fee, ctx.accounts.mint.decimals)?;
+        let points_earned = p.credit(
+            total_price - discount,
+            ctx.accounts.mint.decimals,
+            ctx.accounts.marketplace.points_bps,
+        )?;
+        emit!(PointsUpdated {
+            marketplace: p.marketplace,
+            buyer: p.buyer,
+            earned: points_earned,
+            redeemed: points_redeemed,
+            balance: p.points,
+            reference,
+        });
+        let fee = fee - discount;
 
-        // emit event so off-chain indexers immediately know escrow created
-        emit!(ServiceOrderCreated {
+        authorize_buyer(
+            &ctx.accounts.buyer,
+            &ctx.accounts.signer,
+            ctx.accounts.session.as_mut(),
+            &ctx.accounts.buyer_ata,
+            seller_amount + fee,
+        )?;
+        pay_from_buyer(
+            &ctx.accounts.token_program,
+            &ctx.accounts.buyer_ata,
+            ctx.accounts.seller_ata.to_account_info(),
+            &ctx.accounts.signer,
+            ctx.accounts.session.as_ref(),
+            seller_amount,
+        )?;
+
+        if fee > 0 {
+            pay_from_buyer(
+                &ctx.accounts.token_program,
+                &ctx.accounts.buyer_ata,
+                ctx.accounts.treasury_vault.to_account_info(),
+                &ctx.accounts.signer,
+                ctx.accounts.session.as_ref(),
+                fee,
+            )?;
+            ctx.accounts.treasury.record_fee(fee)?;
+        }
+
+        let (new_quantity, new_active) = match ctx.accounts.variant.as_mut() {
+            Some(v) => {
+                v.take_stock(quantity)?;
+                (v.quantity, v.active)
+            }
+            None => {
+                l.take_stock(quantity)?;
+                (l.quantity, l.active)
+            }
+        };
+        emit!(ListingStockChanged {
+            listing: l.key(),
+            variant: ctx.accounts.variant.as_ref().map(|v| v.id),
+            old_quantity,
+            new_quantity,
+            old_active,
+            new_active,
+            reserved: l.reserved,
+        });
+        ctx.accounts.marketplace.record_orders(1)?;
+        ctx.accounts.treasury.record_volume(total_price)?;
+        ctx.accounts
+            .merchant
+            .record_sale(total_price, ctx.accounts.mint.decimals)?;
+
+        if let Some(receipt) = ctx.accounts.receipt.as_mut() {
+            receipt.marketplace = ctx.accounts.marketplace.key();
+            receipt.listing = l.key();
+            receipt.buyer = ctx.accounts.buyer.key();
+            receipt.seller = l.seller;
+            receipt.mint = l.mint;
+            receipt.quantity = quantity;
+            receipt.variant = variant_id;
+            // what the buyer actually paid, so refunds never exceed it
+            receipt.amount = total_price - discount;
+            receipt.fee = fee;
+            receipt.points_redeemed = points_redeemed;
+            receipt.points_earned = points_earned;
+            receipt.timestamp = Clock::get()?.unix_timestamp;
+            receipt.reference = reference;
+            receipt.status = ReceiptStatus::Completed;
+            receipt.bump = ctx.bumps.receipt.unwrap_or_default();
+            receipt.rent_payer = ctx.accounts.payer.key();
+            receipt.funding_account = ctx.accounts.buyer_ata.key();
+            receipt.is_service = false;
+        }
+
+        emit!(OrderCompleted {
             marketplace: ctx.accounts.marketplace.key(),
             listing: l.key(),
-            buyer: e.buyer,
-            seller: e.seller,
-            mint: e.mint,
-            amount: e.amount,
+            buyer: ctx.accounts.buyer.key(),
+            payer: ctx.accounts.buyer_ata.owner,
+            seller: l.seller,
+            mint: ctx.accounts.mint.key(),
+            quantity,
+            total_amount: total_price,
             reference,
-            escrow: ctx.accounts.escrow.key(),
+            quote,
+            variant: variant_id,
         });
 
         Ok(())
     }
 
-    // release funds from escrow to seller (can be called by buyer or marketplace authority via ctx enforcement)
-    pub fn release_service_order(ctx: Context<ReleaseServiceOrder>) -> Result<()> {
+    // cart checkout: remaining_accounts = [reference, (listing, seller_ata, merchant) per item].
+    // The buyer signs and pays from their own account and rent; session keys, sponsored rent and
+    // delegated payers, like variants, limits and oracle prices, are left to buy_now
+    pub fn checkout_cart<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CheckoutCart<'info>>,
+        items: Vec<CartItem>,
+        reference: Pubkey,
+    ) -> Result<()> {
         require!(
-            !ctx.accounts.escrow.released,
-            MarketplaceError::AlreadyReleased
+            !items.is_empty() && items.len() <= MAX_CART_ITEMS,
+            MarketplaceError::InvalidCart
+        );
+        require!(
+            ctx.accounts.buyer_profile.pending_discount == 0,
+            MarketplaceError::RedemptionUnsupported
         );
 
-        let bump = ctx.accounts.escrow.bump;
-        let amount = ctx.accounts.escrow.amount;
-        let listing = ctx.accounts.escrow.listing;
-        let buyer_key = ctx.accounts.escrow.buyer;
-        let fee_bps = ctx.accounts.escrow.get_fee_bps(&ctx.accounts.marketplace)?;
+        let reference_account = ctx
+            .remaining_accounts
+            .first()
+            .ok_or(MarketplaceError::MissingReference)?;
+        require!(reference_account.key() == reference, MarketplaceError::WrongReference);
+        require!(
+            ctx.remaining_accounts.len() == 1 + items.len() * 3,
+            MarketplaceError::InvalidCart
+        );
 
-        let fee = amount
-            .checked_mul(fee_bps as u64)
-            .ok_or(MarketplaceError::MathOverflow)?
-            / 10_000;
-        let seller_amount = amount.checked_sub(fee).ok_or(MarketplaceError::MathOverflow)?;
+        let marketplace_key = ctx.accounts.marketplace.key();
+        let mint_key = ctx.accounts.mint.key();
+        let fee_bps = ctx.accounts.marketplace.fee_bps as u64;
+        let mut cart_total: u64 = 0;
+        let mut fee_total: u64 = 0;
 
-        let seeds: &[&[u8]] = &[
-            b"escrow",
-            listing.as_ref(),
-            buyer_key.as_ref(),
-            &[bump],
-        ];
-        let signer = &[seeds];
+        for (item, accounts) in items.iter().zip(ctx.remaining_accounts[1..].chunks(3)) {
+            let listing_info = &accounts[0];
+            let seller_ata_info = &accounts[1];
+            let merchant_info = &accounts[2];
+            require!(
+                listing_info.key() == item.listing && listing_info.is_writable,
+                MarketplaceError::InvalidAccount
+            );
 
-        token::transfer(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.vault.to_account_info(),
-                    to: ctx.accounts.seller_ata.to_account_info(),
-                    authority: ctx.accounts.escrow.to_account_info(),
-                },
-                signer,
-            ),
-            seller_amount,
-        )?;
+            let mut l = Account::<Listing>::try_from(listing_info)?;
+            require!(l.marketplace == marketplace_key, MarketplaceError::WrongMarketplace);
+            require!(l.mint == mint_key, MarketplaceError::InvalidAccount);
+            l.check_on_sale()?;
+            require!(!l.is_service, MarketplaceError::WrongFlowForService);
+            require!(
+                l.price_feed.is_none() && l.variant_count == 0 && l.limits.is_open(),
+                MarketplaceError::UnsupportedInCart
+            );
+            require!(
+                item.quantity > 0 && item.quantity <= l.quantity,
+                MarketplaceError::InvalidQuantity
+            );
+
+            let seller_ata = Account::<TokenAccount>::try_from(seller_ata_info)?;
+            require!(
+                seller_ata.owner == l.seller && seller_ata.mint == l.mint,
+                MarketplaceError::InvalidAccount
+            );
+
+            require!(merchant_info.is_writable, MarketplaceError::InvalidAccount);
+            let mut merchant = Account::<Merchant>::try_from(merchant_info)?;
+            require!(
+                merchant.marketplace == marketplace_key && merchant.owner == l.seller,
+                MarketplaceError::InvalidAccount
+            );
+
+            // fee is computed per line so each line settles exactly like buy_now
+            let line_total = l
+                .price
+                .checked_mul(item.quantity as u64)
+                .ok_or(MarketplaceError::MathOverflow)?;
+            let line_fee = line_total
+                .checked_mul(fee_bps)
+                .ok_or(MarketplaceError::MathOverflow)?
+                / 10_000;
+            let seller_amount = line_total
+                .checked_sub(line_fee)
+                .ok_or(MarketplaceError::MathOverflow)?;
 
-        if fee > 0 {
             token::transfer(
-                CpiContext::new_with_signer(
+                CpiContext::new(
                     ctx.accounts.token_program.to_account_info(),
                     Transfer {
-                        from: ctx.accounts.vault.to_account_info(),
-                        to: ctx.accounts.treasury_ata.to_account_info(),
-                        authority: ctx.accounts.escrow.to_account_info(),
+                        from: ctx.accounts.buyer_ata.to_account_info(),
+                        to: seller_ata_info.clone(),
+                        authority: ctx.accounts.buyer.to_account_info(),
                     },
-                    signer,
                 ),
-                    fee,
+                seller_amount,
             )?;
+
+            let (old_quantity, old_active) = (l.quantity, l.active);
+            l.take_stock(item.quantity)?;
+            emit!(ListingStockChanged {
+                listing: l.key(),
+                variant: None,
+                old_quantity,
+                new_quantity: l.quantity,
+                old_active,
+                new_active: l.active,
+                reserved: l.reserved,
+            });
+            // persist now so a listing repeated later in the cart sees the new stock
+            l.exit(&crate::ID)?;
+            merchant.record_sale(line_total, ctx.accounts.mint.decimals)?;
+            merchant.exit(&crate::ID)?;
+
+            cart_total = cart_total
+                .checked_add(line_total)
+                .ok_or(MarketplaceError::MathOverflow)?;
+            fee_total = fee_total
+                .checked_add(line_fee)
+                .ok_or(MarketplaceError::MathOverflow)?;
+
+            emit!(OrderCompleted {
+                marketplace: marketplace_key,
+                listing: l.key(),
+                buyer: ctx.accounts.buyer.key(),
+                payer: ctx.accounts.buyer_ata.owner,
+                seller: l.seller,
+                mint: mint_key,
+                quantity: item.quantity,
+                total_amount: line_total,
+                reference,
+                quote: None,
+                variant: None,
+            });
         }
 
-        token::close_account(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                CloseAccount {
-                    account: ctx.accounts.vault.to_account_info(),
-                    destination: ctx.accounts.buyer.to_account_info(),
-                    authority: ctx.accounts.escrow.to_account_info(),
-                },
-                signer,
-            ),
+        if fee_total > 0 {
+            token::transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.buyer_ata.to_account_info(),
+                        to: ctx.accounts.treasury_vault.to_account_info(),
+                        authority: ctx.accounts.buyer.to_account_info(),
+                    },
+                ),
+                fee_total,
+            )?;
+            ctx.accounts.treasury.record_fee(fee_total)?;
+        }
+        ctx.accounts.treasury.record_volume(cart_total)?;
+        ctx.accounts.marketplace.record_orders(items.len() as u64)?;
+        let p = &mut ctx.accounts.buyer_profile;
+        p.init_if_new(marketplace_key, ctx.accounts.buyer.key(), ctx.bumps.buyer_profile);
+        p.record_orders(items.len() as u32, false)?;
+        // points are earned on the cart as a whole, as buy_now earns them on an order
+        let points_earned = p.credit(
+            cart_total,
+            ctx.accounts.mint.decimals,
+            ctx.accounts.marketplace.points_bps,
         )?;
+        emit!(PointsUpdated {
+            marketplace: p.marketplace,
+            buyer: p.buyer,
+            earned: points_earned,
+            redeemed: 0,
+            balance: p.points,
+            reference,
+        });
 
-        let e = &mut ctx.accounts.escrow;
-        e.released = true;
-
-        emit!(ServiceOrderReleased {
-            marketplace: ctx.accounts.marketplace.key(),
-            escrow: e.key(),
-            buyer: e.buyer,
-            seller: e.seller,
-            mint: e.mint,
-            amount,
-            reference: e.reference,
+        emit!(CartCompleted {
+            marketplace: marketplace_key,
+            buyer: ctx.accounts.buyer.key(),
+            mint: mint_key,
+            item_count: items.len() as u8,
+            total_amount: cart_total,
+            fee: fee_total,
+            reference,
         });
 
         Ok(())
     }
 
-    // cancel/refund escrow back to buyer (can be called by marketplace authority or buyer depending on your policy)
-    pub fn cancel_service_order(ctx: Context<CancelServiceOrder>) -> Result<()> {
+    // Catalog (zero-copy listings for merchants with large inventories)
+    // created at up to MAX_PERMITTED_DATA_INCREASE bytes, grow_catalog adds room afterwards
+    pub fn init_catalog(ctx: Context<InitCatalog>, capacity: u32, base_uri: String) -> Result<()> {
         require!(
-            !ctx.accounts.escrow.released,
-            MarketplaceError::AlreadyReleased
+            base_uri.len() <= MAX_CATALOG_URI_LEN,
+            MarketplaceError::MetadataUriTooLong
         );
+        if !base_uri.is_empty() {
+            Listing::validate_url(&base_uri)?;
+        }
 
-        let bump = ctx.accounts.escrow.bump;
-        let amount = ctx.accounts.escrow.amount;
-        let listing = ctx.accounts.escrow.listing;
-        let buyer_key = ctx.accounts.escrow.buyer;
+        let mut c = ctx.accounts.catalog.load_init()?;
+        c.marketplace = ctx.accounts.marketplace.key();
+        c.merchant = ctx.accounts.merchant.key();
+        c.seller = ctx.accounts.owner.key();
+        c.mint = ctx.accounts.mint.key();
+        c.entry_count = 0;
+        c.bump = ctx.bumps.catalog;
+        c.version = ACCOUNT_VERSION;
+        c.base_uri_len = base_uri.len() as u8;
+        c.base_uri[..base_uri.len()].copy_from_slice(base_uri.as_bytes());
 
-        let seeds: &[&[u8]] = &[
-            b"escrow",
-            listing.as_ref(),
-            buyer_key.as_ref(),
-            &[bump],
-        ];
-        let signer = &[seeds];
+        emit!(CatalogCreated {
+            marketplace: c.marketplace,
+            catalog: ctx.accounts.catalog.key(),
+            seller: c.seller,
+            mint: c.mint,
+            capacity,
+            base_uri,
+        });
+        Ok(())
+    }
 
-        token::transfer(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.vault.to_account_info(),
-                    to: ctx.accounts.buyer_ata.to_account_info(),
-                    authority: ctx.accounts.escrow.to_account_info(),
-                },
-                signer,
-            ),
-            amount,
-        )?;
+    pub fn grow_catalog(ctx: Context<GrowCatalog>, additional: u32) -> Result<()> {
+        let info = ctx.accounts.catalog.to_account_info();
+        let capacity = Catalog::capacity(info.data_len());
+        let new_capacity = capacity
+            .checked_add(additional)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        let new_len = Catalog::space(new_capacity);
+        require!(
+            new_len - info.data_len() <= MAX_PERMITTED_DATA_INCREASE,
+            MarketplaceError::CatalogTooLarge
+        );
+        grow_account(&info, &ctx.accounts.seller, &ctx.accounts.system_program, new_len)
+    }
 
-        token::close_account(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                CloseAccount {
-                    account: ctx.accounts.vault.to_account_info(),
-                    destination: ctx.accounts.buyer.to_account_info(),
-                    authority: ctx.accounts.escrow.to_account_info(),
-                },
-                signer,
-            ),
-        )?;
+    // entries are appended; their index is the stable id buyers purchase by
+    pub fn add_catalog_entry(
+        ctx: Context<AddCatalogEntry>,
+        price: u64,
+        quantity: u32,
+        content_hash: [u8; 32],
+    ) -> Result<()> {
+        require!(price > 0, MarketplaceError::InvalidAmount);
+        ctx.accounts
+            .merchant
+            .check_can_list(&ctx.accounts.marketplace.bond, ctx.accounts.bond.as_deref())?;
 
-        let e = &mut ctx.accounts.escrow;
-        e.released = true;
+        let info = ctx.accounts.catalog.to_account_info();
+        let mut data = info.try_borrow_mut_data()?;
+        let (header, entries) = Catalog::split_mut(&mut data)?;
+        let index = header.entry_count;
+        let entry = entries
+            .get_mut(index as usize)
+            .ok_or(MarketplaceError::CatalogFull)?;
+        entry.price = price;
+        entry.quantity = quantity;
+        entry.revision = 0;
+        entry.active = 1;
+        entry.content_hash = content_hash;
+        header.entry_count = index + 1;
 
-        emit!(ServiceOrderCancelled {
-            marketplace: ctx.accounts.marketplace.key(),
-            escrow: e.key(),
-            buyer: e.buyer,
-            amount,
-            reference: e.reference,
+        emit!(CatalogEntryUpdated {
+            catalog: info.key(),
+            index,
+            price,
+            quantity,
+            active: true,
+            revision: 0,
+            content_hash,
         });
-
         Ok(())
     }
-}
-
 
-#[account]
-pub struct Marketplace {
+    pub fn update_catalog_entry(
+        ctx: Context<UpdateCatalogEntry>,
+        index: u32,
+        new_price: Option<u64>,
+        new_quantity: Option<u32>,
+        active: Option<bool>,
+        new_content_hash: Option<[u8; 32]>,
+    ) -> Result<()> {
+        let info = ctx.accounts.catalog.to_account_info();
+        let mut data = info.try_borrow_mut_data()?;
+        let (header, entries) = Catalog::split_mut(&mut data)?;
+        let entry = header.entry_mut(entries, index)?;
+
+        if let Some(p) = new_price {
+            require!(p > 0, MarketplaceError::InvalidAmount);
+            entry.price = p;
+        }
+        if let Some(q) = new_quantity {
+            entry.quantity = q;
+        }
+        if let Some(a) = active {
+            entry.active = a as u8;
+        }
+        if let Some(h) = new_content_hash {
+            entry.content_hash = h;
+        }
+        entry.revision = entry
+            .revision
+            .checked_add(1)
+            .ok_or(MarketplaceError::MathOverflow)?;
+
+        emit!(CatalogEntryUpdated {
+            catalog: info.key(),
+            index,
+            price: entry.price,
+            quantity: entry.quantity,
+            active: entry.active != 0,
+            revision: entry.revision,
+            content_hash: entry.content_hash,
+        });
+        Ok(())
+    }
+
+    // indices are stable ids, so the slot stays allocated and is never handed out again
+    pub fn remove_catalog_entry(ctx: Context<UpdateCatalogEntry>, index: u32) -> Result<()> {
+        let info = ctx.accounts.catalog.to_account_info();
+        let mut data = info.try_borrow_mut_data()?;
+        let (header, entries) = Catalog::split_mut(&mut data)?;
+        header.entry_mut(entries, index)?.remove()?;
+
+        emit!(CatalogEntryRemoved {
+            catalog: info.key(),
+            index,
+        });
+        Ok(())
+    }
+
+    // returns the rent of the header and every slot; goods orders settle at purchase, so
+    // nothing outstanding refers to the catalog
+    pub fn close_catalog(ctx: Context<CloseCatalog>) -> Result<()> {
+        let c = ctx.accounts.catalog.load()?;
+        emit!(CatalogClosed {
+            marketplace: c.marketplace,
+            catalog: ctx.accounts.catalog.key(),
+            seller: c.seller,
+            entry_count: c.entry_count,
+        });
+        Ok(())
+    }
+
+    // goods purchase of a catalog entry; settles like buy_now for a plain fixed-price listing.
+    // Entries have no sale window, wallet limit or allowlist, so drops belong on listings, and
+    // the buyer signs and pays from their own account and rent: no session keys or relayers
+    pub fn buy_catalog_entry(
+        ctx: Context<BuyCatalogEntry>,
+        index: u32,
+        quantity: u32,
+        reference: Pubkey,
+        expected_revision: Option<u32>,
+        max_total: Option<u64>,
+    ) -> Result<()> {
+        let reference_account = ctx
+            .remaining_accounts
+            .first()
+            .ok_or(MarketplaceError::MissingReference)?;
+        require!(reference_account.key() == reference, MarketplaceError::WrongReference);
+        require!(
+            ctx.accounts.buyer_profile.pending_discount == 0,
+            MarketplaceError::RedemptionUnsupported
+        );
+
+        let info = ctx.accounts.catalog.to_account_info();
+        let (seller, total_price) = {
+            let mut data = info.try_borrow_mut_data()?;
+            let (header, entries) = Catalog::split_mut(&mut data)?;
+            let entry = header.entry_mut(entries, index)?;
+            require!(entry.active != 0, MarketplaceError::ListingInactive);
+            require!(
+                quantity > 0 && quantity <= entry.quantity,
+                MarketplaceError::InvalidQuantity
+            );
+            if let Some(rev) = expected_revision {
+                require!(entry.revision == rev, MarketplaceError::ListingChanged);
+            }
+            let total = entry
+                .price
+                .checked_mul(quantity as u64)
+                .ok_or(MarketplaceError::MathOverflow)?;
+            if let Some(max) = max_total {
+                require!(total <= max, MarketplaceError::MaxTotalExceeded);
+            }
+
+            entry.quantity -= quantity;
+            if entry.quantity == 0 {
+                entry.active = 0;
+            }
+            (header.seller, total)
+        };
+
+        require!(
+            ctx.accounts.seller_ata.owner == seller
+                && ctx.accounts.seller_ata.mint == ctx.accounts.mint.key(),
+            MarketplaceError::InvalidAccount
+        );
+
+        settle_goods_order(
+            &ctx.accounts.token_program,
+            &ctx.accounts.buyer,
+            &ctx.accounts.buyer_ata,
+            &ctx.accounts.seller_ata,
+            &ctx.accounts.treasury_vault,
+            &mut ctx.accounts.treasury,
+            &mut ctx.accounts.marketplace,
+            &mut ctx.accounts.merchant,
+            total_price,
+            ctx.accounts.mint.decimals,
+        )?;
+        let p = &mut ctx.accounts.buyer_profile;
+        p.init_if_new(
+            ctx.accounts.marketplace.key(),
+            ctx.accounts.buyer.key(),
+            ctx.bumps.buyer_profile,
+        );
+        p.record_orders(1, false)?;
+        let points_earned = p.credit(
+            total_price,
+            ctx.accounts.mint.decimals,
+            ctx.accounts.marketplace.points_bps,
+        )?;
+        emit!(PointsUpdated {
+            marketplace: p.marketplace,
+            buyer: p.buyer,
+            earned: points_earned,
+            redeemed: 0,
+            balance: p.points,
+            reference,
+        });
+
+        emit!(CatalogOrderCompleted {
+            marketplace: ctx.accounts.marketplace.key(),
+            catalog: info.key(),
+            index,
+            buyer: ctx.accounts.buyer.key(),
+            seller,
+            mint: ctx.accounts.mint.key(),
+            quantity,
+            total_amount: total_price,
+            reference,
+        });
+        Ok(())
+    }
+
+    // Session keys: a buyer lets a temporary key sign purchases up to a spending cap.
+    // The session PDA becomes the token delegate of the buyer's account, which replaces
+    // any delegate approved before.
+    pub fn create_session_key(
+        ctx: Context<CreateSessionKey>,
+        session_signer: Pubkey,
+        spend_limit: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(spend_limit > 0, MarketplaceError::InvalidAmount);
+        require!(
+            expires_at > now && expires_at - now <= MAX_SESSION_SECS,
+            MarketplaceError::InvalidSession
+        );
+
+        let s = &mut ctx.accounts.session;
+        s.buyer = ctx.accounts.buyer.key();
+        s.signer = session_signer;
+        s.mint = ctx.accounts.buyer_ata.mint;
+        s.spend_limit = spend_limit;
+        s.spent = 0;
+        s.expires_at = expires_at;
+        s.rent_payer = ctx.accounts.payer.key();
+        s.bump = ctx.bumps.session;
+
+        token::approve(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Approve {
+                    to: ctx.accounts.buyer_ata.to_account_info(),
+                    delegate: ctx.accounts.session.to_account_info(),
+                    authority: ctx.accounts.buyer.to_account_info(),
+                },
+            ),
+            spend_limit,
+        )?;
+
+        emit!(SessionKeyCreated {
+            buyer: ctx.accounts.buyer.key(),
+            signer: session_signer,
+            mint: ctx.accounts.buyer_ata.mint,
+            spend_limit,
+            expires_at,
+        });
+        Ok(())
+    }
+
+    // session rent goes back to whoever paid it
+    pub fn revoke_session_key(ctx: Context<RevokeSessionKey>) -> Result<()> {
+        if ctx.accounts.buyer_ata.delegate == COption::Some(ctx.accounts.session.key()) {
+            token::revoke(CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Revoke {
+                    source: ctx.accounts.buyer_ata.to_account_info(),
+                    authority: ctx.accounts.buyer.to_account_info(),
+                },
+            ))?;
+        }
+
+        let s = &ctx.accounts.session;
+        emit!(SessionKeyRevoked {
+            buyer: s.buyer,
+            signer: s.signer,
+            spent: s.spent,
+        });
+        Ok(())
+    }
+
+    // Compressed listings (leaves of an SPL concurrent merkle tree, one tree per merchant)
+    // the tree account is allocated by the client, owned by the compression program
+    pub fn init_listing_tree(
+        ctx: Context<InitListingTree>,
+        max_depth: u32,
+        max_buffer_size: u32,
+    ) -> Result<()> {
+        let t = &mut ctx.accounts.listing_tree;
+        t.marketplace = ctx.accounts.marketplace.key();
+        t.merchant = ctx.accounts.merchant.key();
+        t.seller = ctx.accounts.owner.key();
+        t.merkle_tree = ctx.accounts.merkle_tree.key();
+        t.leaf_count = 0;
+        t.bump = ctx.bumps.listing_tree;
+
+        let mut data = CMT_INIT_EMPTY_MERKLE_TREE.to_vec();
+        data.extend_from_slice(&max_depth.to_le_bytes());
+        data.extend_from_slice(&max_buffer_size.to_le_bytes());
+        compression_cpi(
+            &ctx.accounts.merkle_tree,
+            &ctx.accounts.listing_tree,
+            &ctx.accounts.noop_program,
+            &[],
+            data,
+        )
+    }
+
+    pub fn create_compressed_listing(
+        ctx: Context<CreateCompressedListing>,
+        price: u64,
+        quantity: u32,
+        metadata_hash: [u8; 32],
+    ) -> Result<()> {
+        require!(price > 0, MarketplaceError::InvalidAmount);
+        ctx.accounts
+            .merchant
+            .check_can_list(&ctx.accounts.marketplace.bond, ctx.accounts.bond.as_deref())?;
+
+        let t = &mut ctx.accounts.listing_tree;
+        let listing = CompressedListing {
+            marketplace: t.marketplace,
+            listing_tree: t.key(),
+            seller: t.seller,
+            mint: ctx.accounts.mint.key(),
+            index: t.leaf_count as u32,
+            price,
+            quantity,
+            active: true,
+            metadata_hash,
+        };
+        t.leaf_count = t
+            .leaf_count
+            .checked_add(1)
+            .ok_or(MarketplaceError::MathOverflow)?;
+
+        let leaf = listing.leaf();
+        let mut data = CMT_APPEND.to_vec();
+        data.extend_from_slice(&leaf);
+        compression_cpi(
+            &ctx.accounts.merkle_tree,
+            &ctx.accounts.listing_tree,
+            &ctx.accounts.noop_program,
+            &[],
+            data,
+        )?;
+
+        emit!(CompressedListingUpdated { listing, leaf });
+        Ok(())
+    }
+
+    // remaining_accounts = proof nodes for `listing` under `root`
+    pub fn update_compressed_listing<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdateCompressedListing<'info>>,
+        root: [u8; 32],
+        listing: CompressedListing,
+        new_price: Option<u64>,
+        new_quantity: Option<u32>,
+        active: Option<bool>,
+    ) -> Result<()> {
+        require!(
+            listing.listing_tree == ctx.accounts.listing_tree.key(),
+            MarketplaceError::InvalidAccount
+        );
+
+        let mut updated = listing.clone();
+        if let Some(p) = new_price {
+            require!(p > 0, MarketplaceError::InvalidAmount);
+            updated.price = p;
+        }
+        if let Some(q) = new_quantity {
+            updated.quantity = q;
+        }
+        if let Some(a) = active {
+            updated.active = a;
+        }
+
+        let leaf = updated.leaf();
+        replace_compressed_leaf(
+            &ctx.accounts.merkle_tree,
+            &ctx.accounts.listing_tree,
+            &ctx.accounts.noop_program,
+            ctx.remaining_accounts,
+            root,
+            &listing,
+            leaf,
+        )?;
+
+        emit!(CompressedListingUpdated { listing: updated, leaf });
+        Ok(())
+    }
+
+    // the buyer supplies the current leaf data; the compression program checks it against the
+    // tree while swapping in the leaf with reduced stock. Like catalog entries, compressed
+    // listings carry no sale window or wallet limit and the buyer signs and pays directly.
+    // remaining_accounts = [reference, proof nodes...]
+    pub fn buy_compressed_listing<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BuyCompressedListing<'info>>,
+        root: [u8; 32],
+        listing: CompressedListing,
+        quantity: u32,
+        reference: Pubkey,
+        max_total: Option<u64>,
+    ) -> Result<()> {
+        let reference_account = ctx
+            .remaining_accounts
+            .first()
+            .ok_or(MarketplaceError::MissingReference)?;
+        require!(reference_account.key() == reference, MarketplaceError::WrongReference);
+        require!(
+            ctx.accounts.buyer_profile.pending_discount == 0,
+            MarketplaceError::RedemptionUnsupported
+        );
+
+        require!(
+            listing.listing_tree == ctx.accounts.listing_tree.key()
+                && listing.mint == ctx.accounts.mint.key(),
+            MarketplaceError::InvalidAccount
+        );
+        require!(listing.active, MarketplaceError::ListingInactive);
+        require!(
+            quantity > 0 && quantity <= listing.quantity,
+            MarketplaceError::InvalidQuantity
+        );
+        require!(
+            ctx.accounts.seller_ata.owner == listing.seller
+                && ctx.accounts.seller_ata.mint == listing.mint,
+            MarketplaceError::InvalidAccount
+        );
+
+        let total_price = listing
+            .price
+            .checked_mul(quantity as u64)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        if let Some(max) = max_total {
+            require!(total_price <= max, MarketplaceError::MaxTotalExceeded);
+        }
+
+        let mut updated = listing.clone();
+        updated.quantity -= quantity;
+        if updated.quantity == 0 {
+            updated.active = false;
+        }
+        let leaf = updated.leaf();
+        replace_compressed_leaf(
+            &ctx.accounts.merkle_tree,
+            &ctx.accounts.listing_tree,
+            &ctx.accounts.noop_program,
+            &ctx.remaining_accounts[1..],
+            root,
+            &listing,
+            leaf,
+        )?;
+
+        settle_goods_order(
+            &ctx.accounts.token_program,
+            &ctx.accounts.buyer,
+            &ctx.accounts.buyer_ata,
+            &ctx.accounts.seller_ata,
+            &ctx.accounts.treasury_vault,
+            &mut ctx.accounts.treasury,
+            &mut ctx.accounts.marketplace,
+            &mut ctx.accounts.merchant,
+            total_price,
+            ctx.accounts.mint.decimals,
+        )?;
+        let p = &mut ctx.accounts.buyer_profile;
+        p.init_if_new(
+            ctx.accounts.marketplace.key(),
+            ctx.accounts.buyer.key(),
+            ctx.bumps.buyer_profile,
+        );
+        p.record_orders(1, false)?;
+        let points_earned = p.credit(
+            total_price,
+            ctx.accounts.mint.decimals,
+            ctx.accounts.marketplace.points_bps,
+        )?;
+        emit!(PointsUpdated {
+            marketplace: p.marketplace,
+            buyer: p.buyer,
+            earned: points_earned,
+            redeemed: 0,
+            balance: p.points,
+            reference,
+        });
+
+        emit!(CompressedListingUpdated {
+            listing: updated,
+            leaf,
+        });
+        emit!(OrderCompleted {
+            marketplace: ctx.accounts.marketplace.key(),
+            listing: listing.asset_id(),
+            buyer: ctx.accounts.buyer.key(),
+            payer: ctx.accounts.buyer_ata.owner,
+            seller: listing.seller,
+            mint: listing.mint,
+            quantity,
+            total_amount: total_price,
+            reference,
+            quote: None,
+            variant: None,
+        });
+        Ok(())
+    }
+
+    // Services (escrow)
+    pub fn create_service_order(
+        ctx: Context<CreateServiceOrder>,
+        reference: Pubkey,
+        price_guard: Option<PriceGuard>,
+        expected_revision: Option<u32>,
+        max_total: Option<u64>,
+    ) -> Result<()> {
+        let l = &ctx.accounts.listing;
+        l.check_on_sale()?;
+        require!(l.is_service, MarketplaceError::WrongFlowForGoods);
+
+        // enforce reference presence in transaction metas for Solana Pay correlation
+        let reference_account = ctx
+            .remaining_accounts
+            .first()
+            .ok_or(MarketplaceError::MissingReference)?;
+        require!(reference_account.key() == reference, MarketplaceError::WrongReference);
+
+        let (total_price, quote) = l.settle_total(
+            l.price,
+            1,
+            ctx.accounts.price_feed.as_deref(),
+            ctx.accounts.marketplace.oracle_program,
+            price_guard,
+            ctx.accounts.mint.decimals,
+        )?;
+        l.check_buyer_view(expected_revision, total_price, max_total)?;
+
+        authorize_buyer(
+            &ctx.accounts.buyer,
+            &ctx.accounts.signer,
+            ctx.accounts.session.as_mut(),
+            &ctx.accounts.buyer_ata,
+            total_price,
+        )?;
+        pay_from_buyer(
+            &ctx.accounts.token_program,
+            &ctx.accounts.buyer_ata,
+            ctx.accounts.vault.to_account_info(),
+            &ctx.accounts.signer,
+            ctx.accounts.session.as_ref(),
+            total_price,
+        )?;
+
+        // Initialize escrow account state
+        let e = &mut ctx.accounts.escrow;
+        e.marketplace = ctx.accounts.marketplace.key();
+        e.listing = l.key();
+        e.seller = l.seller;
+        e.buyer = ctx.accounts.buyer.key();
+        e.mint = ctx.accounts.mint.key();
+        e.amount = total_price;
+        e.reference = reference;
+        e.released = false;
+        e.bump = ctx.bumps.escrow;
+        e.version = Escrow::VERSION;
+        e.rent_payer = ctx.accounts.payer.key();
+        e.funding_account = ctx.accounts.buyer_ata.key();
+
+        let l = &mut ctx.accounts.listing;
+        l.open_escrows = l
+            .open_escrows
+            .checked_add(1)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        ctx.accounts.marketplace.open_escrow()?;
+        ctx.accounts.treasury.lock_escrow(total_price)?;
+
+        // emit event so off-chain indexers immediately know escrow created
+        emit!(ServiceOrderCreated {
+            marketplace: ctx.accounts.marketplace.key(),
+            listing: l.key(),
+            buyer: e.buyer,
+            payer: ctx.accounts.buyer_ata.owner,
+            seller: e.seller,
+            mint: e.mint,
+            amount: e.amount,
+            reference,
+            escrow: ctx.accounts.escrow.key(),
+            quote,
+        });
+
+        Ok(())
+    }
+
+    // release funds from escrow to seller (can be called by buyer or marketplace authority via ctx enforcement)
+    pub fn release_service_order(ctx: Context<ReleaseServiceOrder>) -> Result<()> {
+        require!(
+            !ctx.accounts.escrow.released,
+            MarketplaceError::AlreadyReleased
+        );
+
+        let bump = ctx.accounts.escrow.bump;
+        let amount = ctx.accounts.escrow.amount;
+        let listing = ctx.accounts.escrow.listing;
+        let buyer_key = ctx.accounts.escrow.buyer;
+        let fee_bps = ctx.accounts.escrow.get_fee_bps(&ctx.accounts.marketplace)?;
+
+        let fee = amount
+            .checked_mul(fee_bps as u64)
+            .ok_or(MarketplaceError::MathOverflow)?
+            / 10_000;
+        let seller_amount = amount.checked_sub(fee).ok_or(MarketplaceError::MathOverflow)?;
+
+        let seeds: &[&[u8]] = &[
+            b"escrow",
+            listing.as_ref(),
+            buyer_key.as_ref(),
+            &[bump],
+        ];
+        let signer = &[seeds];
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.seller_ata.to_account_info(),
+                    authority: ctx.accounts.escrow.to_account_info(),
+                },
+                signer,
+            ),
+            seller_amount,
+        )?;
+
+        if fee > 0 {
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.vault.to_account_info(),
+                        to: ctx.accounts.treasury_vault.to_account_info(),
+                        authority: ctx.accounts.escrow.to_account_info(),
+                    },
+                    signer,
+                ),
+                fee,
+            )?;
+            ctx.accounts.treasury.record_fee(fee)?;
+        }
+
+        token::close_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                CloseAccount {
+                    account: ctx.accounts.vault.to_account_info(),
+                    destination: ctx.accounts.rent_payer.to_account_info(),
+                    authority: ctx.accounts.escrow.to_account_info(),
+                },
+                signer,
+            ),
+        )?;
+
+        ctx.accounts.listing.release_escrow();
+        ctx.accounts.marketplace.close_escrow();
+        ctx.accounts.marketplace.record_orders(1)?;
+        ctx.accounts.treasury.unlock_escrow(amount);
+        ctx.accounts.treasury.record_volume(amount)?;
+        ctx.accounts
+            .merchant
+            .record_sale(amount, ctx.accounts.mint.decimals)?;
+
+        let p = &mut ctx.accounts.buyer_profile;
+        p.init_if_new(ctx.accounts.marketplace.key(), buyer_key, ctx.bumps.buyer_profile);
+        p.record_orders(1, true)?;
+        let points_earned = p.credit(
+            amount,
+            ctx.accounts.mint.decimals,
+            ctx.accounts.marketplace.points_bps,
+        )?;
+        emit!(PointsUpdated {
+            marketplace: p.marketplace,
+            buyer: p.buyer,
+            earned: points_earned,
+            redeemed: 0,
+            balance: p.points,
+            reference: ctx.accounts.escrow.reference,
+        });
+
+        // the escrow is closed to the rent payer on exit, the receipt is the proof of the order
+        // that reviews and returns work from
+        let e = &mut ctx.accounts.escrow;
+        e.released = true;
+
+        let receipt = &mut ctx.accounts.receipt;
+        receipt.marketplace = e.marketplace;
+        receipt.listing = e.listing;
+        receipt.buyer = e.buyer;
+        receipt.seller = e.seller;
+        receipt.mint = e.mint;
+        receipt.quantity = 1;
+        receipt.variant = None;
+        receipt.amount = amount;
+        receipt.fee = fee;
+        receipt.points_redeemed = 0;
+        receipt.points_earned = points_earned;
+        receipt.timestamp = Clock::get()?.unix_timestamp;
+        receipt.reference = e.reference;
+        receipt.status = ReceiptStatus::Completed;
+        receipt.bump = ctx.bumps.receipt;
+        // whoever releases pays for the receipt, the escrow rent goes back to its own payer
+        receipt.rent_payer = ctx.accounts.payer.key();
+        receipt.funding_account = e.funding_account;
+        receipt.is_service = true;
+
+        emit!(ServiceOrderReleased {
+            marketplace: ctx.accounts.marketplace.key(),
+            escrow: e.key(),
+            buyer: e.buyer,
+            seller: e.seller,
+            mint: e.mint,
+            amount,
+            reference: e.reference,
+        });
+
+        Ok(())
+    }
+
+    // cancel/refund escrow back to buyer (can be called by marketplace authority or buyer depending on your policy)
+    pub fn cancel_service_order(ctx: Context<CancelServiceOrder>) -> Result<()> {
+        require!(
+            !ctx.accounts.escrow.released,
+            MarketplaceError::AlreadyReleased
+        );
+
+        let bump = ctx.accounts.escrow.bump;
+        let amount = ctx.accounts.escrow.amount;
+        let listing = ctx.accounts.escrow.listing;
+        let buyer_key = ctx.accounts.escrow.buyer;
+
+        let seeds: &[&[u8]] = &[
+            b"escrow",
+            listing.as_ref(),
+            buyer_key.as_ref(),
+            &[bump],
+        ];
+        let signer = &[seeds];
+
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.refund_ata.to_account_info(),
+                    authority: ctx.accounts.escrow.to_account_info(),
+                },
+                signer,
+            ),
+            amount,
+        )?;
+
+        token::close_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                CloseAccount {
+                    account: ctx.accounts.vault.to_account_info(),
+                    destination: ctx.accounts.rent_payer.to_account_info(),
+                    authority: ctx.accounts.escrow.to_account_info(),
+                },
+                signer,
+            ),
+        )?;
+
+        ctx.accounts.listing.release_escrow();
+        ctx.accounts.marketplace.close_escrow();
+        ctx.accounts.treasury.unlock_escrow(amount);
+
+        // escrow itself is closed to the rent payer on exit
+        let e = &ctx.accounts.escrow;
+
+        emit!(ServiceOrderCancelled {
+            marketplace: ctx.accounts.marketplace.key(),
+            escrow: e.key(),
+            buyer: e.buyer,
+            refund_account: ctx.accounts.refund_ata.key(),
+            amount,
+            reference: e.reference,
+        });
+
+        Ok(())
+    }
+
+    // escrows released before release closed them still block a new order for the listing
+    pub fn close_escrow(ctx: Context<CloseEscrow>) -> Result<()> {
+        require!(ctx.accounts.escrow.released, MarketplaceError::EscrowNotReleased);
+        Ok(())
+    }
+
+    // Reviews
+    // proof of purchase is the order's receipt, from buy_now or a released service order
+    pub fn submit_review(ctx: Context<SubmitReview>, rating: u8, content_hash: [u8; 32]) -> Result<()> {
+        require!((1..=5).contains(&rating), MarketplaceError::InvalidRating);
+        let receipt = &ctx.accounts.receipt;
+        require!(
+            receipt.status != ReceiptStatus::Refunded,
+            MarketplaceError::InvalidReceiptStatus
+        );
+
+        let r = &mut ctx.accounts.review;
+        r.listing = ctx.accounts.listing.key();
+        r.seller = receipt.seller;
+        r.reviewer = ctx.accounts.buyer.key();
+        r.reference = receipt.reference;
+        r.rating = rating;
+        r.content_hash = content_hash;
+        r.response_hash = None;
+        r.created_at = Clock::get()?.unix_timestamp;
+        r.bump = ctx.bumps.review;
+
+        ctx.accounts.listing.add_rating(rating)?;
+        ctx.accounts.merchant.add_rating(rating)?;
+
+        emit!(ReviewSubmitted {
+            review: r.key(),
+            listing: r.listing,
+            seller: r.seller,
+            reviewer: r.reviewer,
+            reference: r.reference,
+            rating,
+            content_hash,
+        });
+        Ok(())
+    }
+
+    // buyer reclaims rent of a goods receipt they no longer need
+    pub fn close_receipt(ctx: Context<CloseReceipt>) -> Result<()> {
+        require!(
+            ctx.accounts.receipt.status != ReceiptStatus::ReturnRequested,
+            MarketplaceError::ReturnPending
+        );
+        Ok(())
+    }
+
+    // Returns (goods bought via buy_now with a receipt)
+    pub fn request_return(ctx: Context<RequestReturn>, reason_hash: [u8; 32]) -> Result<()> {
+        let receipt = &mut ctx.accounts.receipt;
+        receipt.check_returnable()?;
+        require!(
+            receipt.status == ReceiptStatus::Completed,
+            MarketplaceError::InvalidReceiptStatus
+        );
+
+        let window = ctx.accounts.marketplace.return_window_secs;
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            window > 0 && now <= receipt.timestamp.saturating_add(window),
+            MarketplaceError::ReturnWindowClosed
+        );
+        receipt.status = ReceiptStatus::ReturnRequested;
+
+        let rr = &mut ctx.accounts.return_request;
+        rr.receipt = receipt.key();
+        rr.buyer = receipt.buyer;
+        rr.seller = receipt.seller;
+        rr.reason_hash = reason_hash;
+        rr.requested_at = now;
+        rr.bump = ctx.bumps.return_request;
+        Ok(())
+    }
+
+    // seller declines a return, the buyer gets the request's rent back
+    pub fn reject_return(ctx: Context<RejectReturn>) -> Result<()> {
+        let receipt = &mut ctx.accounts.receipt;
+        receipt.status = ReceiptStatus::ReturnRejected;
+
+        emit!(ReturnResolved {
+            receipt: receipt.key(),
+            buyer: receipt.buyer,
+            seller: receipt.seller,
+            rejected: true,
+            reference: receipt.reference,
+        });
+        Ok(())
+    }
+
+    // buyer drops their request; they may ask again while the window is open
+    pub fn withdraw_return(ctx: Context<WithdrawReturn>) -> Result<()> {
+        let receipt = &mut ctx.accounts.receipt;
+        receipt.status = ReceiptStatus::Completed;
+
+        emit!(ReturnResolved {
+            receipt: receipt.key(),
+            buyer: receipt.buyer,
+            seller: receipt.seller,
+            rejected: false,
+            reference: receipt.reference,
+        });
+        Ok(())
+    }
+
+    // seller approves a return and pays the buyer back from their own token account
+    pub fn refund_order(ctx: Context<RefundOrder>, restock: bool) -> Result<()> {
+        let receipt = &mut ctx.accounts.receipt;
+        receipt.check_returnable()?;
+        require!(
+            receipt.status == ReceiptStatus::ReturnRequested,
+            MarketplaceError::InvalidReceiptStatus
+        );
+
+        let refund_amount = match ctx.accounts.marketplace.fee_refund_policy {
+            FeeRefundPolicy::RetainFee => receipt
+                .amount
+                .checked_sub(receipt.fee)
+                .ok_or(MarketplaceError::MathOverflow)?,
+            FeeRefundPolicy::SellerCoversFee => receipt.amount,
+        };
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.seller_ata.to_account_info(),
+                    to: ctx.accounts.refund_ata.to_account_info(),
+                    authority: ctx.accounts.seller.to_account_info(),
+                },
+            ),
+            refund_amount,
+        )?;
+
+        let restocked = if restock {
+            let quantity = receipt.quantity;
+            let l = ctx
+                .accounts
+                .listing
+                .as_mut()
+                .ok_or(MarketplaceError::InvalidAccount)?;
+            require!(!l.is_service, MarketplaceError::WrongFlowForService);
+            let (old_quantity, old_active, new_quantity, new_active) =
+                match (receipt.variant, ctx.accounts.variant.as_mut()) {
+                    (Some(id), Some(v)) => {
+                        require!(v.id == id, MarketplaceError::WrongVariant);
+                        let (old_quantity, old_active) = (v.quantity, v.active);
+                        v.restock(quantity)?;
+                        (old_quantity, old_active, v.quantity, v.active)
+                    }
+                    (None, None) => {
+                        let (old_quantity, old_active) = (l.quantity, l.active);
+                        l.restock(quantity)?;
+                        (old_quantity, old_active, l.quantity, l.active)
+                    }
+                    _ => return err!(MarketplaceError::WrongVariant),
+                };
+            emit!(ListingStockChanged {
+                listing: l.key(),
+                variant: receipt.variant,
+                old_quantity,
+                new_quantity,
+                old_active,
+                new_active,
+                reserved: l.reserved,
+            });
+            quantity
+        } else {
+            0
+        };
+
+        // the sale no longer counts towards the merchant, and the buyer gets back the points
+        // spent on it while losing the ones it earned
+        let decimals = ctx.accounts.mint.decimals;
+        let discount = denormalize_amount(receipt.points_redeemed, decimals)?;
+        let sale_amount = receipt
+            .amount
+            .checked_add(discount)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        ctx.accounts.merchant.reverse_sale(sale_amount, decimals)?;
+        let mut points_clawed_back = 0;
+        if receipt.points_earned > 0 || receipt.points_redeemed > 0 {
+            let p = ctx
+                .accounts
+                .buyer_profile
+                .as_mut()
+                .ok_or(MarketplaceError::BuyerProfileRequired)?;
+            points_clawed_back = p.reverse_points(receipt.points_earned, receipt.points_redeemed)?;
+        }
+
+        receipt.status = ReceiptStatus::Refunded;
+
+        emit!(OrderRefunded {
+            marketplace: receipt.marketplace,
+            listing: receipt.listing,
+            receipt: receipt.key(),
+            buyer: receipt.buyer,
+            seller: receipt.seller,
+            mint: receipt.mint,
+            amount: refund_amount,
+            fee_retained: receipt.amount - refund_amount,
+            restocked,
+            points_clawed_back,
+            points_restored: receipt.points_redeemed,
+            reference: receipt.reference,
+        });
+        Ok(())
+    }
+
+    pub fn respond_to_review(ctx: Context<RespondToReview>, response_hash: [u8; 32]) -> Result<()> {
+        let r = &mut ctx.accounts.review;
+        r.response_hash = Some(response_hash);
+
+        emit!(ReviewResponded {
+            review: r.key(),
+            seller: r.seller,
+            response_hash,
+        });
+        Ok(())
+    }
+
+    // Badges
+    // the badge mint is created alongside the definition and can never be transferred
+    pub fn create_badge(
+        ctx: Context<CreateBadge>,
+        id: u16,
+        name: String,
+        kind: BadgeKind,
+        threshold: u64,
+    ) -> Result<()> {
+        require!(name.len() <= MAX_BADGE_NAME_LEN, MarketplaceError::NameTooLong);
+        require!(threshold > 0, MarketplaceError::InvalidAmount);
+
+        let badge_key = ctx.accounts.badge.key();
+        let seeds: &[&[u8]] = &[b"badge_mint", badge_key.as_ref(), &[ctx.bumps.badge_mint]];
+        create_soulbound_mint(
+            &ctx.accounts.authority,
+            &ctx.accounts.badge_mint,
+            &ctx.accounts.token_program,
+            &ctx.accounts.system_program,
+            &[seeds],
+            0,
+        )?;
+
+        let b = &mut ctx.accounts.badge;
+        b.marketplace = ctx.accounts.marketplace.key();
+        b.id = id;
+        b.name = name;
+        b.kind = kind;
+        b.threshold = threshold;
+        b.mint = ctx.accounts.badge_mint.key();
+        b.mint_bump = ctx.bumps.badge_mint;
+        b.active = true;
+        b.awarded_count = 0;
+        b.bump = ctx.bumps.badge;
+        Ok(())
+    }
+
+    // retired badges can no longer be awarded, existing awards are kept
+    pub fn set_badge_status(ctx: Context<SetBadgeStatus>, active: bool) -> Result<()> {
+        ctx.accounts.badge.active = active;
+        Ok(())
+    }
+
+    // permissionless: anyone may award a badge once the user's counter has reached the threshold
+    pub fn award_badge(ctx: Context<AwardBadge>) -> Result<()> {
+        let b = &ctx.accounts.badge;
+        require!(b.active, MarketplaceError::BadgeInactive);
+
+        let progress = match b.kind {
+            BadgeKind::BuyerOrders | BadgeKind::BuyerServices => {
+                let p = ctx
+                    .accounts
+                    .buyer_profile
+                    .as_ref()
+                    .ok_or(MarketplaceError::BadgeNotEarned)?;
+                if b.kind == BadgeKind::BuyerOrders {
+                    p.orders_completed as u64
+                } else {
+                    p.services_completed as u64
+                }
+            }
+            BadgeKind::MerchantSales | BadgeKind::MerchantVolume => {
+                let m = ctx
+                    .accounts
+                    .merchant
+                    .as_ref()
+                    .ok_or(MarketplaceError::BadgeNotEarned)?;
+                if b.kind == BadgeKind::MerchantSales {
+                    m.sales_count
+                } else {
+                    m.sales_volume
+                }
+            }
+        };
+        require!(progress >= b.threshold, MarketplaceError::BadgeNotEarned);
+
+        let badge_key = b.key();
+        let seeds: &[&[u8]] = &[b"badge_mint", badge_key.as_ref(), &[b.mint_bump]];
+        token_2022::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_2022::MintTo {
+                    mint: ctx.accounts.badge_mint.to_account_info(),
+                    to: ctx.accounts.badge_account.to_account_info(),
+                    authority: ctx.accounts.badge_mint.to_account_info(),
+                },
+                &[seeds],
+            ),
+            1,
+        )?;
+
+        let a = &mut ctx.accounts.badge_award;
+        a.badge = badge_key;
+        a.user = ctx.accounts.user.key();
+        a.awarded_at = Clock::get()?.unix_timestamp;
+        a.bump = ctx.bumps.badge_award;
+
+        let b = &mut ctx.accounts.badge;
+        b.awarded_count = b
+            .awarded_count
+            .checked_add(1)
+            .ok_or(MarketplaceError::MathOverflow)?;
+
+        emit!(BadgeAwarded {
+            marketplace: b.marketplace,
+            badge: badge_key,
+            id: b.id,
+            user: a.user,
+            awarded_at: a.awarded_at,
+        });
+        Ok(())
+    }
+
+    // Loyalty points
+    // stage points to be taken off the next buy_now; setting a lower value returns them.
+    // Carts, catalog and compressed purchases refuse to run while points are staged, and
+    // service orders leave them staged
+    pub fn redeem_points(ctx: Context<RedeemPoints>, points: u64) -> Result<()> {
+        let p = &mut ctx.accounts.buyer_profile;
+        let available = p
+            .points
+            .checked_add(p.pending_discount)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        require!(points <= available, MarketplaceError::InsufficientPoints);
+        p.pending_discount = points;
+        p.points = available - points;
+        Ok(())
+    }
+
+    // non-transferable Token-2022 mint mirroring point balances in wallets; the mint is its own authority
+    pub fn init_points_mint(ctx: Context<InitPointsMint>) -> Result<()> {
+        let marketplace_key = ctx.accounts.marketplace.key();
+        let seeds: &[&[u8]] = &[
+            b"points_mint",
+            marketplace_key.as_ref(),
+            &[ctx.bumps.points_mint],
+        ];
+        create_soulbound_mint(
+            &ctx.accounts.authority,
+            &ctx.accounts.points_mint,
+            &ctx.accounts.token_program,
+            &ctx.accounts.system_program,
+            &[seeds],
+            // one token per point
+            NORMALIZED_DECIMALS,
+        )
+    }
+
+    // mints or burns so the buyer's points token balance matches their spendable points
+    pub fn sync_points(ctx: Context<SyncPoints>) -> Result<()> {
+        let marketplace_key = ctx.accounts.marketplace.key();
+        let seeds: &[&[u8]] = &[
+            b"points_mint",
+            marketplace_key.as_ref(),
+            &[ctx.bumps.points_mint],
+        ];
+        let signer = &[seeds];
+
+        // the balance actually held is what gets corrected, so tokens the buyer burned
+        // themselves never make the sync burn more than the account holds
+        let held = ctx.accounts.points_account.amount;
+        let p = &mut ctx.accounts.buyer_profile;
+        if p.points > held {
+            token_2022::mint_to(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_2022::MintTo {
+                        mint: ctx.accounts.points_mint.to_account_info(),
+                        to: ctx.accounts.points_account.to_account_info(),
+                        authority: ctx.accounts.points_mint.to_account_info(),
+                    },
+                    signer,
+                ),
+                p.points - held,
+            )?;
+        } else if p.points < held {
+            token_2022::burn(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_2022::Burn {
+                        mint: ctx.accounts.points_mint.to_account_info(),
+                        from: ctx.accounts.points_account.to_account_info(),
+                        authority: ctx.accounts.buyer.to_account_info(),
+                    },
+                ),
+                held - p.points,
+            )?;
+        }
+        p.minted = p.points;
+        Ok(())
+    }
+
+    // Migrations
+    // permissionless: upgrading only changes the layout, the payer covers any extra rent
+    pub fn migrate_marketplace(ctx: Context<MigrateAccount>) -> Result<()> {
+        let info = ctx.accounts.account.to_account_info();
+        if info.data_len() == 8 + Marketplace::V1_SIZE {
+            return grow_in_place(
+                &info,
+                &ctx.accounts.payer,
+                &ctx.accounts.system_program,
+                8 + Marketplace::SIZE,
+                |mp: &mut Marketplace| mp.version = Marketplace::VERSION,
+            );
+        }
+        check_legacy_len(info.data_len(), 8 + MarketplaceV0::SIZE, 8 + Marketplace::SIZE)?;
+        let old: MarketplaceV0 = read_legacy(&info, Marketplace::DISCRIMINATOR)?;
+        write_migrated(
+            &info,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            8 + Marketplace::SIZE,
+            &old.upgrade(),
+        )
+    }
+
+    pub fn migrate_merchant(ctx: Context<MigrateAccount>) -> Result<()> {
+        let info = ctx.accounts.account.to_account_info();
+        check_legacy_len(info.data_len(), 8 + MerchantV0::SIZE, 8 + Merchant::SIZE)?;
+        let old: MerchantV0 = read_legacy(&info, Merchant::DISCRIMINATOR)?;
+        write_migrated(
+            &info,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            8 + Merchant::SIZE,
+            &old.upgrade(),
+        )
+    }
+
+    pub fn migrate_listing(ctx: Context<MigrateAccount>) -> Result<()> {
+        let info = ctx.accounts.account.to_account_info();
+        // current listings grow with their metadata, so Listing::SIZE is only a lower bound
+        check_legacy_len(info.data_len(), 8 + ListingV0::SIZE, 8 + Listing::SIZE)?;
+        let old: ListingV0 = read_legacy(&info, Listing::DISCRIMINATOR)?;
+        let listing = old.upgrade();
+        write_migrated(
+            &info,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            8 + Listing::SIZE + listing.metadata.space(),
+            &listing,
+        )
+    }
+
+    pub fn migrate_escrow(ctx: Context<MigrateAccount>) -> Result<()> {
+        let info = ctx.accounts.account.to_account_info();
+        if info.data_len() == 8 + Escrow::V1_SIZE {
+            return grow_in_place(
+                &info,
+                &ctx.accounts.payer,
+                &ctx.accounts.system_program,
+                8 + Escrow::SIZE,
+                |e: &mut Escrow| e.version = Escrow::VERSION,
+            );
+        }
+        check_legacy_len(info.data_len(), 8 + EscrowV0::SIZE, 8 + Escrow::SIZE)?;
+        let old: EscrowV0 = read_legacy(&info, Escrow::DISCRIMINATOR)?;
+        write_migrated(
+            &info,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            8 + Escrow::SIZE,
+            &old.upgrade(),
+        )
+    }
+}
+
+
+#[account]
+pub struct Marketplace {
+    pub id: u16,
+    pub metadata: MarketplaceMetadata,
+    pub authority: Pubkey,
+    pub fee_bps: u16,
+    pub bump: u8,
+    // 0 disables returns
+    pub return_window_secs: i64,
+    pub fee_refund_policy: FeeRefundPolicy,
+    // may slash merchant bonds
+    pub arbiter: Pubkey,
+    pub bond: BondConfig,
+    // may withdraw fees and configure fee splits
+    pub treasury_authority: Pubkey,
+    // aggregates across all mints; per-mint volume lives on the Treasury
+    pub merchant_count: u32,
+    pub order_count: u64,
+    pub open_escrow_count: u32,
+    // loyalty points earned per spend, 0 disables earning
+    pub points_bps: u16,
+    pub version: u8,
+    // program that must own listing price feeds; default until the authority configures one
+    pub oracle_program: Pubkey,
+    pub padding: [u8; ACCOUNT_PADDING_LEN],
+}
+impl Marketplace {
+    // version 2 grew the account so oracle_program no longer takes up the padding
+    pub const VERSION: u8 = 2;
+    pub const SIZE: usize = 2
+        + MarketplaceMetadata::SIZE
+        + 32 + 2 + 1 + 8 + 1 + 32 + BondConfig::SIZE + 32 + 4 + 8 + 4 + 2
+        + 1 + 32 + ACCOUNT_PADDING_LEN;
+    // version 1 kept oracle_program in the first half of its padding
+    pub const V1_SIZE: usize = Marketplace::SIZE - 32;
+
+    pub fn record_orders(&mut self, count: u64) -> Result<()> {
+        self.order_count = self
+            .order_count
+            .checked_add(count)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn open_escrow(&mut self) -> Result<()> {
+        self.open_escrow_count = self
+            .open_escrow_count
+            .checked_add(1)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        Ok(())
+    }
+
+    // escrows opened before the counter existed can't push it below zero
+    pub fn close_escrow(&mut self) {
+        self.open_escrow_count = self.open_escrow_count.saturating_sub(1);
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MarketplaceMetadata {
+    pub name: String,
+    // off-chain JSON with branding, region, etc.; empty for none
+    pub uri: String,
+    // mint storefronts price in by default, informational for clients
+    pub default_mint: Pubkey,
+}
+impl MarketplaceMetadata {
+    pub const SIZE: usize = (4 + MAX_MARKETPLACE_NAME_LEN) + (4 + MAX_METADATA_URI_LEN) + 32;
+
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.name.len() <= MAX_MARKETPLACE_NAME_LEN,
+            MarketplaceError::NameTooLong
+        );
+        require!(
+            !self.name.trim().is_empty() && !self.name.chars().any(char::is_control),
+            MarketplaceError::InvalidName
+        );
+        require!(
+            self.uri.len() <= MAX_METADATA_URI_LEN,
+            MarketplaceError::MetadataUriTooLong
+        );
+        if !self.uri.is_empty() {
+            Listing::validate_url(&self.uri)?;
+        }
+        Ok(())
+    }
+}
+
+// min_amount == 0 means unverified merchants list without a bond
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct BondConfig {
+    pub mint: Pubkey,
+    pub min_amount: u64,
+    pub cooldown_secs: i64,
+}
+impl BondConfig {
+    pub const SIZE: usize = 32 + 8 + 8;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum FeeRefundPolicy {
+    // marketplace keeps its fee, the buyer gets back what the seller received
+    RetainFee,
+    // seller refunds the full price including the fee
+    SellerCoversFee,
+}
+
+#[account]
+pub struct Merchant {
+    pub marketplace: Pubkey,
+    pub owner: Pubkey,
+    pub verified: bool,
+    pub bump: u8,
+    pub next_nonce: u64,
+    pub rating_sum: u64,
+    pub rating_count: u32,
+    // completed goods orders and released service orders; volume is in NORMALIZED_DECIMALS
+    // units so sales in different mints add up
+    pub sales_count: u64,
+    pub sales_volume: u64,
+    pub version: u8,
+    pub padding: [u8; ACCOUNT_PADDING_LEN],
+}
+impl Merchant {
+    pub const SIZE: usize = 32 + 32 + 1 + 1 + 8 + 8 + 4 + 8 + 8 + 1 + ACCOUNT_PADDING_LEN;
+
+    // `amount` is in base units of a mint with `decimals`
+    pub fn record_sale(&mut self, amount: u64, decimals: u8) -> Result<()> {
+        self.sales_count = self
+            .sales_count
+            .checked_add(1)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        self.sales_volume = self
+            .sales_volume
+            .checked_add(normalize_amount(amount, decimals)?)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        Ok(())
+    }
+
+    // only the subtraction saturates, for sales recorded before the counters existed
+    pub fn reverse_sale(&mut self, amount: u64, decimals: u8) -> Result<()> {
+        let amount = normalize_amount(amount, decimals)?;
+        self.sales_count = self.sales_count.saturating_sub(1);
+        self.sales_volume = self.sales_volume.saturating_sub(amount);
+        Ok(())
+    }
+
+    pub fn add_rating(&mut self, rating: u8) -> Result<()> {
+        self.rating_sum = self
+            .rating_sum
+            .checked_add(rating as u64)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        self.rating_count = self
+            .rating_count
+            .checked_add(1)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        Ok(())
+    }
+
+    // unverified merchants need an active bond once the marketplace sets a minimum
+    pub fn check_can_list(&self, bond_cfg: &BondConfig, bond: Option<&MerchantBond>) -> Result<()> {
+        if !self.verified && bond_cfg.min_amount > 0 {
+            let bond = bond.ok_or(MarketplaceError::BondRequired)?;
+            require!(
+                bond.mint == bond_cfg.mint
+                    && bond.unlock_at.is_none()
+                    && bond.amount >= bond_cfg.min_amount,
+                MarketplaceError::BondRequired
+            );
+        }
+        Ok(())
+    }
+}
+
+// fees for one mint accumulate in the ATA owned by this PDA
+#[account]
+pub struct Treasury {
+    pub marketplace: Pubkey,
+    pub mint: Pubkey,
+    pub total_collected: u64,
+    pub total_withdrawn: u64,
+    pub total_distributed: u64,
+    pub splits: Vec<FeeSplit>,
+    pub bump: u8,
+    // gross settled sales and funds currently locked in service escrows for this mint
+    pub volume: u64,
+    pub open_escrow_amount: u64,
+}
+impl Treasury {
+    pub const SIZE: usize =
+        32 + 32 + 8 + 8 + 8 + (4 + MAX_FEE_SPLITS * FeeSplit::SIZE) + 1 + 8 + 8;
+
+    pub fn record_volume(&mut self, amount: u64) -> Result<()> {
+        self.volume = self
+            .volume
+            .checked_add(amount)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn lock_escrow(&mut self, amount: u64) -> Result<()> {
+        self.open_escrow_amount = self
+            .open_escrow_amount
+            .checked_add(amount)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        Ok(())
+    }
+
+    // saturating for the same reason as Marketplace::close_escrow
+    pub fn unlock_escrow(&mut self, amount: u64) {
+        self.open_escrow_amount = self.open_escrow_amount.saturating_sub(amount);
+    }
+
+    pub fn record_fee(&mut self, fee: u64) -> Result<()> {
+        self.total_collected = self
+            .total_collected
+            .checked_add(fee)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct FeeSplit {
+    // wallet that owns the receiving token account
+    pub recipient: Pubkey,
+    pub bps: u16,
+}
+impl FeeSplit {
+    pub const SIZE: usize = 32 + 2;
+
+    // an empty list turns distribution off, otherwise the shares must add up to the whole vault
+    pub fn validate(splits: &[FeeSplit]) -> Result<()> {
+        require!(splits.len() <= MAX_FEE_SPLITS, MarketplaceError::InvalidFeeSplits);
+        if !splits.is_empty() {
+            let total: u32 = splits.iter().map(|s| s.bps as u32).sum();
+            require!(total == 10_000, MarketplaceError::InvalidFeeSplits);
+        }
+        Ok(())
+    }
+
+    // rounds down, the dust stays in the vault for the next distribution
+    pub fn share_of(&self, balance: u64) -> u64 {
+        ((balance as u128) * (self.bps as u128) / 10_000) as u64
+    }
+}
+
+#[account]
+pub struct MerchantBond {
+    pub merchant: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    // set by request_bond_withdrawal and cleared by the next deposit; withdrawals open once it passes
+    pub unlock_at: Option<i64>,
+    pub bump: u8,
+}
+impl MerchantBond {
+    pub const SIZE: usize = 32 + 32 + 32 + 8 + 9 + 1;
+
+    pub fn check_withdrawable(&self, now: i64, amount: u64) -> Result<()> {
+        let unlock_at = self.unlock_at.ok_or(MarketplaceError::BondLocked)?;
+        require!(now >= unlock_at, MarketplaceError::BondLocked);
+        require!(amount > 0 && amount <= self.amount, MarketplaceError::InvalidAmount);
+        Ok(())
+    }
+
+    // a bond keeps its mint while it holds funds, even after the marketplace switches bond
+    // mints; new and emptied bonds take the marketplace's current one
+    pub fn deposit_mint(&self, bond_cfg: &BondConfig) -> Pubkey {
+        if self.amount > 0 {
+            self.mint
+        } else {
+            bond_cfg.mint
+        }
+    }
+}
+
+#[account]
+pub struct Listing {
+    pub marketplace: Pubkey,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub price: u64,
+    pub quantity: u32,
+    pub is_service: bool,
+    pub active: bool,
+    pub bump: u8,
+    pub name: String,
+    pub image_url: String,
+    pub nonce: u64,
+    pub open_escrows: u32,
+    pub price_feed: Option<Pubkey>,
+    // bumped on every seller edit so buyers can pin the version they were shown
+    pub revision: u32,
+    // units held by open reservations, already taken out of `quantity`
+    pub reserved: u32,
+    pub variant_count: u16,
+    // variable length, account space is SIZE + metadata.space()
+    pub metadata: ListingMetadata,
+    pub sale_window: SaleWindow,
+    pub limits: PurchaseLimits,
+    pub rating_sum: u64,
+    pub rating_count: u32,
+    pub version: u8,
+    pub padding: [u8; ACCOUNT_PADDING_LEN],
+}
+impl Listing {
+    // the leading 8 is carried over from version 0, which counted the discriminator twice
+    pub const SIZE: usize = 8 + 32 + 32 + 32 + 8 + 4 + 1 + 1 + 1
+        + (4 + MAX_NAME_LEN)
+        + (4 + MAX_IMAGE_URL_LEN)
+        + 8 + 4 + 33 + 4 + 4 + 2
+        + SaleWindow::SIZE
+        + PurchaseLimits::SIZE
+        + 8 + 4
+        + 1 + ACCOUNT_PADDING_LEN;
+}
+
+// unix timestamps; either bound may be left open
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct SaleWindow {
+    pub sale_start: Option<i64>,
+    pub sale_end: Option<i64>,
+}
+impl SaleWindow {
+    pub const SIZE: usize = 9 + 9;
+
+    pub fn validate(&self) -> Result<()> {
+        if let (Some(start), Some(end)) = (self.sale_start, self.sale_end) {
+            require!(start < end, MarketplaceError::InvalidSaleWindow);
+        }
+        Ok(())
+    }
+
+    pub fn is_unbounded(&self) -> bool {
+        self.sale_start.is_none() && self.sale_end.is_none()
+    }
+
+    // start is inclusive, end exclusive
+    pub fn check(&self, now: i64) -> Result<()> {
+        if let Some(start) = self.sale_start {
+            require!(now >= start, MarketplaceError::SaleNotStarted);
+        }
+        if let Some(end) = self.sale_end {
+            require!(now < end, MarketplaceError::SaleEnded);
+        }
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct ListingMetadata {
+    pub category: u16,
+    pub tags: Vec<String>,
+    // off-chain JSON document and the sha256 of its contents
+    pub uri: String,
+    pub content_hash: [u8; 32],
+    pub images: Vec<String>,
+}
+impl ListingMetadata {
+    pub fn space(&self) -> usize {
+        2 + 4
+            + self.tags.iter().map(|t| 4 + t.len()).sum::<usize>()
+            + 4 + self.uri.len()
+            + 32
+            + 4
+            + self.images.iter().map(|i| 4 + i.len()).sum::<usize>()
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        require!(self.tags.len() <= MAX_TAGS, MarketplaceError::TooManyTags);
+        require!(
+            self.tags.iter().all(|t| !t.is_empty() && t.len() <= MAX_TAG_LEN),
+            MarketplaceError::InvalidTag
+        );
+        require!(
+            self.uri.len() <= MAX_METADATA_URI_LEN,
+            MarketplaceError::MetadataUriTooLong
+        );
+        if !self.uri.is_empty() {
+            Listing::validate_url(&self.uri)?;
+        }
+        require!(
+            self.images.len() <= MAX_EXTRA_IMAGES,
+            MarketplaceError::TooManyImages
+        );
+        for image in &self.images {
+            require!(!image.is_empty(), MarketplaceError::UnsupportedUrl);
+            Listing::validate_image_url(image)?;
+        }
+        Ok(())
+    }
+}
+
+#[account]
+pub struct Escrow {
+    pub marketplace: Pubkey,
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub buyer: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub reference: Pubkey,
+    pub released: bool,
+    pub bump: u8,
+    pub version: u8,
+    // funded the escrow and vault rent; default for escrows opened before it was recorded
+    pub rent_payer: Pubkey,
+    // token account the order was paid from, refunded on cancel
+    pub funding_account: Pubkey,
+    pub padding: [u8; ACCOUNT_PADDING_LEN],
+}
+impl Escrow {
+    // version 2 grew the account so rent_payer and funding_account no longer take up the padding
+    pub const VERSION: u8 = 2;
+    pub const SIZE: usize =
+        32 + 32 + 32 + 32 + 32 + 8 + 32 + 1 + 1 + 1 + 32 + 32 + ACCOUNT_PADDING_LEN;
+    // version 1 had the same fields with no padding left over
+    pub const V1_SIZE: usize = Escrow::SIZE - ACCOUNT_PADDING_LEN;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct PurchaseLimits {
+    pub max_per_wallet: Option<u32>,
+    // sha256 merkle root over sha256(wallet) leaves, pairs hashed in sorted order
+    pub allowlist_root: Option<[u8; 32]>,
+}
+impl PurchaseLimits {
+    pub const SIZE: usize = 5 + 33;
+
+    pub fn is_open(&self) -> bool {
+        self.max_per_wallet.is_none() && self.allowlist_root.is_none()
+    }
+
+    pub fn is_allowlisted(root: [u8; 32], proof: &[[u8; 32]], wallet: &Pubkey) -> bool {
+        let mut node = hashv(&[wallet.as_ref()]).to_bytes();
+        for sibling in proof {
+            node = if node <= *sibling {
+                hashv(&[&node, sibling]).to_bytes()
+            } else {
+                hashv(&[sibling, &node]).to_bytes()
+            };
+        }
+        node == root
+    }
+
+    // presale listings only sell, or hold stock, for wallets in the merkle tree
+    pub fn check_allowlist(&self, proof: Option<&[[u8; 32]]>, wallet: &Pubkey) -> Result<()> {
+        if let Some(root) = self.allowlist_root {
+            let proof = proof.ok_or(MarketplaceError::NotAllowlisted)?;
+            require!(
+                PurchaseLimits::is_allowlisted(root, proof, wallet),
+                MarketplaceError::NotAllowlisted
+            );
+        }
+        Ok(())
+    }
+}
+
+// per (listing, buyer) running total, only needed for listings with a wallet cap
+#[account]
+pub struct PurchaseRecord {
+    pub listing: Pubkey,
+    pub buyer: Pubkey,
+    pub purchased: u32,
+    pub bump: u8,
+}
+impl PurchaseRecord {
+    pub const SIZE: usize = 32 + 32 + 4 + 1;
+}
+
+#[account]
+pub struct Variant {
+    pub listing: Pubkey,
+    pub id: u16,
+    pub price: u64,
+    pub quantity: u32,
+    pub active: bool,
+    pub bump: u8,
+}
+impl Variant {
+    pub const SIZE: usize = 32 + 2 + 8 + 4 + 1 + 1;
+}
+
+// on-chain proof of a purchase: optional in buy_now, keyed by the Solana Pay reference, and
+// always written when a service order is released, keyed by its escrow and reference
+#[account]
+pub struct Receipt {
+    pub marketplace: Pubkey,
+    pub listing: Pubkey,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub quantity: u32,
+    pub variant: Option<u16>,
+    pub amount: u64,
+    pub fee: u64,
+    // loyalty points spent on and earned by the order, reversed by a refund
+    pub points_redeemed: u64,
+    pub points_earned: u64,
+    pub timestamp: i64,
+    pub reference: Pubkey,
+    pub status: ReceiptStatus,
+    pub bump: u8,
+    // paid the receipt rent and gets it back on close
+    pub rent_payer: Pubkey,
+    // token account the order was paid from, refunded on return
+    pub funding_account: Pubkey,
+    // written by release_service_order, after the buyer or the marketplace accepted the work
+    pub is_service: bool,
+}
+impl Receipt {
+    pub const SIZE: usize =
+        32 + 32 + 32 + 32 + 32 + 4 + 3 + 8 + 8 + 8 + 8 + 8 + 32 + 1 + 1 + 32 + 32 + 1;
+
+    // only goods go through returns; disputes over a service are the escrow's job
+    pub fn check_returnable(&self) -> Result<()> {
+        require!(!self.is_service, MarketplaceError::WrongFlowForService);
+        Ok(())
+    }
+
+    // receipts written before the funding account was recorded refund to any buyer account
+    pub fn is_refund_account(&self, account: Pubkey, owner: Pubkey) -> bool {
+        if self.funding_account == Pubkey::default() {
+            owner == self.buyer
+        } else {
+            account == self.funding_account
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ReceiptStatus {
+    Completed,
+    ReturnRequested,
+    Refunded,
+    // the seller declined; the buyer can still take it to the marketplace arbiter
+    ReturnRejected,
+}
+
+#[account]
+pub struct ReturnRequest {
+    pub receipt: Pubkey,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub reason_hash: [u8; 32],
+    pub requested_at: i64,
+    pub bump: u8,
+}
+impl ReturnRequest {
+    pub const SIZE: usize = 32 + 32 + 32 + 32 + 8 + 1;
+}
+
+// one per order, keyed by the order's receipt
+#[account]
+pub struct Review {
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub reviewer: Pubkey,
+    pub reference: Pubkey,
+    pub rating: u8,
+    pub content_hash: [u8; 32],
+    pub response_hash: Option<[u8; 32]>,
+    pub created_at: i64,
+    pub bump: u8,
+}
+impl Review {
+    pub const SIZE: usize = 32 + 32 + 32 + 32 + 1 + 32 + 33 + 8 + 1;
+}
+
+// Fixed-size listings for merchants with large inventories. The header is followed by
+// `capacity` CatalogEntry slots filling the rest of the account, read without deserializing.
+// An entry is 56 bytes against ~670 for a Listing with empty metadata (see tests/catalog.rs).
+#[account(zero_copy)]
+pub struct Catalog {
+    pub marketplace: Pubkey,
+    pub merchant: Pubkey,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub entry_count: u32,
+    pub bump: u8,
+    pub version: u8,
+    pub base_uri_len: u8,
+    pub padding: [u8; 1],
+    // entry metadata lives off-chain at `{base_uri}/{index}.json`, pinned by the entry's content_hash
+    pub base_uri: [u8; MAX_CATALOG_URI_LEN],
+}
+impl Catalog {
+    pub const HEADER_LEN: usize = 8 + std::mem::size_of::<Catalog>();
+    pub const ENTRY_LEN: usize = std::mem::size_of::<CatalogEntry>();
+
+    pub fn space(capacity: u32) -> usize {
+        Self::HEADER_LEN + capacity as usize * Self::ENTRY_LEN
+    }
+
+    pub fn capacity(data_len: usize) -> u32 {
+        (data_len.saturating_sub(Self::HEADER_LEN) / Self::ENTRY_LEN) as u32
+    }
+
+    // header and entry slots of a catalog account's data, discriminator already checked by the loader
+    pub fn split_mut(data: &mut [u8]) -> Result<(&mut Catalog, &mut [CatalogEntry])> {
+        let capacity = Self::capacity(data.len()) as usize;
+        let (header, entries) = data[8..Self::space(capacity as u32)]
+            .split_at_mut(std::mem::size_of::<Catalog>());
+        let header = bytemuck::try_from_bytes_mut::<Catalog>(header)
+            .map_err(|_| error!(MarketplaceError::InvalidAccount))?;
+        let entries = bytemuck::try_cast_slice_mut::<u8, CatalogEntry>(entries)
+            .map_err(|_| error!(MarketplaceError::InvalidAccount))?;
+        Ok((header, entries))
+    }
+
+    // an added entry that has not been removed
+    pub fn entry_mut<'a>(
+        &self,
+        entries: &'a mut [CatalogEntry],
+        index: u32,
+    ) -> Result<&'a mut CatalogEntry> {
+        require!(index < self.entry_count, MarketplaceError::InvalidCatalogEntry);
+        let entry = entries
+            .get_mut(index as usize)
+            .ok_or(MarketplaceError::InvalidCatalogEntry)?;
+        require!(entry.removed == 0, MarketplaceError::InvalidCatalogEntry);
+        Ok(entry)
+    }
+}
+
+#[zero_copy]
+pub struct CatalogEntry {
+    pub price: u64,
+    pub quantity: u32,
+    pub revision: u32,
+    pub active: u8,
+    // removed entries can't be updated or bought, unlike inactive ones
+    pub removed: u8,
+    pub padding: [u8; 6],
+    pub content_hash: [u8; 32],
+}
+impl CatalogEntry {
+    // the revision bump fails purchases pinned to the entry as it was
+    pub fn remove(&mut self) -> Result<()> {
+        self.revision = self
+            .revision
+            .checked_add(1)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        self.price = 0;
+        self.quantity = 0;
+        self.active = 0;
+        self.removed = 1;
+        self.content_hash = [0; 32];
+        Ok(())
+    }
+}
+
+// Temporary signing key for a buyer; spends are capped here and by the token approval
+#[account]
+pub struct SessionKey {
+    pub buyer: Pubkey,
+    pub signer: Pubkey,
+    pub mint: Pubkey,
+    pub spend_limit: u64,
+    pub spent: u64,
+    pub expires_at: i64,
+    pub rent_payer: Pubkey,
+    pub bump: u8,
+}
+impl SessionKey {
+    pub const SIZE: usize = 32 + 32 + 32 + 8 + 8 + 8 + 32 + 1;
+
+    // counts `amount` against the cap if the session is live and belongs to this buyer and signer
+    pub fn authorize_spend(
+        &mut self,
+        buyer: Pubkey,
+        signer: Pubkey,
+        mint: Pubkey,
+        now: i64,
+        amount: u64,
+    ) -> Result<()> {
+        require!(
+            self.buyer == buyer && self.signer == signer && self.mint == mint && now < self.expires_at,
+            MarketplaceError::InvalidSession
+        );
+        let spent = self
+            .spent
+            .checked_add(amount)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        require!(spent <= self.spend_limit, MarketplaceError::SessionLimitExceeded);
+        self.spent = spent;
+        Ok(())
+    }
+}
+
+// Per-merchant tree of compressed listings; also the tree authority in compression CPIs
+#[account]
+pub struct ListingTree {
+    pub marketplace: Pubkey,
+    pub merchant: Pubkey,
+    pub seller: Pubkey,
+    pub merkle_tree: Pubkey,
+    // next leaf index
+    pub leaf_count: u64,
+    pub bump: u8,
+}
+impl ListingTree {
+    pub const SIZE: usize = 32 + 32 + 32 + 32 + 8 + 1;
+}
+
+// Leaf contents of a compressed listing; the tree stores sha256(borsh(listing)).
+// Indexers rebuild leaves from CompressedListingUpdated events.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CompressedListing {
+    pub marketplace: Pubkey,
+    pub listing_tree: Pubkey,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub index: u32,
+    pub price: u64,
+    pub quantity: u32,
+    pub active: bool,
+    // sha256 of the off-chain listing JSON
+    pub metadata_hash: [u8; 32],
+}
+impl CompressedListing {
+    pub fn leaf(&self) -> [u8; 32] {
+        hashv(&[&self.try_to_vec().unwrap_or_default()]).to_bytes()
+    }
+
+    // stable id of the listing across leaf updates, reported as OrderCompleted.listing;
+    // an address only, no account is created there
+    pub fn asset_id(&self) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                b"compressed_listing",
+                self.listing_tree.as_ref(),
+                self.index.to_le_bytes().as_ref(),
+            ],
+            &crate::ID,
+        )
+        .0
+    }
+}
+
+// loyalty balance per buyer; points are NORMALIZED_DECIMALS units of any accepted mint, so one
+// point earned in one mint discounts the same value in another
+#[account]
+pub struct BuyerProfile {
+    pub marketplace: Pubkey,
+    pub buyer: Pubkey,
+    pub points: u64,
+    // staged by redeem_points and applied against the fee of the next buy_now
+    pub pending_discount: u64,
+    pub lifetime_points: u64,
+    // points currently held as tokens of the points mint
+    pub minted: u64,
+    pub bump: u8,
+    // milestones for badges
+    pub orders_completed: u32,
+    pub services_completed: u32,
+}
+impl BuyerProfile {
+    pub const SIZE: usize = 32 + 32 + 8 + 8 + 8 + 8 + 1 + 4 + 4;
+}
+
+// milestone badge, awarded as one token of its own non-transferable mint
+#[account]
+pub struct BadgeDefinition {
+    pub marketplace: Pubkey,
+    pub id: u16,
+    pub name: String,
+    pub kind: BadgeKind,
+    pub threshold: u64,
+    pub mint: Pubkey,
+    pub mint_bump: u8,
+    pub active: bool,
+    pub awarded_count: u32,
+    pub bump: u8,
+}
+impl BadgeDefinition {
+    pub const SIZE: usize = 32 + 2 + (4 + MAX_BADGE_NAME_LEN) + 1 + 8 + 32 + 1 + 1 + 4 + 1;
+}
+
+// the on-chain counter a badge threshold is checked against
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum BadgeKind {
+    // BuyerProfile.orders_completed, e.g. threshold 1 for a first purchase
+    BuyerOrders,
+    // BuyerProfile.services_completed
+    BuyerServices,
+    // Merchant.sales_count
+    MerchantSales,
+    // Merchant.sales_volume, in NORMALIZED_DECIMALS units across mints
+    MerchantVolume,
+}
+
+// one per (badge, user); its existence prevents awarding the same badge twice
+#[account]
+pub struct BadgeAward {
+    pub badge: Pubkey,
+    pub user: Pubkey,
+    pub awarded_at: i64,
+    pub bump: u8,
+}
+impl BadgeAward {
+    pub const SIZE: usize = 32 + 32 + 8 + 1;
+}
+
+#[account]
+pub struct Reservation {
+    pub listing: Pubkey,
+    pub buyer: Pubkey,
+    pub quantity: u32,
+    pub expires_at: i64,
+    pub bump: u8,
+    pub rent_payer: Pubkey,
+}
+impl Reservation {
+    pub const SIZE: usize = 32 + 32 + 4 + 8 + 1 + 32;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CartItem {
+    pub listing: Pubkey,
+    pub quantity: u32,
+}
+
+// buyer's view of the feed when signing; the purchase fails if the rate has since dropped further
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct PriceGuard {
+    pub expected_rate: i64,
+    pub max_slippage_bps: u16,
+}
+impl PriceGuard {
+    // a lower rate means more tokens per quote unit, which is the buyer's downside
+    pub fn check(&self, rate: i64) -> Result<()> {
+        let min_rate = (self.expected_rate as i128)
+            .checked_mul(10_000 - self.max_slippage_bps.min(10_000) as i128)
+            .ok_or(MarketplaceError::MathOverflow)?
+            / 10_000;
+        require!(rate as i128 >= min_rate, MarketplaceError::SlippageExceeded);
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct PriceQuote {
+    pub quote_price: u64,
+    pub rate: i64,
+    pub expo: i32,
+}
+
+// Events
+#[event]
+pub struct OrderCompleted {
+    pub marketplace: Pubkey,
+    // the Listing account, or CompressedListing::asset_id for compressed listings
+    pub listing: Pubkey,
+    // beneficiary of the order
+    pub buyer: Pubkey,
+    // owner of the token account that funded it, differs from buyer on delegated purchases
+    pub payer: Pubkey,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub quantity: u32,
+    pub total_amount: u64,
+    pub reference: Pubkey,
+    pub quote: Option<PriceQuote>,
+    pub variant: Option<u16>,
+}
+
+#[event]
+pub struct PointsUpdated {
+    pub marketplace: Pubkey,
+    pub buyer: Pubkey,
+    pub earned: u64,
+    pub redeemed: u64,
+    pub balance: u64,
+    pub reference: Pubkey,
+}
+
+#[event]
+pub struct CatalogCreated {
+    pub marketplace: Pubkey,
+    pub catalog: Pubkey,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub capacity: u32,
+    pub base_uri: String,
+}
+
+#[event]
+pub struct CatalogEntryUpdated {
+    pub catalog: Pubkey,
+    pub index: u32,
+    pub price: u64,
+    pub quantity: u32,
+    pub active: bool,
+    pub revision: u32,
+    pub content_hash: [u8; 32],
+}
+
+#[event]
+pub struct CatalogEntryRemoved {
+    pub catalog: Pubkey,
+    pub index: u32,
+}
+
+#[event]
+pub struct CatalogClosed {
+    pub marketplace: Pubkey,
+    pub catalog: Pubkey,
+    pub seller: Pubkey,
+    pub entry_count: u32,
+}
+
+#[event]
+pub struct SessionKeyCreated {
+    pub buyer: Pubkey,
+    pub signer: Pubkey,
+    pub mint: Pubkey,
+    pub spend_limit: u64,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct SessionKeyRevoked {
+    pub buyer: Pubkey,
+    pub signer: Pubkey,
+    pub spent: u64,
+}
+
+#[event]
+pub struct CompressedListingUpdated {
+    pub listing: CompressedListing,
+    pub leaf: [u8; 32],
+}
+
+#[event]
+pub struct CatalogOrderCompleted {
+    pub marketplace: Pubkey,
+    pub catalog: Pubkey,
+    pub index: u32,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub quantity: u32,
+    pub total_amount: u64,
+    pub reference: Pubkey,
+}
+
+#[event]
+pub struct BadgeAwarded {
+    pub marketplace: Pubkey,
+    pub badge: Pubkey,
+    pub id: u16,
+    pub user: Pubkey,
+    pub awarded_at: i64,
+}
+
+#[event]
+pub struct MarketplaceInitialized {
+    pub marketplace: Pubkey,
+    pub authority: Pubkey,
+    pub id: u16,
+    pub fee_bps: u16,
+    pub name: String,
+}
+
+#[event]
+pub struct MerchantRegistered {
+    pub marketplace: Pubkey,
+    pub merchant: Pubkey,
+    pub owner: Pubkey,
+    // merchant account this one was cross-registered from
+    pub source: Option<Pubkey>,
+}
+
+#[event]
+pub struct MerchantStatusChanged {
+    pub marketplace: Pubkey,
+    pub merchant: Pubkey,
+    pub owner: Pubkey,
+    pub was_verified: bool,
+    pub verified: bool,
+}
+
+#[event]
+pub struct FeesWithdrawn {
+    pub marketplace: Pubkey,
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct FeesDistributed {
+    pub marketplace: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct BondSlashed {
+    pub marketplace: Pubkey,
+    pub merchant: Pubkey,
+    pub recipient: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+    pub reference: Pubkey,
+}
+
+#[event]
+pub struct ListingCreated {
+    pub marketplace: Pubkey,
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub nonce: u64,
+    pub price: u64,
+    pub quantity: u32,
+    pub is_service: bool,
+    pub name: String,
+    pub image_url: String,
+    pub price_feed: Option<Pubkey>,
+    pub metadata: ListingMetadata,
+    pub sale_window: SaleWindow,
+    pub limits: PurchaseLimits,
+}
+
+#[event]
+pub struct ListingUpdated {
+    pub marketplace: Pubkey,
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub revision: u32,
+    pub old_price: u64,
+    pub new_price: u64,
+    pub old_quantity: u32,
+    pub new_quantity: u32,
+    pub old_active: bool,
+    pub new_active: bool,
+    pub old_price_feed: Option<Pubkey>,
+    pub new_price_feed: Option<Pubkey>,
+    pub old_name: String,
+    pub new_name: String,
+    pub old_image_url: String,
+    pub new_image_url: String,
+    pub old_sale_window: SaleWindow,
+    pub new_sale_window: SaleWindow,
+    pub old_limits: PurchaseLimits,
+    pub new_limits: PurchaseLimits,
+    // metadata is only set when it was changed, it can be large
+    pub old_metadata: Option<ListingMetadata>,
+    pub new_metadata: Option<ListingMetadata>,
+}
+
+impl ListingUpdated {
+    pub fn new(listing: Pubkey, old: &Listing, new: &Listing) -> Self {
+        Self {
+            marketplace: new.marketplace,
+            listing,
+            seller: new.seller,
+            revision: new.revision,
+            old_price: old.price,
+            new_price: new.price,
+            old_quantity: old.quantity,
+            new_quantity: new.quantity,
+            old_active: old.active,
+            new_active: new.active,
+            old_price_feed: old.price_feed,
+            new_price_feed: new.price_feed,
+            old_name: old.name.clone(),
+            new_name: new.name.clone(),
+            old_image_url: old.image_url.clone(),
+            new_image_url: new.image_url.clone(),
+            old_sale_window: old.sale_window,
+            new_sale_window: new.sale_window,
+            old_limits: old.limits,
+            new_limits: new.limits,
+            old_metadata: None,
+            new_metadata: None,
+        }
+    }
+}
+
+// stock moved outside update_listing: purchases, reservations and restocks
+#[event]
+pub struct ListingStockChanged {
+    pub listing: Pubkey,
+    // set when a variant's stock moved instead of the listing's own
+    pub variant: Option<u16>,
+    pub old_quantity: u32,
+    pub new_quantity: u32,
+    pub old_active: bool,
+    pub new_active: bool,
+    pub reserved: u32,
+}
+
+#[event]
+pub struct ListingClosed {
+    pub marketplace: Pubkey,
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub revision: u32,
+}
+
+#[event]
+pub struct VariantAdded {
+    pub listing: Pubkey,
+    pub variant: Pubkey,
+    pub id: u16,
+    pub price: u64,
+    pub quantity: u32,
+    pub listing_revision: u32,
+}
+
+#[event]
+pub struct VariantUpdated {
+    pub listing: Pubkey,
+    pub variant: Pubkey,
+    pub id: u16,
+    pub old_price: u64,
+    pub new_price: u64,
+    pub old_quantity: u32,
+    pub new_quantity: u32,
+    pub old_active: bool,
+    pub new_active: bool,
+    pub listing_revision: u32,
+}
+
+#[event]
+pub struct VariantRemoved {
+    pub listing: Pubkey,
+    pub variant: Pubkey,
+    pub id: u16,
+    pub old_price: u64,
+    pub old_quantity: u32,
+    pub old_active: bool,
+    pub listing_revision: u32,
+}
+
+#[event]
+pub struct CartCompleted {
+    pub marketplace: Pubkey,
+    pub buyer: Pubkey,
+    pub mint: Pubkey,
+    pub item_count: u8,
+    pub total_amount: u64,
+    pub fee: u64,
+    pub reference: Pubkey,
+}
+
+#[event]
+pub struct ServiceOrderCreated {
+    pub marketplace: Pubkey,
+    pub listing: Pubkey,
+    // beneficiary of the order
+    pub buyer: Pubkey,
+    // owner of the token account that funded it, differs from buyer on delegated purchases
+    pub payer: Pubkey,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub reference: Pubkey,
+    pub escrow: Pubkey,
+    pub quote: Option<PriceQuote>,
+}
+
+#[event]
+pub struct ServiceOrderReleased {
+    pub marketplace: Pubkey,
+    pub escrow: Pubkey,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub reference: Pubkey,
+}
+
+#[event]
+pub struct OrderRefunded {
+    pub marketplace: Pubkey,
+    pub listing: Pubkey,
+    pub receipt: Pubkey,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub fee_retained: u64,
+    pub restocked: u32,
+    pub points_clawed_back: u64,
+    pub points_restored: u64,
+    pub reference: Pubkey,
+}
+
+#[event]
+pub struct ReturnResolved {
+    pub receipt: Pubkey,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    // false when the buyer withdrew the request
+    pub rejected: bool,
+    pub reference: Pubkey,
+}
+
+#[event]
+pub struct ReviewSubmitted {
+    pub review: Pubkey,
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub reviewer: Pubkey,
+    pub reference: Pubkey,
+    pub rating: u8,
+    pub content_hash: [u8; 32],
+}
+
+#[event]
+pub struct ReviewResponded {
+    pub review: Pubkey,
+    pub seller: Pubkey,
+    pub response_hash: [u8; 32],
+}
+
+#[event]
+pub struct ServiceOrderCancelled {
+    pub marketplace: Pubkey,
+    pub escrow: Pubkey,
+    pub buyer: Pubkey,
+    pub refund_account: Pubkey,
+    pub amount: u64,
+    pub reference: Pubkey,
+}
+
+// Errs
+#[error_code]
+pub enum MarketplaceError {
+    #[msg("Fee too high (max 10%)")]
+    FeeTooHigh,
+    #[msg("Invalid amount")]
+    InvalidAmount,
+    #[msg("Invalid quantity")]
+    InvalidQuantity,
+    #[msg("Listing is inactive")]
+    ListingInactive,
+    #[msg("Wrong marketplace")]
+    WrongMarketplace,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Already released")]
+    AlreadyReleased,
+    #[msg("Use service escrow flow")]
+    WrongFlowForGoods,
+    #[msg("Use goods buy-now flow")]
+    WrongFlowForService,
+    #[msg("Invalid Account")]
+    InvalidAccount,
+    #[msg("Missing Solana Pay reference in remaining_accounts")]
+    MissingReference,
+    #[msg("Provided reference does not match remaining_accounts entry")]
+    WrongReference,
+    #[msg("Listing must be inactive before it can be closed")]
+    ListingStillActive,
+    #[msg("Listing still has open escrows")]
+    OpenEscrowsRemaining,
+    #[msg("Cart is empty, too large or does not match remaining_accounts")]
+    InvalidCart,
+    #[msg("Listing requires a flow not supported by cart checkout")]
+    UnsupportedInCart,
+    #[msg("Price feed account missing or does not match listing")]
+    WrongPriceFeed,
+    #[msg("Price feed account is malformed or not trading")]
+    InvalidPriceFeed,
+    #[msg("Price feed is stale")]
+    StalePrice,
+    #[msg("Price feed is not owned by the marketplace's oracle program")]
+    UntrustedPriceFeed,
+    #[msg("Price feed confidence interval is too wide")]
+    PriceTooUncertain,
+    #[msg("Oracle-priced listings require a price guard")]
+    MissingPriceGuard,
+    #[msg("Price moved beyond allowed slippage")]
+    SlippageExceeded,
+    #[msg("Listing changed since it was quoted to the buyer")]
+    ListingChanged,
+    #[msg("Order total exceeds buyer's maximum")]
+    MaxTotalExceeded,
+    #[msg("Reservation TTL out of range")]
+    InvalidReservationTtl,
+    #[msg("Reservation has expired")]
+    ReservationExpired,
+    #[msg("Reservation has not expired yet")]
+    ReservationNotExpired,
+    #[msg("Listing still has outstanding reservations")]
+    OutstandingReservations,
+    #[msg("Listing has variants, a variant must be selected")]
+    VariantRequired,
+    #[msg("Variant does not match the listing or requested id")]
+    WrongVariant,
+    #[msg("Variant is inactive")]
+    VariantInactive,
+    #[msg("Not supported for listings with variants")]
+    UnsupportedForVariants,
+    #[msg("Listing still has variants")]
+    VariantsRemaining,
+    #[msg("Listing name is too long")]
+    NameTooLong,
+    #[msg("Image URL is too long")]
+    ImageUrlTooLong,
+    #[msg("Metadata URI is too long")]
+    MetadataUriTooLong,
+    #[msg("Too many tags")]
+    TooManyTags,
+    #[msg("Tags must be non-empty and within the length limit")]
+    InvalidTag,
+    #[msg("Too many images")]
+    TooManyImages,
+    #[msg("Listing name must be non-empty printable text")]
+    InvalidName,
+    #[msg("URL must use https://, ipfs:// or ar:// and contain no whitespace")]
+    UnsupportedUrl,
+    #[msg("Sale window start must be before its end")]
+    InvalidSaleWindow,
+    #[msg("Sale has not started yet")]
+    SaleNotStarted,
+    #[msg("Sale has ended")]
+    SaleEnded,
+    #[msg("Wallet is not on the listing allowlist")]
+    NotAllowlisted,
+    #[msg("Purchase exceeds the per-wallet limit")]
+    WalletLimitExceeded,
+    #[msg("Listing has a per-wallet limit, purchase record account required")]
+    PurchaseRecordRequired,
+    #[msg("Escrow has not been released")]
+    EscrowNotReleased,
+    #[msg("Rating must be between 1 and 5")]
+    InvalidRating,
+    // no longer returned since reviews take only a receipt; kept so the codes after it don't shift
+    #[msg("Provide exactly one of a released escrow or a receipt")]
+    OrderProofRequired,
+    #[msg("Receipt is not in the required state")]
+    InvalidReceiptStatus,
+    #[msg("Return window has closed or returns are disabled")]
+    ReturnWindowClosed,
+    #[msg("Receipt has a pending return request")]
+    ReturnPending,
+    #[msg("Unverified merchant must hold an active bond of at least the marketplace minimum")]
+    BondRequired,
+    #[msg("Bond is still in its withdrawal cooldown")]
+    BondLocked,
+    #[msg("Fee splits must sum to 10000 bps and match the recipients provided")]
+    InvalidFeeSplits,
+    #[msg("Not enough loyalty points")]
+    InsufficientPoints,
+    #[msg("Badge is not active")]
+    BadgeInactive,
+    #[msg("User has not reached the badge threshold")]
+    BadgeNotEarned,
+    #[msg("Account is not in a legacy layout")]
+    AlreadyMigrated,
+    #[msg("Catalog entry index out of range")]
+    InvalidCatalogEntry,
+    #[msg("Catalog has no free entry slots, grow it first")]
+    CatalogFull,
+    #[msg("Catalog size change exceeds the per-instruction limit")]
+    CatalogTooLarge,
+    #[msg("Signer is neither the buyer nor one of its session keys")]
+    UnauthorizedSigner,
+    #[msg("Session key is expired or does not match")]
+    InvalidSession,
+    #[msg("Session key spending cap exceeded")]
+    SessionLimitExceeded,
+    #[msg("The buyer's profile is required to reverse loyalty points")]
+    BuyerProfileRequired,
+    #[msg("Account size matches no known layout")]
+    UnknownAccountLayout,
+    #[msg("Staged points can only be redeemed through buy_now")]
+    RedemptionUnsupported,
+}
+
+// Contexts
+#[derive(Accounts)]
+#[instruction(id: u16)]
+pub struct InitMarketplace<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Marketplace::SIZE,
+        seeds = [b"marketplace", authority.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMarketplace<'info> {
+    #[account(mut, has_one = authority)]
+    pub marketplace: Account<'info, Marketplace>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitTreasury<'info> {
+    #[account(has_one = authority)]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Treasury::SIZE,
+        seeds = [b"treasury", marketplace.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = mint,
+        associated_token::authority = treasury
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+    pub mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ManageTreasury<'info> {
+    #[account(has_one = treasury_authority)]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(mut, has_one = marketplace)]
+    pub treasury: Account<'info, Treasury>,
+    pub treasury_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    #[account(has_one = treasury_authority)]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(mut, has_one = marketplace)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut, associated_token::mint = treasury.mint, associated_token::authority = treasury)]
+    pub treasury_vault: Account<'info, TokenAccount>,
+    pub treasury_authority: Signer<'info>,
+    #[account(mut, token::mint = treasury.mint)]
+    pub destination: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct DistributeFees<'info> {
+    #[account(mut)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut, associated_token::mint = treasury.mint, associated_token::authority = treasury)]
+    pub treasury_vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterMerchant<'info> {
+    #[account(mut)]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Merchant::SIZE,
+        seeds = [b"merchant", marketplace.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub merchant: Account<'info, Merchant>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CrossRegisterMerchant<'info> {
+    #[account(has_one = owner)]
+    pub source_merchant: Account<'info, Merchant>,
+    #[account(address = source_merchant.marketplace)]
+    pub source_marketplace: Account<'info, Marketplace>,
+    #[account(mut)]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Merchant::SIZE,
+        seeds = [b"merchant", marketplace.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub merchant: Account<'info, Merchant>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetMerchantStatus<'info> {
+    #[account(mut, has_one = marketplace)]
+    pub merchant: Account<'info, Merchant>,
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(address = marketplace.authority)]
+    pub authority: Signer<'info>,
+}
+
+
+#[derive(Accounts)]
+#[instruction(
+    price: u64,
+    quantity: u32,
+    is_service: bool,
+    name: String,
+    image_url: String,
+    price_feed: Option<Pubkey>,
+    metadata: ListingMetadata,
+    sale_window: SaleWindow,
+)]
+pub struct CreateListing<'info> {
+    #[account(mut)]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(mut, has_one = marketplace, has_one = owner)]
+    pub merchant: Account<'info, Merchant>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Listing::SIZE + metadata.space(),
+        seeds = [b"listing", merchant.key().as_ref(), &merchant.next_nonce.to_le_bytes()],
+        bump
+    )]
+    pub listing: Account<'info, Listing>,
+    pub mint: Account<'info, Mint>,
+    #[account(seeds = [b"bond", merchant.key().as_ref()], bump = bond.bump)]
+    pub bond: Option<Account<'info, MerchantBond>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositBond<'info> {
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(has_one = marketplace, has_one = owner)]
+    pub merchant: Account<'info, Merchant>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + MerchantBond::SIZE,
+        seeds = [b"bond", merchant.key().as_ref()],
+        bump
+    )]
+    pub bond: Account<'info, MerchantBond>,
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = bond
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(mut, token::mint = mint, token::authority = owner)]
+    pub owner_ata: Account<'info, TokenAccount>,
+    // checked against MerchantBond::deposit_mint
+    pub mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RequestBondWithdrawal<'info> {
+    #[account(mut, has_one = owner, has_one = merchant)]
+    pub bond: Account<'info, MerchantBond>,
+    #[account(has_one = marketplace)]
+    pub merchant: Account<'info, Merchant>,
+    pub marketplace: Account<'info, Marketplace>,
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawBond<'info> {
+    #[account(mut, has_one = owner)]
+    pub bond: Account<'info, MerchantBond>,
+    #[account(mut, associated_token::mint = bond.mint, associated_token::authority = bond)]
+    pub vault: Account<'info, TokenAccount>,
+    pub owner: Signer<'info>,
+    #[account(mut, token::mint = bond.mint, token::authority = owner)]
+    pub owner_ata: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SlashBond<'info> {
+    #[account(mut, has_one = merchant)]
+    pub bond: Account<'info, MerchantBond>,
+    #[account(has_one = marketplace)]
+    pub merchant: Account<'info, Merchant>,
+    #[account(has_one = arbiter)]
+    pub marketplace: Account<'info, Marketplace>,
+    pub arbiter: Signer<'info>,
+    #[account(mut, associated_token::mint = bond.mint, associated_token::authority = bond)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = bond.mint)]
+    pub recipient_ata: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateListing<'info> {
+    #[account(mut, has_one = seller)]
+    pub listing: Account<'info, Listing>,
+    pub seller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: Option<String>, image_url: Option<String>, metadata: Option<ListingMetadata>)]
+pub struct UpdateListingMetadata<'info> {
+    #[account(
+        mut,
+        has_one = seller,
+        realloc = 8 + Listing::SIZE + metadata.as_ref().unwrap_or(&listing.metadata).space(),
+        realloc::payer = seller,
+        realloc::zero = false
+    )]
+    pub listing: Account<'info, Listing>,
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseListing<'info> {
+    #[account(mut, has_one = seller, close = seller)]
+    pub listing: Account<'info, Listing>,
+    #[account(mut)]
+    pub seller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u16)]
+pub struct AddVariant<'info> {
+    #[account(mut, has_one = seller)]
+    pub listing: Account<'info, Listing>,
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    #[account(
+        init,
+        payer = seller,
+        space = 8 + Variant::SIZE,
+        seeds = [b"variant", listing.key().as_ref(), &id.to_le_bytes()],
+        bump
+    )]
+    pub variant: Account<'info, Variant>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateVariant<'info> {
+    #[account(mut, has_one = listing)]
+    pub variant: Account<'info, Variant>,
+    #[account(mut, has_one = seller)]
+    pub listing: Account<'info, Listing>,
+    pub seller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveVariant<'info> {
+    #[account(mut, has_one = listing, close = seller)]
+    pub variant: Account<'info, Variant>,
+    #[account(mut, has_one = seller)]
+    pub listing: Account<'info, Listing>,
+    #[account(mut)]
+    pub seller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReserveInventory<'info> {
+    #[account(mut)]
+    pub listing: Account<'info, Listing>,
+    pub buyer: Signer<'info>,
+    // pays the reservation rent and gets it back when the hold is released or bought
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Reservation::SIZE,
+        seeds = [b"reservation", listing.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub reservation: Account<'info, Reservation>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseReservation<'info> {
+    #[account(mut, has_one = listing, has_one = rent_payer, close = rent_payer)]
+    pub reservation: Account<'info, Reservation>,
+    #[account(mut)]
+    pub listing: Account<'info, Listing>,
+    /// CHECK: rent destination, pinned by reservation.rent_payer
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(quantity: u32, reference: Pubkey)]
+pub struct BuyNow<'info> {
+    #[account(mut, has_one = marketplace, has_one = mint)]
+    pub listing: Account<'info, Listing>,
+    #[account(mut)]
+    pub marketplace: Account<'info, Marketplace>,
+    /// CHECK: purchasing wallet, authorized by `signer` directly or through `session`
+    pub buyer: UncheckedAccount<'info>,
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"session", buyer.key().as_ref(), signer.key().as_ref()],
+        bump = session.bump
+    )]
+    pub session: Option<Account<'info, SessionKey>>,
+    // pays rent for any accounts created, e.g. a relayer sponsoring the purchase
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // the buyer's own account, or a paying wallet's account that approved the signer as delegate
+    #[account(mut, token::mint = mint)]
+    pub buyer_ata: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub seller_ata: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"merchant", marketplace.key().as_ref(), listing.seller.as_ref()],
+        bump = merchant.bump
+    )]
+    pub merchant: Account<'info, Merchant>,
+    #[account(
+        mut,
+        seeds = [b"treasury", marketplace.key().as_ref(), mint.key().as_ref()],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut, associated_token::mint = mint, associated_token::authority = treasury)]
+    pub treasury_vault: Account<'info, TokenAccount>,
+    pub mint: Account<'info, Mint>,
+    /// CHECK: parsed as a Pyth-style price account, address checked against listing.price_feed
+    pub price_feed: Option<UncheckedAccount<'info>>,
+    // the buyer's own hold; its rent goes back to whoever paid for it, not this purchase's payer
+    #[account(
+        mut,
+        has_one = listing,
+        has_one = buyer,
+        constraint = reservation_rent_payer.as_ref().map(|a| a.key()) == Some(reservation.rent_payer)
+            @ MarketplaceError::InvalidAccount,
+        close = reservation_rent_payer
+    )]
+    pub reservation: Option<Account<'info, Reservation>>,
+    /// CHECK: rent destination, pinned by reservation.rent_payer
+    #[account(mut)]
+    pub reservation_rent_payer: Option<UncheckedAccount<'info>>,
+    #[account(mut, has_one = listing)]
+    pub variant: Option<Account<'info, Variant>>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + PurchaseRecord::SIZE,
+        seeds = [b"purchase", listing.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub purchase_record: Option<Account<'info, PurchaseRecord>>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Receipt::SIZE,
+        seeds = [b"receipt", reference.as_ref()],
+        bump
+    )]
+    pub receipt: Option<Account<'info, Receipt>>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + BuyerProfile::SIZE,
+        seeds = [b"buyer", marketplace.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub buyer_profile: Box<Account<'info, BuyerProfile>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CheckoutCart<'info> {
+    #[account(mut)]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    #[account(mut, token::mint = mint, token::authority = buyer)]
+    pub buyer_ata: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"treasury", marketplace.key().as_ref(), mint.key().as_ref()],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut, associated_token::mint = mint, associated_token::authority = treasury)]
+    pub treasury_vault: Account<'info, TokenAccount>,
+    pub mint: Account<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = 8 + BuyerProfile::SIZE,
+        seeds = [b"buyer", marketplace.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub buyer_profile: Box<Account<'info, BuyerProfile>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(capacity: u32)]
+pub struct InitCatalog<'info> {
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(has_one = marketplace, has_one = owner)]
+    pub merchant: Account<'info, Merchant>,
+    #[account(
+        init,
+        payer = owner,
+        space = Catalog::space(capacity),
+        constraint = Catalog::space(capacity) <= MAX_PERMITTED_DATA_INCREASE @ MarketplaceError::CatalogTooLarge,
+        seeds = [b"catalog", merchant.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub catalog: AccountLoader<'info, Catalog>,
+    pub mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GrowCatalog<'info> {
+    #[account(mut, has_one = seller)]
+    pub catalog: AccountLoader<'info, Catalog>,
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AddCatalogEntry<'info> {
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(has_one = marketplace, has_one = owner)]
+    pub merchant: Account<'info, Merchant>,
+    #[account(mut, has_one = merchant)]
+    pub catalog: AccountLoader<'info, Catalog>,
+    pub owner: Signer<'info>,
+    #[account(seeds = [b"bond", merchant.key().as_ref()], bump = bond.bump)]
+    pub bond: Option<Account<'info, MerchantBond>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCatalogEntry<'info> {
+    #[account(mut, has_one = seller)]
+    pub catalog: AccountLoader<'info, Catalog>,
+    pub seller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseCatalog<'info> {
+    #[account(mut, has_one = seller, close = seller)]
+    pub catalog: AccountLoader<'info, Catalog>,
+    #[account(mut)]
+    pub seller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct BuyCatalogEntry<'info> {
+    #[account(mut, has_one = marketplace, has_one = merchant, has_one = mint)]
+    pub catalog: AccountLoader<'info, Catalog>,
+    #[account(mut)]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(mut)]
+    pub merchant: Account<'info, Merchant>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    #[account(mut, token::mint = mint, token::authority = buyer)]
+    pub buyer_ata: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub seller_ata: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"treasury", marketplace.key().as_ref(), mint.key().as_ref()],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut, associated_token::mint = mint, associated_token::authority = treasury)]
+    pub treasury_vault: Account<'info, TokenAccount>,
+    pub mint: Account<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = 8 + BuyerProfile::SIZE,
+        seeds = [b"buyer", marketplace.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub buyer_profile: Box<Account<'info, BuyerProfile>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(session_signer: Pubkey)]
+pub struct CreateSessionKey<'info> {
+    pub buyer: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + SessionKey::SIZE,
+        seeds = [b"session", buyer.key().as_ref(), session_signer.as_ref()],
+        bump
+    )]
+    pub session: Account<'info, SessionKey>,
+    #[account(mut, token::authority = buyer)]
+    pub buyer_ata: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeSessionKey<'info> {
+    #[account(mut, has_one = buyer, has_one = rent_payer, close = rent_payer)]
+    pub session: Account<'info, SessionKey>,
+    pub buyer: Signer<'info>,
+    /// CHECK: rent destination, pinned by session.rent_payer
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+    #[account(mut, token::authority = buyer)]
+    pub buyer_ata: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct InitListingTree<'info> {
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(has_one = marketplace, has_one = owner)]
+    pub merchant: Account<'info, Merchant>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + ListingTree::SIZE,
+        seeds = [b"listing_tree", merkle_tree.key().as_ref()],
+        bump
+    )]
+    pub listing_tree: Account<'info, ListingTree>,
+    /// CHECK: allocated by the client and initialized by the compression program
+    #[account(mut, owner = ACCOUNT_COMPRESSION_PROGRAM_ID)]
+    pub merkle_tree: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    /// CHECK: address checked
+    #[account(address = ACCOUNT_COMPRESSION_PROGRAM_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+    /// CHECK: address checked
+    #[account(address = NOOP_PROGRAM_ID)]
+    pub noop_program: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateCompressedListing<'info> {
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(has_one = marketplace, has_one = owner)]
+    pub merchant: Account<'info, Merchant>,
+    #[account(mut, has_one = merchant, has_one = merkle_tree)]
+    pub listing_tree: Account<'info, ListingTree>,
+    /// CHECK: matched against listing_tree, owned by the compression program
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+    pub mint: Account<'info, Mint>,
+    pub owner: Signer<'info>,
+    #[account(seeds = [b"bond", merchant.key().as_ref()], bump = bond.bump)]
+    pub bond: Option<Account<'info, MerchantBond>>,
+    /// CHECK: address checked
+    #[account(address = ACCOUNT_COMPRESSION_PROGRAM_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+    /// CHECK: address checked
+    #[account(address = NOOP_PROGRAM_ID)]
+    pub noop_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCompressedListing<'info> {
+    #[account(has_one = seller, has_one = merkle_tree)]
+    pub listing_tree: Account<'info, ListingTree>,
+    /// CHECK: matched against listing_tree, owned by the compression program
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+    pub seller: Signer<'info>,
+    /// CHECK: address checked
+    #[account(address = ACCOUNT_COMPRESSION_PROGRAM_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+    /// CHECK: address checked
+    #[account(address = NOOP_PROGRAM_ID)]
+    pub noop_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct BuyCompressedListing<'info> {
+    #[account(has_one = marketplace, has_one = merchant, has_one = merkle_tree)]
+    pub listing_tree: Account<'info, ListingTree>,
+    /// CHECK: matched against listing_tree, owned by the compression program
+    #[account(mut)]
+    pub merkle_tree: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(mut)]
+    pub merchant: Account<'info, Merchant>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    #[account(mut, token::mint = mint, token::authority = buyer)]
+    pub buyer_ata: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub seller_ata: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"treasury", marketplace.key().as_ref(), mint.key().as_ref()],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut, associated_token::mint = mint, associated_token::authority = treasury)]
+    pub treasury_vault: Account<'info, TokenAccount>,
+    pub mint: Account<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = 8 + BuyerProfile::SIZE,
+        seeds = [b"buyer", marketplace.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub buyer_profile: Box<Account<'info, BuyerProfile>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    /// CHECK: address checked
+    #[account(address = ACCOUNT_COMPRESSION_PROGRAM_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+    /// CHECK: address checked
+    #[account(address = NOOP_PROGRAM_ID)]
+    pub noop_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateServiceOrder<'info> {
+    #[account(mut)]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(mut, has_one = marketplace, has_one = mint)]
+    pub listing: Account<'info, Listing>,
+    /// CHECK: purchasing wallet, authorized by `signer` directly or through `session`
+    pub buyer: UncheckedAccount<'info>,
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"session", buyer.key().as_ref(), signer.key().as_ref()],
+        bump = session.bump
+    )]
+    pub session: Option<Account<'info, SessionKey>>,
+    // pays escrow and vault rent and gets it back when they close
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    // the buyer's own account, or a paying wallet's account that approved the signer as delegate
+    #[account(mut, token::mint = mint)]
+    pub buyer_ata: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Escrow::SIZE,
+        seeds = [b"escrow", listing.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = escrow
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"treasury", marketplace.key().as_ref(), mint.key().as_ref()],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+    pub mint: Account<'info, Mint>,
+    /// CHECK: parsed as a Pyth-style price account, address checked against listing.price_feed
+    pub price_feed: Option<UncheckedAccount<'info>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseServiceOrder<'info> {
+    #[account(mut, has_one = marketplace, has_one = listing, close = rent_payer)]
+    pub escrow: Account<'info, Escrow>,
+    #[account(mut)]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(mut)]
+    pub listing: Account<'info, Listing>,
+    #[account(
+        mut,
+        seeds = [b"merchant", marketplace.key().as_ref(), escrow.seller.as_ref()],
+        bump = merchant.bump
+    )]
+    pub merchant: Account<'info, Merchant>,
+    #[account(mut, constraint = payer.key() == escrow.buyer || payer.key() == marketplace.authority)]
+    pub payer: Signer<'info>,
+    // keyed by the escrow as well, so a goods receipt squatting the reference can't block release
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Receipt::SIZE,
+        seeds = [b"receipt", escrow.key().as_ref(), escrow.reference.as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, Receipt>,
+    /// CHECK: destination for escrow and vault rent, verified via constraint
+    #[account(mut, constraint = rent_payer.key() == escrow.rent_destination() @ MarketplaceError::InvalidAccount)]
+    pub rent_payer: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub seller_ata: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"treasury", marketplace.key().as_ref(), escrow.mint.as_ref()],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut, associated_token::mint = escrow.mint, associated_token::authority = treasury)]
+    pub treasury_vault: Account<'info, TokenAccount>,
+    #[account(mut, token::authority = escrow, close = rent_payer)]
+    pub vault: Account<'info, TokenAccount>,
+    #[account(address = escrow.mint)]
+    pub mint: Account<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + BuyerProfile::SIZE,
+        seeds = [b"buyer", marketplace.key().as_ref(), escrow.buyer.as_ref()],
+        bump
+    )]
+    pub buyer_profile: Box<Account<'info, BuyerProfile>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+//Backend can cancel, rent refunded
+#[derive(Accounts)]
+pub struct CancelServiceOrder<'info> {
+    #[account(mut, has_one = marketplace, has_one = listing, close = rent_payer)]
+    pub escrow: Account<'info, Escrow>,
+    #[account(mut)]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(mut)]
+    pub listing: Account<'info, Listing>,
+    #[account(constraint = payer.key() == escrow.buyer || payer.key() == marketplace.authority)]
+    pub payer: Signer<'info>,
+    /// CHECK: destination for escrow and vault rent, verified via constraint
+    #[account(mut, constraint = rent_payer.key() == escrow.rent_destination() @ MarketplaceError::InvalidAccount)]
+    pub rent_payer: UncheckedAccount<'info>,
+    // the token account that funded the order, which may belong to a paying wallet
+    #[account(
+        mut,
+        token::mint = escrow.mint,
+        constraint = escrow.is_refund_account(refund_ata.key(), refund_ata.owner) @ MarketplaceError::InvalidAccount
+    )]
+    pub refund_ata: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"treasury", marketplace.key().as_ref(), escrow.mint.as_ref()],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+    #[account(mut, token::authority = escrow, close = rent_payer)]
+    pub vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CloseEscrow<'info> {
+    #[account(mut, has_one = buyer, close = rent_payer)]
+    pub escrow: Account<'info, Escrow>,
+    pub buyer: Signer<'info>,
+    /// CHECK: destination for escrow rent, verified via constraint
+    #[account(mut, constraint = rent_payer.key() == escrow.rent_destination() @ MarketplaceError::InvalidAccount)]
+    pub rent_payer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitReview<'info> {
+    #[account(has_one = buyer, has_one = listing)]
+    pub receipt: Account<'info, Receipt>,
+    #[account(mut)]
+    pub listing: Account<'info, Listing>,
+    #[account(
+        mut,
+        seeds = [b"merchant", listing.marketplace.as_ref(), listing.seller.as_ref()],
+        bump = merchant.bump
+    )]
+    pub merchant: Account<'info, Merchant>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Review::SIZE,
+        seeds = [b"review", receipt.key().as_ref()],
+        bump
+    )]
+    pub review: Account<'info, Review>,
+    pub buyer: Signer<'info>,
+    // pays the review rent, e.g. a relayer sponsoring the buyer
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseReceipt<'info> {
+    #[account(mut, has_one = buyer, has_one = rent_payer, close = rent_payer)]
+    pub receipt: Account<'info, Receipt>,
+    pub buyer: Signer<'info>,
+    /// CHECK: rent destination, pinned by receipt.rent_payer
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RequestReturn<'info> {
+    #[account(mut, has_one = buyer, has_one = marketplace)]
+    pub receipt: Account<'info, Receipt>,
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(
+        init,
+        payer = buyer,
+        space = 8 + ReturnRequest::SIZE,
+        seeds = [b"return", receipt.key().as_ref()],
+        bump
+    )]
+    pub return_request: Account<'info, ReturnRequest>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+// approving closes the return request, its rent goes back to the buyer
+#[derive(Accounts)]
+pub struct RejectReturn<'info> {
+    #[account(mut, has_one = seller, has_one = buyer)]
+    pub receipt: Account<'info, Receipt>,
+    #[account(mut, has_one = receipt, close = buyer)]
+    pub return_request: Account<'info, ReturnRequest>,
+    pub seller: Signer<'info>,
+    /// CHECK: rent destination, pinned by receipt.buyer
+    #[account(mut)]
+    pub buyer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawReturn<'info> {
+    #[account(mut, has_one = buyer)]
+    pub receipt: Account<'info, Receipt>,
+    #[account(mut, has_one = receipt, close = buyer)]
+    pub return_request: Account<'info, ReturnRequest>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RefundOrder<'info> {
+    #[account(mut, has_one = seller, has_one = marketplace, has_one = buyer)]
+    pub receipt: Account<'info, Receipt>,
+    #[account(mut, has_one = receipt, close = buyer)]
+    pub return_request: Account<'info, ReturnRequest>,
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(
+        mut,
+        seeds = [b"merchant", marketplace.key().as_ref(), seller.key().as_ref()],
+        bump = merchant.bump
+    )]
+    pub merchant: Account<'info, Merchant>,
+    // required when the order earned or spent loyalty points
+    #[account(
+        mut,
+        seeds = [b"buyer", marketplace.key().as_ref(), receipt.buyer.as_ref()],
+        bump = buyer_profile.bump
+    )]
+    pub buyer_profile: Option<Account<'info, BuyerProfile>>,
+    // only needed when restocking, which also relists a listing or variant that sold out
+    #[account(mut, address = receipt.listing)]
+    pub listing: Option<Account<'info, Listing>>,
+    #[account(mut, constraint = variant.listing == receipt.listing @ MarketplaceError::WrongVariant)]
+    pub variant: Option<Account<'info, Variant>>,
+    pub seller: Signer<'info>,
+    /// CHECK: rent destination, pinned by receipt.buyer
+    #[account(mut)]
+    pub buyer: UncheckedAccount<'info>,
+    #[account(mut, token::mint = receipt.mint, token::authority = seller)]
+    pub seller_ata: Account<'info, TokenAccount>,
+    // the token account that paid for the order, which may belong to a paying wallet
+    #[account(
+        mut,
+        token::mint = receipt.mint,
+        constraint = receipt.is_refund_account(refund_ata.key(), refund_ata.owner) @ MarketplaceError::InvalidAccount
+    )]
+    pub refund_ata: Account<'info, TokenAccount>,
+    #[account(address = receipt.mint)]
+    pub mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RespondToReview<'info> {
+    #[account(mut, has_one = seller)]
+    pub review: Account<'info, Review>,
+    pub seller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u16)]
+pub struct CreateBadge<'info> {
+    #[account(has_one = authority)]
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + BadgeDefinition::SIZE,
+        seeds = [b"badge", marketplace.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub badge: Account<'info, BadgeDefinition>,
+    /// CHECK: created and initialized as a Token-2022 mint in the instruction
+    #[account(mut, seeds = [b"badge_mint", badge.key().as_ref()], bump)]
+    pub badge_mint: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetBadgeStatus<'info> {
+    #[account(mut, has_one = marketplace)]
+    pub badge: Account<'info, BadgeDefinition>,
+    #[account(has_one = authority)]
+    pub marketplace: Account<'info, Marketplace>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AwardBadge<'info> {
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(mut, has_one = marketplace)]
+    pub badge: Account<'info, BadgeDefinition>,
+    #[account(mut, address = badge.mint)]
+    pub badge_mint: InterfaceAccount<'info, token_interface::Mint>,
+    /// CHECK: wallet receiving the badge, only used as a key
+    pub user: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + BadgeAward::SIZE,
+        seeds = [b"badge_award", badge.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub badge_award: Account<'info, BadgeAward>,
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = badge_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program
+    )]
+    pub badge_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    // whichever side the badge kind is measured on
+    #[account(seeds = [b"buyer", marketplace.key().as_ref(), user.key().as_ref()], bump = buyer_profile.bump)]
+    pub buyer_profile: Option<Account<'info, BuyerProfile>>,
+    #[account(seeds = [b"merchant", marketplace.key().as_ref(), user.key().as_ref()], bump = merchant.bump)]
+    pub merchant: Option<Account<'info, Merchant>>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemPoints<'info> {
+    #[account(
+        mut,
+        seeds = [b"buyer", buyer_profile.marketplace.as_ref(), buyer.key().as_ref()],
+        bump = buyer_profile.bump,
+        has_one = buyer
+    )]
+    pub buyer_profile: Account<'info, BuyerProfile>,
+    pub buyer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitPointsMint<'info> {
+    #[account(has_one = authority)]
+    pub marketplace: Account<'info, Marketplace>,
+    /// CHECK: created and initialized as a Token-2022 mint in the instruction
+    #[account(mut, seeds = [b"points_mint", marketplace.key().as_ref()], bump)]
+    pub points_mint: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SyncPoints<'info> {
+    pub marketplace: Account<'info, Marketplace>,
+    #[account(
+        mut,
+        seeds = [b"buyer", marketplace.key().as_ref(), buyer.key().as_ref()],
+        bump = buyer_profile.bump,
+        has_one = buyer
+    )]
+    pub buyer_profile: Account<'info, BuyerProfile>,
+    #[account(
+        mut,
+        seeds = [b"points_mint", marketplace.key().as_ref()],
+        bump,
+        mint::token_program = token_program
+    )]
+    pub points_mint: InterfaceAccount<'info, token_interface::Mint>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = points_mint,
+        associated_token::authority = buyer,
+        associated_token::token_program = token_program
+    )]
+    pub points_account: InterfaceAccount<'info, token_interface::TokenAccount>,
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    /// CHECK: owner, discriminator and legacy layout are checked in the instruction
+    #[account(mut)]
+    pub account: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+impl Listing {
+    pub fn get_fee_bps(&self, mp: &Account<Marketplace>) -> Result<u16> {
+        require!(self.marketplace == mp.key(), MarketplaceError::WrongMarketplace);
+        Ok(mp.fee_bps)
+    }
+
+    // token amount owed for `quantity` units, converting through the price feed when the
+    // listing is priced in a quote currency
+    pub fn settle_total(
+        &self,
+        unit_price: u64,
+        quantity: u32,
+        price_feed: Option<&AccountInfo>,
+        oracle_program: Pubkey,
+        guard: Option<PriceGuard>,
+        mint_decimals: u8,
+    ) -> Result<(u64, Option<PriceQuote>)> {
+        let total = unit_price
+            .checked_mul(quantity as u64)
+            .ok_or(MarketplaceError::MathOverflow)?;
+
+        let Some(feed_key) = self.price_feed else {
+            return Ok((total, None));
+        };
+        let feed_info = price_feed.ok_or(MarketplaceError::WrongPriceFeed)?;
+        require!(feed_info.key() == feed_key, MarketplaceError::WrongPriceFeed);
+        let guard = guard.ok_or(MarketplaceError::MissingPriceGuard)?;
+
+        let feed = PriceFeed::load(feed_info, oracle_program)?;
+        feed.check_usable(Clock::get()?.unix_timestamp)?;
+
+        guard.check(feed.price)?;
+
+        let amount = feed.quote_to_tokens(total, mint_decimals)?;
+        Ok((
+            amount,
+            Some(PriceQuote {
+                quote_price: unit_price,
+                rate: feed.price,
+                expo: feed.expo,
+            }),
+        ))
+    }
+
+    pub fn check_buyer_view(
+        &self,
+        expected_revision: Option<u32>,
+        total: u64,
+        max_total: Option<u64>,
+    ) -> Result<()> {
+        if let Some(rev) = expected_revision {
+            require!(self.revision == rev, MarketplaceError::ListingChanged);
+        }
+        if let Some(max) = max_total {
+            require!(total <= max, MarketplaceError::MaxTotalExceeded);
+        }
+        Ok(())
+    }
+
+    pub fn validate_name(name: &str) -> Result<()> {
+        require!(name.len() <= MAX_NAME_LEN, MarketplaceError::NameTooLong);
+        require!(
+            !name.trim().is_empty() && !name.chars().any(char::is_control),
+            MarketplaceError::InvalidName
+        );
+        Ok(())
+    }
+
+    // empty means no image
+    pub fn validate_image_url(url: &str) -> Result<()> {
+        require!(url.len() <= MAX_IMAGE_URL_LEN, MarketplaceError::ImageUrlTooLong);
+        if !url.is_empty() {
+            Listing::validate_url(url)?;
+        }
+        Ok(())
+    }
+
+    pub fn validate_url(url: &str) -> Result<()> {
+        let ok = ["https://", "ipfs://", "ar://"]
+            .iter()
+            .any(|scheme| url.len() > scheme.len() && url.starts_with(scheme));
+        require!(
+            ok && !url.chars().any(|c| c.is_whitespace() || c.is_control()),
+            MarketplaceError::UnsupportedUrl
+        );
+        Ok(())
+    }
+
+    pub fn check_on_sale(&self) -> Result<()> {
+        require!(self.active, MarketplaceError::ListingInactive);
+        if self.sale_window.is_unbounded() {
+            return Ok(());
+        }
+        self.sale_window.check(Clock::get()?.unix_timestamp)
+    }
+
+    pub fn bump_revision(&mut self) -> Result<()> {
+        self.revision = self
+            .revision
+            .checked_add(1)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        Ok(())
+    }
+
+    // take sold units out of stock; the listing only goes inactive once nothing is held either
+    pub fn take_stock(&mut self, quantity: u32) -> Result<()> {
+        self.quantity = self
+            .quantity
+            .checked_sub(quantity)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        if self.quantity == 0 && self.reserved == 0 {
+            self.active = false;
+        }
+        Ok(())
+    }
+
+    pub fn restock_reserved(&mut self, quantity: u32) -> Result<()> {
+        self.reserved = self
+            .reserved
+            .checked_sub(quantity)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        self.quantity = self
+            .quantity
+            .checked_add(quantity)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn add_rating(&mut self, rating: u8) -> Result<()> {
+        self.rating_sum = self
+            .rating_sum
+            .checked_add(rating as u64)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        self.rating_count = self
+            .rating_count
+            .checked_add(1)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        Ok(())
+    }
+
+    // returned stock relists a listing that had sold out, a delisted one stays delisted
+    pub fn restock(&mut self, quantity: u32) -> Result<()> {
+        if self.quantity == 0 && self.reserved == 0 {
+            self.active = true;
+        }
+        self.quantity = self
+            .quantity
+            .checked_add(quantity)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        Ok(())
+    }
+
+    // a bookkeeping counter must never block settling an order
+    pub fn release_escrow(&mut self) {
+        self.open_escrows = self.open_escrows.saturating_sub(1);
+    }
+}
+
+impl Variant {
+    pub fn take_stock(&mut self, quantity: u32) -> Result<()> {
+        self.quantity = self
+            .quantity
+            .checked_sub(quantity)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        if self.quantity == 0 {
+            self.active = false;
+        }
+        Ok(())
+    }
+
+    pub fn restock(&mut self, quantity: u32) -> Result<()> {
+        if self.quantity == 0 {
+            self.active = true;
+        }
+        self.quantity = self
+            .quantity
+            .checked_add(quantity)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        Ok(())
+    }
+}
+
+impl Escrow {
+    // escrows opened before the funding account was recorded refund to any buyer account
+    pub fn is_refund_account(&self, account: Pubkey, owner: Pubkey) -> bool {
+        if self.funding_account == Pubkey::default() {
+            owner == self.buyer
+        } else {
+            account == self.funding_account
+        }
+    }
+
+    pub fn rent_destination(&self) -> Pubkey {
+        if self.rent_payer == Pubkey::default() {
+            self.buyer
+        } else {
+            self.rent_payer
+        }
+    }
+
+    pub fn get_fee_bps(&self, mp: &Account<Marketplace>) -> Result<u16> {
+        require!(self.marketplace == mp.key(), MarketplaceError::WrongMarketplace);
+        Ok(mp.fee_bps)
+    }
+}
+
+impl BuyerProfile {
+    // profiles are created on first use by init_if_needed
+    pub fn init_if_new(&mut self, marketplace: Pubkey, buyer: Pubkey, bump: u8) {
+        if self.buyer == Pubkey::default() {
+            self.marketplace = marketplace;
+            self.buyer = buyer;
+            self.bump = bump;
+        }
+    }
+
+    pub fn credit(&mut self, amount_paid: u64, decimals: u8, points_bps: u16) -> Result<u64> {
+        let earned = normalize_amount(amount_paid, decimals)?
+            .checked_mul(points_bps as u64)
+            .ok_or(MarketplaceError::MathOverflow)?
+            / 10_000;
+        self.points = self
+            .points
+            .checked_add(earned)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        self.lifetime_points = self
+            .lifetime_points
+            .checked_add(earned)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        Ok(earned)
+    }
+
+    // every purchase path counts here, a cart counts once per line like Marketplace::record_orders
+    pub fn record_orders(&mut self, count: u32, service: bool) -> Result<()> {
+        let counter = if service {
+            &mut self.services_completed
+        } else {
+            &mut self.orders_completed
+        };
+        *counter = counter.checked_add(count).ok_or(MarketplaceError::MathOverflow)?;
+        Ok(())
+    }
+
+    // points earned by a refunded order that were already spent can't be taken back,
+    // returns how many were
+    pub fn reverse_points(&mut self, earned: u64, redeemed: u64) -> Result<u64> {
+        let clawed_back = earned.min(self.points);
+        self.points = (self.points - clawed_back)
+            .checked_add(redeemed)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        self.lifetime_points = self.lifetime_points.saturating_sub(earned);
+        Ok(clawed_back)
+    }
+
+    // discount is capped by the marketplace fee; whatever is left stays staged.
+    // Returns the points used and the discount in base units of the mint
+    pub fn take_discount(&mut self, fee: u64, decimals: u8) -> Result<(u64, u64)> {
+        let points = self.pending_discount.min(normalize_amount(fee, decimals)?);
+        let discount = denormalize_amount(points, decimals)?;
+        // only the points the discount actually covers are used up
+        let points = normalize_amount(discount, decimals)?;
+        self.pending_discount -= points;
+        Ok((points, discount))
+    }
+}
+
+// creates a NonTransferable Token-2022 mint at a PDA that is its own mint authority
+fn create_soulbound_mint<'info>(
+    payer: &Signer<'info>,
+    mint: &UncheckedAccount<'info>,
+    token_program: &Program<'info, Token2022>,
+    system_program: &Program<'info, System>,
+    signer: &[&[&[u8]]],
+    decimals: u8,
+) -> Result<()> {
+    let space = ExtensionType::try_calculate_account_len::<
+        token_2022::spl_token_2022::state::Mint,
+    >(&[ExtensionType::NonTransferable])?;
+    anchor_lang::system_program::create_account(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            anchor_lang::system_program::CreateAccount {
+                from: payer.to_account_info(),
+                to: mint.to_account_info(),
+            },
+            signer,
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        &token_program.key(),
+    )?;
+    token_interface::non_transferable_mint_initialize(CpiContext::new(
+        token_program.to_account_info(),
+        token_interface::NonTransferableMintInitialize {
+            token_program_id: token_program.to_account_info(),
+            mint: mint.to_account_info(),
+        },
+    ))?;
+    token_2022::initialize_mint2(
+        CpiContext::new(
+            token_program.to_account_info(),
+            token_2022::InitializeMint2 {
+                mint: mint.to_account_info(),
+            },
+        ),
+        decimals,
+        &mint.key(),
+        None,
+    )
+}
+
+// Version 0 layouts, as they were before the version byte and reserved space were added.
+// Only read by the migrate_* instructions; never add fields here. Fields introduced since then
+// get the values a freshly initialized account would have.
+#[derive(AnchorDeserialize)]
+pub struct MarketplaceV0 {
     pub authority: Pubkey,
     pub fee_bps: u16,
     pub bump: u8,
 }
-impl Marketplace {
+impl MarketplaceV0 {
     pub const SIZE: usize = 32 + 2 + 1;
+
+    // version 0 marketplaces were seeded without an id and keep their address, so they read as id 0
+    pub fn upgrade(self) -> Marketplace {
+        Marketplace {
+            id: 0,
+            metadata: MarketplaceMetadata {
+                name: String::new(),
+                uri: String::new(),
+                default_mint: Pubkey::default(),
+            },
+            authority: self.authority,
+            fee_bps: self.fee_bps,
+            bump: self.bump,
+            return_window_secs: 0,
+            fee_refund_policy: FeeRefundPolicy::RetainFee,
+            arbiter: self.authority,
+            bond: BondConfig {
+                mint: Pubkey::default(),
+                min_amount: 0,
+                cooldown_secs: DEFAULT_BOND_COOLDOWN_SECS,
+            },
+            treasury_authority: self.authority,
+            // not tracked before; open_escrow_count saturates when older escrows close
+            merchant_count: 0,
+            order_count: 0,
+            open_escrow_count: 0,
+            points_bps: 0,
+            version: Marketplace::VERSION,
+            oracle_program: Pubkey::default(),
+            padding: [0; ACCOUNT_PADDING_LEN],
+        }
+    }
 }
 
-#[account]
-pub struct Merchant {
+#[derive(AnchorDeserialize)]
+pub struct MerchantV0 {
     pub marketplace: Pubkey,
     pub owner: Pubkey,
     pub verified: bool,
     pub bump: u8,
     pub next_nonce: u64,
 }
-impl Merchant {
+impl MerchantV0 {
     pub const SIZE: usize = 32 + 32 + 1 + 1 + 8;
+
+    pub fn upgrade(self) -> Merchant {
+        Merchant {
+            marketplace: self.marketplace,
+            owner: self.owner,
+            verified: self.verified,
+            bump: self.bump,
+            next_nonce: self.next_nonce,
+            rating_sum: 0,
+            rating_count: 0,
+            sales_count: 0,
+            sales_volume: 0,
+            version: ACCOUNT_VERSION,
+            padding: [0; ACCOUNT_PADDING_LEN],
+        }
+    }
 }
 
-#[account]
-pub struct Listing {
+#[derive(AnchorDeserialize)]
+pub struct ListingV0 {
     pub marketplace: Pubkey,
     pub seller: Pubkey,
     pub mint: Pubkey,
@@ -417,12 +5365,42 @@ pub struct Listing {
     pub image_url: String,
     pub nonce: u64,
 }
-impl Listing {
+impl ListingV0 {
+    // counts the discriminator twice; accounts were allocated with 8 + SIZE
     pub const SIZE: usize = 8 + 32 + 32 + 32 + 8 + 4 + 1 + 1 + 1 + 104 + 204 + 8;
+
+    pub fn upgrade(self) -> Listing {
+        Listing {
+            marketplace: self.marketplace,
+            seller: self.seller,
+            mint: self.mint,
+            price: self.price,
+            quantity: self.quantity,
+            is_service: self.is_service,
+            active: self.active,
+            bump: self.bump,
+            name: self.name,
+            image_url: self.image_url,
+            nonce: self.nonce,
+            // escrows opened before the migration are not counted
+            open_escrows: 0,
+            price_feed: None,
+            revision: 0,
+            reserved: 0,
+            variant_count: 0,
+            metadata: ListingMetadata::default(),
+            sale_window: SaleWindow::default(),
+            limits: PurchaseLimits::default(),
+            rating_sum: 0,
+            rating_count: 0,
+            version: ACCOUNT_VERSION,
+            padding: [0; ACCOUNT_PADDING_LEN],
+        }
+    }
 }
 
-#[account]
-pub struct Escrow {
+#[derive(AnchorDeserialize)]
+pub struct EscrowV0 {
     pub marketplace: Pubkey,
     pub listing: Pubkey,
     pub seller: Pubkey,
@@ -433,260 +5411,399 @@ pub struct Escrow {
     pub released: bool,
     pub bump: u8,
 }
-impl Escrow {
+impl EscrowV0 {
     pub const SIZE: usize = 32 + 32 + 32 + 32 + 32 + 8 + 32 + 1 + 1;
-}
 
-// Events
-#[event]
-pub struct OrderCompleted {
-    pub marketplace: Pubkey,
-    pub listing: Pubkey,
-    pub buyer: Pubkey,
-    pub seller: Pubkey,
-    pub mint: Pubkey,
-    pub quantity: u32,
-    pub total_amount: u64,
-    pub reference: Pubkey,
+    pub fn upgrade(self) -> Escrow {
+        Escrow {
+            marketplace: self.marketplace,
+            listing: self.listing,
+            seller: self.seller,
+            buyer: self.buyer,
+            mint: self.mint,
+            amount: self.amount,
+            reference: self.reference,
+            released: self.released,
+            bump: self.bump,
+            version: Escrow::VERSION,
+            rent_payer: self.buyer,
+            funding_account: Pubkey::default(),
+            padding: [0; ACCOUNT_PADDING_LEN],
+        }
+    }
 }
 
-#[event]
-pub struct ServiceOrderCreated {
-    pub marketplace: Pubkey,
-    pub listing: Pubkey,
-    pub buyer: Pubkey,
-    pub seller: Pubkey,
-    pub mint: Pubkey,
-    pub amount: u64,
-    pub reference: Pubkey,
-    pub escrow: Pubkey,
+// accounts already at (or grown past) their current size are migrated; anything else that is not
+// exactly the legacy size is a layout this program never wrote
+pub fn check_legacy_len(len: usize, legacy_len: usize, current_len: usize) -> Result<()> {
+    require!(len < current_len, MarketplaceError::AlreadyMigrated);
+    require!(len == legacy_len, MarketplaceError::UnknownAccountLayout);
+    Ok(())
 }
 
-#[event]
-pub struct ServiceOrderReleased {
-    pub marketplace: Pubkey,
-    pub escrow: Pubkey,
-    pub buyer: Pubkey,
-    pub seller: Pubkey,
-    pub mint: Pubkey,
-    pub amount: u64,
-    pub reference: Pubkey,
+// deserializes a program account still in an older layout
+pub fn read_legacy<T: AnchorDeserialize>(info: &AccountInfo, discriminator: &[u8]) -> Result<T> {
+    require!(info.owner == &crate::ID, MarketplaceError::InvalidAccount);
+    let data = info.try_borrow_data()?;
+    require!(
+        data.len() >= 8 && &data[..8] == discriminator,
+        MarketplaceError::InvalidAccount
+    );
+    T::deserialize(&mut &data[8..]).map_err(|_| error!(MarketplaceError::InvalidAccount))
 }
 
-#[event]
-pub struct ServiceOrderCancelled {
-    pub marketplace: Pubkey,
-    pub escrow: Pubkey,
-    pub buyer: Pubkey,
-    pub amount: u64,
-    pub reference: Pubkey,
+// grows the account to its current size and rewrites it
+fn write_migrated<'info, T: AccountSerialize>(
+    info: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    new_len: usize,
+    account: &T,
+) -> Result<()> {
+    grow_account(info, payer, system_program, new_len)?;
+    let mut data = info.try_borrow_mut_data()?;
+    account.try_serialize(&mut &mut data[..])
 }
 
-// Errs
-#[error_code]
-pub enum MarketplaceError {
-    #[msg("Fee too high (max 10%)")]
-    FeeTooHigh,
-    #[msg("Invalid amount")]
-    InvalidAmount,
-    #[msg("Invalid quantity")]
-    InvalidQuantity,
-    #[msg("Listing is inactive")]
-    ListingInactive,
-    #[msg("Wrong marketplace")]
-    WrongMarketplace,
-    #[msg("Math overflow")]
-    MathOverflow,
-    #[msg("Already released")]
-    AlreadyReleased,
-    #[msg("Use service escrow flow")]
-    WrongFlowForGoods,
-    #[msg("Use goods buy-now flow")]
-    WrongFlowForService,
-    #[msg("Invalid Account")]
-    InvalidAccount,
-    #[msg("Missing Solana Pay reference in remaining_accounts")]
-    MissingReference,
-    #[msg("Provided reference does not match remaining_accounts entry")]
-    WrongReference,
+// Upgrades an account whose layout only grew at the end: the account is grown to its current
+// size and read back through the current layout with the new bytes zeroed.
+fn grow_in_place<'info, T: AccountSerialize + AccountDeserialize>(
+    info: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    new_len: usize,
+    upgrade: impl FnOnce(&mut T),
+) -> Result<()> {
+    require!(info.owner == &crate::ID, MarketplaceError::InvalidAccount);
+    let old_len = info.data_len();
+    grow_account(info, payer, system_program, new_len)?;
+    let mut data = info.try_borrow_mut_data()?;
+    let mut account: T = read_grown(&mut data, old_len)?;
+    upgrade(&mut account);
+    account.try_serialize(&mut &mut data[..])
 }
 
-// Contexts
-#[derive(Accounts)]
-#[instruction(fee_bps: u16)]
-pub struct InitMarketplace<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + Marketplace::SIZE,
-        seeds = [b"marketplace", authority.key().as_ref()],
-        bump
-    )]
-    pub marketplace: Account<'info, Marketplace>,
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
+// zeroes whatever follows the old layout before reading, so new fields start out empty
+pub fn read_grown<T: AccountDeserialize>(data: &mut [u8], old_len: usize) -> Result<T> {
+    data[old_len..].fill(0);
+    T::try_deserialize(&mut &data[..])
 }
 
-#[derive(Accounts)]
-pub struct UpdateMarketplace<'info> {
-    #[account(mut, has_one = authority)]
-    pub marketplace: Account<'info, Marketplace>,
-    pub authority: Signer<'info>,
+// resizes a program account, topping up rent from the payer
+fn grow_account<'info>(
+    info: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    new_len: usize,
+) -> Result<()> {
+    let shortfall = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(info.lamports());
+    if shortfall > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: payer.to_account_info(),
+                    to: info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    info.resize(new_len)?;
+    Ok(())
 }
 
-#[derive(Accounts)]
-pub struct RegisterMerchant<'info> {
-    #[account(mut)]
-    pub marketplace: Account<'info, Marketplace>,
-    #[account(
-        init,
-        payer = owner,
-        space = 8 + Merchant::SIZE,
-        seeds = [b"merchant", marketplace.key().as_ref(), owner.key().as_ref()],
-        bump
-    )]
-    pub merchant: Account<'info, Merchant>,
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    pub system_program: Program<'info, System>,
+// the signer is the buyer itself or a live session key of the buyer with `amount` left
+// under its cap. Without a session the buyer may spend from another wallet's account
+// as its approved delegate; the token program enforces the delegated amount.
+fn authorize_buyer(
+    buyer: &AccountInfo,
+    signer: &Signer,
+    session: Option<&mut Account<SessionKey>>,
+    buyer_ata: &Account<TokenAccount>,
+    amount: u64,
+) -> Result<()> {
+    let Some(s) = session else {
+        require!(
+            signer.key() == buyer.key()
+                && (buyer_ata.owner == buyer.key()
+                    || buyer_ata.delegate == COption::Some(signer.key())),
+            MarketplaceError::UnauthorizedSigner
+        );
+        return Ok(());
+    };
+    require!(buyer_ata.owner == buyer.key(), MarketplaceError::InvalidSession);
+    s.authorize_spend(
+        buyer.key(),
+        signer.key(),
+        buyer_ata.mint,
+        Clock::get()?.unix_timestamp,
+        amount,
+    )
 }
 
-#[derive(Accounts)]
-pub struct SetMerchantStatus<'info> {
-    #[account(mut, has_one = marketplace)]
-    pub merchant: Account<'info, Merchant>,
-    pub marketplace: Account<'info, Marketplace>,
-    #[account(address = marketplace.authority)]
-    pub authority: Signer<'info>,
+// moves buyer funds under the signer's authority, or the session PDA's as approved delegate
+fn pay_from_buyer<'info>(
+    token_program: &Program<'info, Token>,
+    from: &Account<'info, TokenAccount>,
+    to: AccountInfo<'info>,
+    signer: &Signer<'info>,
+    session: Option<&Account<'info, SessionKey>>,
+    amount: u64,
+) -> Result<()> {
+    let Some(s) = session else {
+        return token::transfer(
+            CpiContext::new(
+                token_program.to_account_info(),
+                Transfer {
+                    from: from.to_account_info(),
+                    to,
+                    authority: signer.to_account_info(),
+                },
+            ),
+            amount,
+        );
+    };
+    let seeds: &[&[u8]] = &[b"session", s.buyer.as_ref(), s.signer.as_ref(), &[s.bump]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: from.to_account_info(),
+                to,
+                authority: s.to_account_info(),
+            },
+            &[seeds],
+        ),
+        amount,
+    )
 }
 
+// buyer pays the seller and the marketplace fee for a fixed-price goods order, stats are updated
+#[allow(clippy::too_many_arguments)]
+fn settle_goods_order<'info>(
+    token_program: &Program<'info, Token>,
+    buyer: &Signer<'info>,
+    buyer_ata: &Account<'info, TokenAccount>,
+    seller_ata: &Account<'info, TokenAccount>,
+    treasury_vault: &Account<'info, TokenAccount>,
+    treasury: &mut Account<'info, Treasury>,
+    marketplace: &mut Account<'info, Marketplace>,
+    merchant: &mut Account<'info, Merchant>,
+    total_price: u64,
+    decimals: u8,
+) -> Result<()> {
+    require!(
+        merchant.marketplace == marketplace.key() && merchant.owner == seller_ata.owner,
+        MarketplaceError::InvalidAccount
+    );
+    let fee = total_price
+        .checked_mul(marketplace.fee_bps as u64)
+        .ok_or(MarketplaceError::MathOverflow)?
+        / 10_000;
+    let seller_amount = total_price
+        .checked_sub(fee)
+        .ok_or(MarketplaceError::MathOverflow)?;
 
-#[derive(Accounts)]
-pub struct CreateListing<'info> {
-    #[account(mut)]
-    pub marketplace: Account<'info, Marketplace>,
-    #[account(mut, has_one = marketplace, has_one = owner)]
-    pub merchant: Account<'info, Merchant>,
-    #[account(mut)]
-    pub owner: Signer<'info>,
-    #[account(
-        init,
-        payer = owner,
-        space = 8 + Listing::SIZE,
-        seeds = [b"listing", merchant.key().as_ref(), &merchant.next_nonce.to_le_bytes()],
-        bump
-    )]
-    pub listing: Account<'info, Listing>,
-    pub mint: Account<'info, Mint>,
-    pub system_program: Program<'info, System>,
+    token::transfer(
+        CpiContext::new(
+            token_program.to_account_info(),
+            Transfer {
+                from: buyer_ata.to_account_info(),
+                to: seller_ata.to_account_info(),
+                authority: buyer.to_account_info(),
+            },
+        ),
+        seller_amount,
+    )?;
+
+    if fee > 0 {
+        token::transfer(
+            CpiContext::new(
+                token_program.to_account_info(),
+                Transfer {
+                    from: buyer_ata.to_account_info(),
+                    to: treasury_vault.to_account_info(),
+                    authority: buyer.to_account_info(),
+                },
+            ),
+            fee,
+        )?;
+        treasury.record_fee(fee)?;
+    }
+    marketplace.record_orders(1)?;
+    treasury.record_volume(total_price)?;
+    merchant.record_sale(total_price, decimals)
 }
 
-#[derive(Accounts)]
-pub struct UpdateListing<'info> {
-    #[account(mut, has_one = seller)]
-    pub listing: Account<'info, Listing>,
-    pub seller: Signer<'info>,
+// converts base units of a mint with `decimals` to NORMALIZED_DECIMALS units, rounding down
+pub fn normalize_amount(amount: u64, decimals: u8) -> Result<u64> {
+    if decimals >= NORMALIZED_DECIMALS {
+        // a divisor past u64 leaves nothing of any amount
+        Ok(10u64
+            .checked_pow((decimals - NORMALIZED_DECIMALS) as u32)
+            .map_or(0, |scale| amount / scale))
+    } else {
+        Ok(amount
+            .checked_mul(10u64.pow((NORMALIZED_DECIMALS - decimals) as u32))
+            .ok_or(MarketplaceError::MathOverflow)?)
+    }
 }
 
-#[derive(Accounts)]
-pub struct BuyNow<'info> {
-    #[account(mut, has_one = marketplace, has_one = mint)]
-    pub listing: Account<'info, Listing>,
-    #[account(mut)]
-    pub marketplace: Account<'info, Marketplace>,
-    #[account(mut)]
-    pub buyer: Signer<'info>,
-    #[account(mut, token::mint = mint, token::authority = buyer)]
-    pub buyer_ata: Account<'info, TokenAccount>,
-    #[account(mut)]
-    pub seller_ata: Account<'info, TokenAccount>,
-    #[account(mut, token::mint = mint, token::authority = marketplace.authority)]
-    pub treasury_ata: Account<'info, TokenAccount>,
-    pub mint: Account<'info, Mint>,
-    pub token_program: Program<'info, Token>,
+// converts NORMALIZED_DECIMALS units back to base units of a mint with `decimals`, rounding down
+pub fn denormalize_amount(amount: u64, decimals: u8) -> Result<u64> {
+    if decimals >= NORMALIZED_DECIMALS {
+        10u64
+            .checked_pow((decimals - NORMALIZED_DECIMALS) as u32)
+            .and_then(|scale| amount.checked_mul(scale))
+            .ok_or(error!(MarketplaceError::MathOverflow))
+    } else {
+        Ok(amount / 10u64.pow((NORMALIZED_DECIMALS - decimals) as u32))
+    }
 }
 
-#[derive(Accounts)]
-pub struct CreateServiceOrder<'info> {
-    pub marketplace: Account<'info, Marketplace>,
-    #[account(mut, has_one = marketplace)]
-    pub listing: Account<'info, Listing>,
-    #[account(mut)]
-    pub buyer: Signer<'info>,
-    #[account(mut, token::mint = mint, token::authority = buyer)]
-    pub buyer_ata: Account<'info, TokenAccount>,
-    #[account(
-        init,
-        payer = buyer,
-        space = 8 + Escrow::SIZE,
-        seeds = [b"escrow", listing.key().as_ref(), buyer.key().as_ref()],
-        bump
-    )]
-    pub escrow: Account<'info, Escrow>,
-    #[account(
-        init,
-        payer = buyer,
-        associated_token::mint = mint,
-        associated_token::authority = escrow
-    )]
-    pub vault: Account<'info, TokenAccount>,
-    pub mint: Account<'info, Mint>,
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
+// account compression instruction discriminators (Anchor sighashes of the compression program)
+const CMT_INIT_EMPTY_MERKLE_TREE: [u8; 8] = [191, 11, 119, 7, 180, 107, 220, 110];
+const CMT_APPEND: [u8; 8] = [149, 120, 18, 222, 236, 225, 88, 203];
+const CMT_REPLACE_LEAF: [u8; 8] = [204, 165, 76, 100, 73, 147, 0, 128];
+
+// calls the compression program with the listing tree PDA signing as tree authority
+fn compression_cpi<'info>(
+    merkle_tree: &AccountInfo<'info>,
+    listing_tree: &Account<'info, ListingTree>,
+    noop_program: &AccountInfo<'info>,
+    proof: &[AccountInfo<'info>],
+    data: Vec<u8>,
+) -> Result<()> {
+    let mut accounts = vec![
+        AccountMeta::new(merkle_tree.key(), false),
+        AccountMeta::new_readonly(listing_tree.key(), true),
+        AccountMeta::new_readonly(noop_program.key(), false),
+    ];
+    accounts.extend(proof.iter().map(|p| AccountMeta::new_readonly(p.key(), false)));
+    let ix = Instruction {
+        program_id: ACCOUNT_COMPRESSION_PROGRAM_ID,
+        accounts,
+        data,
+    };
+
+    let mut infos = vec![
+        merkle_tree.clone(),
+        listing_tree.to_account_info(),
+        noop_program.clone(),
+    ];
+    infos.extend_from_slice(proof);
+
+    let merkle_tree_key = merkle_tree.key();
+    let seeds: &[&[u8]] = &[b"listing_tree", merkle_tree_key.as_ref(), &[listing_tree.bump]];
+    invoke_signed(&ix, &infos, &[seeds]).map_err(Into::into)
 }
 
-#[derive(Accounts)]
-pub struct ReleaseServiceOrder<'info> {
-    #[account(mut, has_one = marketplace)]
-    pub escrow: Account<'info, Escrow>,
-    pub marketplace: Account<'info, Marketplace>,
-    pub listing: Account<'info, Listing>,
-    #[account(constraint = payer.key() == escrow.buyer || payer.key() == marketplace.authority)]
-    pub payer: Signer<'info>,
-    /// CHECK: destination for vault rent, verified via constraint
-    #[account(mut, constraint = buyer.key() == escrow.buyer @ MarketplaceError::InvalidAccount)]
-    pub buyer: UncheckedAccount<'info>,
-    #[account(mut)]
-    pub seller_ata: Account<'info, TokenAccount>,
-    #[account(mut, token::mint = escrow.mint, token::authority = marketplace.authority)]
-    pub treasury_ata: Account<'info, TokenAccount>,
-    #[account(mut, token::authority = escrow, close = buyer)]
-    pub vault: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
+// replace_leaf fails unless `previous` hashes to the leaf at its index under a recent root
+fn replace_compressed_leaf<'info>(
+    merkle_tree: &AccountInfo<'info>,
+    listing_tree: &Account<'info, ListingTree>,
+    noop_program: &AccountInfo<'info>,
+    proof: &[AccountInfo<'info>],
+    root: [u8; 32],
+    previous: &CompressedListing,
+    new_leaf: [u8; 32],
+) -> Result<()> {
+    let mut data = CMT_REPLACE_LEAF.to_vec();
+    data.extend_from_slice(&root);
+    data.extend_from_slice(&previous.leaf());
+    data.extend_from_slice(&new_leaf);
+    data.extend_from_slice(&previous.index.to_le_bytes());
+    compression_cpi(merkle_tree, listing_tree, noop_program, proof, data)
 }
 
-//Backend can cancel, rent refunded
-#[derive(Accounts)]
-pub struct CancelServiceOrder<'info> {
-    #[account(mut)]
-    pub escrow: Account<'info, Escrow>,
-    pub marketplace: Account<'info, Marketplace>,
-    #[account(constraint = payer.key() == escrow.buyer || payer.key() == marketplace.authority)]
-    pub payer: Signer<'info>,
-    /// CHECK: destination for vault rent, verified via constraint
-    #[account(mut, constraint = buyer.key() == escrow.buyer @ MarketplaceError::InvalidAccount)]
-    pub buyer: UncheckedAccount<'info>,
-    #[account(mut, token::mint = escrow.mint, token::authority = escrow.buyer)]
-    pub buyer_ata: Account<'info, TokenAccount>,
-    #[account(mut, token::authority = escrow, close = buyer)]
-    pub vault: Account<'info, TokenAccount>,
-    pub token_program: Program<'info, Token>,
+// Minimal reader for Pyth-style (legacy v2) price accounts. Only the aggregate price is used.
+pub struct PriceFeed {
+    pub price: i64,
+    // confidence interval around `price`, same scale
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
 }
 
+impl PriceFeed {
+    const MAGIC: u32 = 0xa1b2_c3d4;
+    const ACCOUNT_TYPE_PRICE: u32 = 3;
+    const STATUS_TRADING: u32 = 1;
+    const EXPO_OFFSET: usize = 20;
+    const TIMESTAMP_OFFSET: usize = 96;
+    const AGG_PRICE_OFFSET: usize = 208;
+    const AGG_CONF_OFFSET: usize = 216;
+    const AGG_STATUS_OFFSET: usize = 224;
+    const MIN_LEN: usize = 240;
 
-impl Listing {
-    pub fn get_fee_bps(&self, mp: &Account<Marketplace>) -> Result<u16> {
-        require!(self.marketplace == mp.key(), MarketplaceError::WrongMarketplace);
-        Ok(mp.fee_bps)
+    pub fn load(info: &AccountInfo, oracle_program: Pubkey) -> Result<Self> {
+        require!(
+            oracle_program != Pubkey::default() && *info.owner == oracle_program,
+            MarketplaceError::UntrustedPriceFeed
+        );
+        let data = info.try_borrow_data()?;
+        require!(data.len() >= Self::MIN_LEN, MarketplaceError::InvalidPriceFeed);
+
+        let read_u32 = |o: usize| u32::from_le_bytes(data[o..o + 4].try_into().unwrap());
+        let read_i64 = |o: usize| i64::from_le_bytes(data[o..o + 8].try_into().unwrap());
+        let read_u64 = |o: usize| u64::from_le_bytes(data[o..o + 8].try_into().unwrap());
+
+        require!(
+            read_u32(0) == Self::MAGIC
+                && read_u32(8) == Self::ACCOUNT_TYPE_PRICE
+                && read_u32(Self::AGG_STATUS_OFFSET) == Self::STATUS_TRADING,
+            MarketplaceError::InvalidPriceFeed
+        );
+
+        let feed = PriceFeed {
+            price: read_i64(Self::AGG_PRICE_OFFSET),
+            conf: read_u64(Self::AGG_CONF_OFFSET),
+            expo: read_u32(Self::EXPO_OFFSET) as i32,
+            publish_time: read_i64(Self::TIMESTAMP_OFFSET),
+        };
+        require!(feed.price > 0, MarketplaceError::InvalidPriceFeed);
+        Ok(feed)
     }
-}
 
-impl Escrow {
-    pub fn get_fee_bps(&self, mp: &Account<Marketplace>) -> Result<u16> {
-        require!(self.marketplace == mp.key(), MarketplaceError::WrongMarketplace);
-        Ok(mp.fee_bps)
+    pub fn check_usable(&self, now: i64) -> Result<()> {
+        require!(
+            now.saturating_sub(self.publish_time) <= MAX_PRICE_AGE_SECS,
+            MarketplaceError::StalePrice
+        );
+        require!(
+            (self.conf as u128) * 10_000 <= (self.price as u128) * MAX_PRICE_CONF_BPS as u128,
+            MarketplaceError::PriceTooUncertain
+        );
+        Ok(())
+    }
+
+    // feed price is quote units per whole token, scaled by 10^expo; rounds up in the seller's favour
+    pub fn quote_to_tokens(&self, quote_amount: u64, mint_decimals: u8) -> Result<u64> {
+        let pow10 = |e: u32| 10u128.checked_pow(e).ok_or(MarketplaceError::MathOverflow);
+
+        let mut num = (quote_amount as u128)
+            .checked_mul(pow10(mint_decimals as u32)?)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        let mut den = (self.price as u128)
+            .checked_mul(pow10(QUOTE_DECIMALS)?)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        if self.expo < 0 {
+            num = num
+                .checked_mul(pow10(self.expo.unsigned_abs())?)
+                .ok_or(MarketplaceError::MathOverflow)?;
+        } else {
+            den = den
+                .checked_mul(pow10(self.expo as u32)?)
+                .ok_or(MarketplaceError::MathOverflow)?;
+        }
+
+        let amount = num
+            .checked_add(den - 1)
+            .ok_or(MarketplaceError::MathOverflow)?
+            / den;
+        u64::try_from(amount).map_err(|_| error!(MarketplaceError::MathOverflow))
     }
-}
\ No newline at end of file
+}
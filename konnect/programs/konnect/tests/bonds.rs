@@ -0,0 +1,61 @@
+mod common;
+
+use ::konnect::*;
+use anchor_lang::prelude::*;
+use common::{error_code, zeroed};
+
+fn bond(mint: Pubkey, amount: u64, unlock_at: Option<i64>) -> MerchantBond {
+    let mut b: MerchantBond = zeroed(MerchantBond::SIZE);
+    b.mint = mint;
+    b.amount = amount;
+    b.unlock_at = unlock_at;
+    b
+}
+
+#[test]
+fn withdrawals_wait_for_the_cooldown() {
+    let b = bond(Pubkey::new_unique(), 1_000, Some(100));
+    assert_eq!(error_code(b.check_withdrawable(99, 500)), u32::from(MarketplaceError::BondLocked));
+    b.check_withdrawable(100, 500).unwrap();
+    b.check_withdrawable(100, 1_000).unwrap();
+    assert_eq!(error_code(b.check_withdrawable(100, 1_001)), u32::from(MarketplaceError::InvalidAmount));
+    assert_eq!(error_code(b.check_withdrawable(100, 0)), u32::from(MarketplaceError::InvalidAmount));
+}
+
+#[test]
+fn withdrawals_need_a_request_first() {
+    let b = bond(Pubkey::new_unique(), 1_000, None);
+    assert_eq!(error_code(b.check_withdrawable(i64::MAX, 500)), u32::from(MarketplaceError::BondLocked));
+}
+
+#[test]
+fn only_a_settled_bond_of_the_right_mint_lets_unverified_merchants_list() {
+    let mint = Pubkey::new_unique();
+    let cfg = BondConfig { mint, min_amount: 1_000, cooldown_secs: 60 };
+    let mut m: Merchant = zeroed(Merchant::SIZE);
+    let required = u32::from(MarketplaceError::BondRequired);
+
+    assert_eq!(error_code(m.check_can_list(&cfg, None)), required);
+    assert_eq!(error_code(m.check_can_list(&cfg, Some(&bond(mint, 999, None)))), required);
+    assert_eq!(
+        error_code(m.check_can_list(&cfg, Some(&bond(Pubkey::new_unique(), 1_000, None)))),
+        required
+    );
+    // a pending withdrawal stops the bond counting until the next deposit clears it
+    assert_eq!(error_code(m.check_can_list(&cfg, Some(&bond(mint, 1_000, Some(100))))), required);
+    m.check_can_list(&cfg, Some(&bond(mint, 1_000, None))).unwrap();
+
+    m.verified = true;
+    m.check_can_list(&cfg, None).unwrap();
+    m.check_can_list(&BondConfig { min_amount: 0, ..cfg }, None).unwrap();
+}
+
+#[test]
+fn deposits_stay_in_the_bond_mint_until_it_is_emptied() {
+    let (old_mint, new_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let cfg = BondConfig { mint: new_mint, min_amount: 1_000, cooldown_secs: 60 };
+    // the marketplace moved to a new bond mint after this bond was opened
+    assert_eq!(bond(old_mint, 500, None).deposit_mint(&cfg), old_mint);
+    assert_eq!(bond(old_mint, 0, None).deposit_mint(&cfg), new_mint);
+    assert_eq!(zeroed::<MerchantBond>(MerchantBond::SIZE).deposit_mint(&cfg), new_mint);
+}
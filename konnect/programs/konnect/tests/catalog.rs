@@ -0,0 +1,71 @@
+mod common;
+
+use ::konnect::*;
+use anchor_lang::prelude::*;
+use common::error_code;
+
+// account data is 8-byte aligned on-chain, mirror that with a u64 backing buffer
+fn catalog_data(capacity: u32) -> Vec<u64> {
+    vec![0u64; Catalog::space(capacity).div_ceil(8)]
+}
+
+#[test]
+fn entries_are_aligned_after_the_header() {
+    assert_eq!(Catalog::HEADER_LEN % 8, 0);
+    assert_eq!(Catalog::ENTRY_LEN, 56);
+
+    let mut buf = catalog_data(3);
+    let data = &mut bytemuck::cast_slice_mut::<u64, u8>(&mut buf)[..Catalog::space(3)];
+    let (header, entries) = Catalog::split_mut(data).unwrap();
+    assert_eq!(entries.len(), 3);
+
+    header.entry_count = 1;
+    entries[2].price = 42;
+    assert_eq!(Catalog::capacity(Catalog::space(3)), 3);
+
+    let (header, entries) = Catalog::split_mut(data).unwrap();
+    assert_eq!(header.entry_count, 1);
+    assert_eq!(entries[2].price, 42);
+}
+
+// rent per sellable item; compute units are measured in tests/catalog.ts
+#[test]
+fn catalog_entry_rent_is_a_fraction_of_a_listing() {
+    let rent = Rent::default();
+    let listing_len = 8 + Listing::SIZE + ListingMetadata::default().space();
+    assert_eq!(listing_len, 669);
+    let listing = rent.minimum_balance(listing_len);
+
+    let capacity = 1_000;
+    let per_entry = rent.minimum_balance(Catalog::space(capacity)) / capacity as u64;
+
+    // the header is shared, so an entry costs little more than its own 56 bytes
+    assert!(per_entry < rent.minimum_balance(2 * Catalog::ENTRY_LEN));
+    assert!(per_entry * 10 < listing);
+}
+
+#[test]
+fn removed_entries_are_no_longer_addressable() {
+    let mut buf = catalog_data(2);
+    let data = &mut bytemuck::cast_slice_mut::<u64, u8>(&mut buf)[..Catalog::space(2)];
+    let (header, entries) = Catalog::split_mut(data).unwrap();
+    header.entry_count = 2;
+    entries[0].price = 5;
+    entries[0].quantity = 3;
+    entries[0].active = 1;
+    entries[0].content_hash = [4; 32];
+
+    header.entry_mut(entries, 0).unwrap().remove().unwrap();
+    let removed = entries[0];
+    assert_eq!(removed.revision, 1);
+    assert_eq!(removed.price, 0);
+    assert_eq!(removed.quantity, 0);
+    assert_eq!(removed.active, 0);
+    assert_eq!(removed.content_hash, [0; 32]);
+
+    let invalid = u32::from(MarketplaceError::InvalidCatalogEntry);
+    assert_eq!(error_code(header.entry_mut(entries, 0).map(|_| ())), invalid);
+    // past entry_count even though the slot exists
+    assert_eq!(error_code(header.entry_mut(entries, 2).map(|_| ())), invalid);
+    assert!(header.entry_mut(entries, 1).is_ok());
+}
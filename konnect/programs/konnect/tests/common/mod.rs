@@ -0,0 +1,20 @@
+// Helpers shared by the integration tests; each test crate uses only some of them.
+#![allow(dead_code)]
+
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+// an account as a freshly allocated, all-zero buffer reads back
+pub fn zeroed<T: AccountDeserialize + Discriminator>(size: usize) -> T {
+    let mut data = T::DISCRIMINATOR.to_vec();
+    data.resize(8 + size, 0);
+    T::try_deserialize(&mut &data[..]).unwrap()
+}
+
+pub fn error_code<T>(result: Result<T>) -> u32 {
+    match result {
+        Err(Error::AnchorError(e)) => e.error_code_number,
+        Err(e) => panic!("expected an anchor error, got {e:?}"),
+        Ok(_) => panic!("expected an error"),
+    }
+}
@@ -0,0 +1,65 @@
+use ::konnect::*;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::spl_associated_token_account::solana_program::hash::hash;
+
+fn listing() -> CompressedListing {
+    CompressedListing {
+        marketplace: Pubkey::new_from_array([1; 32]),
+        listing_tree: Pubkey::new_from_array([2; 32]),
+        seller: Pubkey::new_from_array([3; 32]),
+        mint: Pubkey::new_from_array([4; 32]),
+        index: 5,
+        price: 2_000_000,
+        quantity: 3,
+        active: true,
+        metadata_hash: [6; 32],
+    }
+}
+
+// clients rebuild leaves from events, so the hash must be over the plain borsh encoding
+#[test]
+fn leaf_is_sha256_of_borsh_encoding() {
+    let l = listing();
+    let encoded = l.try_to_vec().unwrap();
+    assert_eq!(encoded.len(), 4 * 32 + 4 + 8 + 4 + 1 + 32);
+    assert_eq!(l.leaf(), hash(&encoded).to_bytes());
+}
+
+#[test]
+fn any_field_change_changes_the_leaf() {
+    let base = listing().leaf();
+
+    let mut l = listing();
+    l.quantity -= 1;
+    assert_ne!(l.leaf(), base);
+
+    let mut l = listing();
+    l.active = false;
+    assert_ne!(l.leaf(), base);
+
+    let mut l = listing();
+    l.index += 1;
+    assert_ne!(l.leaf(), base);
+}
+
+// orders name the listing, not the tree it is stored in, and the id survives leaf updates
+#[test]
+fn asset_id_is_per_listing_and_stable() {
+    let l = listing();
+    assert_ne!(l.asset_id(), l.listing_tree);
+
+    let mut updated = listing();
+    updated.quantity -= 1;
+    updated.price += 1;
+    assert_eq!(updated.asset_id(), l.asset_id());
+
+    let mut other = listing();
+    other.index += 1;
+    assert_ne!(other.asset_id(), l.asset_id());
+
+    let (expected, _) = Pubkey::find_program_address(
+        &[b"compressed_listing", l.listing_tree.as_ref(), &5u32.to_le_bytes()],
+        &::konnect::ID,
+    );
+    assert_eq!(l.asset_id(), expected);
+}
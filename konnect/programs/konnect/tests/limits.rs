@@ -0,0 +1,85 @@
+mod common;
+
+use ::konnect::*;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::spl_associated_token_account::solana_program::hash::hashv;
+use common::error_code;
+
+fn parent(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    if a <= b {
+        hashv(&[&a, &b]).to_bytes()
+    } else {
+        hashv(&[&b, &a]).to_bytes()
+    }
+}
+
+// a four-wallet tree built the way merchant tooling does: sha256 leaves, sorted pairs
+fn tree(wallets: &[Pubkey; 4]) -> ([u8; 32], Vec<Vec<[u8; 32]>>) {
+    let leaves: Vec<[u8; 32]> = wallets
+        .iter()
+        .map(|w| hashv(&[w.as_ref()]).to_bytes())
+        .collect();
+    let left = parent(leaves[0], leaves[1]);
+    let right = parent(leaves[2], leaves[3]);
+    let proofs = vec![
+        vec![leaves[1], right],
+        vec![leaves[0], right],
+        vec![leaves[3], left],
+        vec![leaves[2], left],
+    ];
+    (parent(left, right), proofs)
+}
+
+fn limits(allowlist_root: Option<[u8; 32]>) -> PurchaseLimits {
+    PurchaseLimits {
+        max_per_wallet: None,
+        allowlist_root,
+    }
+}
+
+#[test]
+fn every_allowlisted_wallet_proves_membership() {
+    let wallets = [(); 4].map(|_| Pubkey::new_unique());
+    let (root, proofs) = tree(&wallets);
+    for (wallet, proof) in wallets.iter().zip(&proofs) {
+        assert!(PurchaseLimits::is_allowlisted(root, proof, wallet));
+        assert!(limits(Some(root)).check_allowlist(Some(proof), wallet).is_ok());
+    }
+}
+
+#[test]
+fn other_wallets_and_bad_proofs_are_rejected() {
+    let wallets = [(); 4].map(|_| Pubkey::new_unique());
+    let (root, proofs) = tree(&wallets);
+    let outsider = Pubkey::new_unique();
+    let l = limits(Some(root));
+
+    assert!(!PurchaseLimits::is_allowlisted(root, &proofs[0], &outsider));
+    // someone else's proof
+    assert!(!PurchaseLimits::is_allowlisted(root, &proofs[1], &wallets[0]));
+    // a truncated proof
+    assert!(!PurchaseLimits::is_allowlisted(root, &proofs[0][..1], &wallets[0]));
+
+    for (proof, wallet) in [(Some(&proofs[0][..]), &outsider), (None, &wallets[0])] {
+        assert_eq!(
+            error_code(l.check_allowlist(proof, wallet)),
+            u32::from(MarketplaceError::NotAllowlisted)
+        );
+    }
+}
+
+#[test]
+fn a_single_wallet_list_is_its_own_root() {
+    let wallet = Pubkey::new_unique();
+    let root = hashv(&[wallet.as_ref()]).to_bytes();
+    assert!(limits(Some(root)).check_allowlist(Some(&[]), &wallet).is_ok());
+}
+
+#[test]
+fn listings_without_a_root_ignore_proofs() {
+    let l = limits(None);
+    assert!(l.is_open());
+    assert!(l.check_allowlist(None, &Pubkey::new_unique()).is_ok());
+    assert!(l.check_allowlist(Some(&[[1; 32]]), &Pubkey::new_unique()).is_ok());
+    assert!(!limits(Some([0; 32])).is_open());
+}
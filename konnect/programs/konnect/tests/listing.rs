@@ -0,0 +1,167 @@
+mod common;
+
+use ::konnect::*;
+use anchor_lang::prelude::*;
+use common::error_code;
+
+// a listing as create_listing leaves it, without metadata
+fn listing() -> Listing {
+    let mut data = Listing::DISCRIMINATOR.to_vec();
+    data.resize(8 + Listing::SIZE, 0);
+    let mut l = Listing::try_deserialize(&mut &data[..]).unwrap();
+    l.active = true;
+    l.version = ACCOUNT_VERSION;
+    l
+}
+
+#[test]
+fn release_escrow_counts_down_and_saturates() {
+    let mut l = listing();
+    l.open_escrows = 2;
+    l.release_escrow();
+    assert_eq!(l.open_escrows, 1);
+
+    l.open_escrows = 0;
+    l.release_escrow();
+    assert_eq!(l.open_escrows, 0);
+}
+
+#[test]
+fn buyer_view_pins_revision_and_total() {
+    let mut l = listing();
+    l.bump_revision().unwrap();
+    assert_eq!(l.revision, 1);
+
+    assert!(l.check_buyer_view(None, 1_000, None).is_ok());
+    assert!(l.check_buyer_view(Some(1), 1_000, Some(1_000)).is_ok());
+    assert_eq!(
+        error_code(l.check_buyer_view(Some(0), 1_000, None)),
+        u32::from(MarketplaceError::ListingChanged)
+    );
+    assert_eq!(
+        error_code(l.check_buyer_view(None, 1_001, Some(1_000))),
+        u32::from(MarketplaceError::MaxTotalExceeded)
+    );
+}
+
+#[test]
+fn price_guard_allows_slippage_down_to_its_bound() {
+    let guard = PriceGuard {
+        expected_rate: 10_000,
+        max_slippage_bps: 100,
+    };
+    // rates above the expectation only favour the buyer
+    assert!(guard.check(12_000).is_ok());
+    assert!(guard.check(9_900).is_ok());
+    assert_eq!(
+        error_code(guard.check(9_899)),
+        u32::from(MarketplaceError::SlippageExceeded)
+    );
+
+    let exact = PriceGuard {
+        expected_rate: 10_000,
+        max_slippage_bps: 0,
+    };
+    assert_eq!(
+        error_code(exact.check(9_999)),
+        u32::from(MarketplaceError::SlippageExceeded)
+    );
+}
+
+#[test]
+fn held_stock_keeps_a_sold_out_listing_active() {
+    let mut l = listing();
+    l.quantity = 1;
+    l.reserved = 2;
+    l.take_stock(1).unwrap();
+    assert_eq!(l.quantity, 0);
+    assert!(l.active);
+
+    l.restock_reserved(2).unwrap();
+    assert_eq!((l.quantity, l.reserved), (2, 0));
+    l.take_stock(2).unwrap();
+    assert!(!l.active);
+}
+
+#[test]
+fn stock_counters_never_underflow() {
+    let mut l = listing();
+    l.quantity = 1;
+    assert_eq!(
+        error_code(l.take_stock(2)),
+        u32::from(MarketplaceError::MathOverflow)
+    );
+    assert_eq!(
+        error_code(l.restock_reserved(1)),
+        u32::from(MarketplaceError::MathOverflow)
+    );
+    assert_eq!(l.quantity, 1);
+}
+
+#[test]
+fn selling_out_a_variant_deactivates_it() {
+    let mut v = Variant {
+        listing: Pubkey::new_unique(),
+        id: 1,
+        price: 1_000,
+        quantity: 2,
+        active: true,
+        bump: 255,
+    };
+    v.take_stock(1).unwrap();
+    assert!(v.active);
+    v.take_stock(1).unwrap();
+    assert!(!v.active);
+    assert_eq!(
+        error_code(v.take_stock(1)),
+        u32::from(MarketplaceError::MathOverflow)
+    );
+}
+
+#[test]
+fn returned_stock_relists_a_sold_out_listing() {
+    let mut l = listing();
+    l.quantity = 1;
+    l.take_stock(1).unwrap();
+    assert!(!l.active);
+
+    l.restock(2).unwrap();
+    assert!(l.active);
+    assert_eq!(l.quantity, 2);
+}
+
+#[test]
+fn returned_stock_keeps_a_delisted_listing_delisted() {
+    let mut l = listing();
+    l.quantity = 3;
+    l.active = false;
+    l.restock(1).unwrap();
+    assert!(!l.active);
+    assert_eq!(l.quantity, 4);
+
+    l.quantity = u32::MAX;
+    assert_eq!(
+        error_code(l.restock(1)),
+        u32::from(MarketplaceError::MathOverflow)
+    );
+}
+
+#[test]
+fn returned_stock_relists_a_sold_out_variant() {
+    let mut v = Variant {
+        listing: Pubkey::new_unique(),
+        id: 1,
+        price: 1_000,
+        quantity: 1,
+        active: true,
+        bump: 255,
+    };
+    v.take_stock(1).unwrap();
+    v.restock(1).unwrap();
+    assert!(v.active);
+    assert_eq!(v.quantity, 1);
+
+    v.active = false;
+    v.restock(1).unwrap();
+    assert!(!v.active);
+}
@@ -0,0 +1,127 @@
+mod common;
+
+use ::konnect::*;
+use anchor_lang::prelude::*;
+use common::error_code;
+
+fn metadata() -> ListingMetadata {
+    ListingMetadata {
+        category: 3,
+        tags: vec!["shoes".to_string(), "running".to_string()],
+        uri: "https://example.com/listing.json".to_string(),
+        content_hash: [7; 32],
+        images: vec!["ipfs://bafy".to_string(), "ar://tx".to_string()],
+    }
+}
+
+#[test]
+fn space_matches_the_borsh_encoding() {
+    for m in [ListingMetadata::default(), metadata()] {
+        assert_eq!(m.space(), m.try_to_vec().unwrap().len());
+    }
+}
+
+// the account is sized for the longest name and image plus the metadata it carries
+#[test]
+fn listing_fits_its_account_at_maximum_lengths() {
+    let mut data = Listing::DISCRIMINATOR.to_vec();
+    data.resize(8 + Listing::SIZE, 0);
+    let mut l = Listing::try_deserialize(&mut &data[..]).unwrap();
+    l.name = "n".repeat(MAX_NAME_LEN);
+    l.image_url = format!("https://{}", "i".repeat(MAX_IMAGE_URL_LEN - 8));
+    l.price_feed = Some(Pubkey::new_unique());
+    l.sale_window = SaleWindow {
+        sale_start: Some(1),
+        sale_end: Some(2),
+    };
+    l.limits = PurchaseLimits {
+        max_per_wallet: Some(1),
+        allowlist_root: Some([1; 32]),
+    };
+    l.metadata = metadata();
+
+    let mut out = Vec::new();
+    l.try_serialize(&mut out).unwrap();
+    assert!(out.len() <= 8 + Listing::SIZE + l.metadata.space());
+}
+
+#[test]
+fn metadata_limits_are_enforced() {
+    assert!(metadata().validate().is_ok());
+    assert!(ListingMetadata::default().validate().is_ok());
+
+    let mut m = metadata();
+    m.tags = vec!["t".to_string(); MAX_TAGS + 1];
+    assert_eq!(error_code(m.validate()), u32::from(MarketplaceError::TooManyTags));
+
+    let mut m = metadata();
+    m.tags = vec![String::new()];
+    assert_eq!(error_code(m.validate()), u32::from(MarketplaceError::InvalidTag));
+    m.tags = vec!["t".repeat(MAX_TAG_LEN + 1)];
+    assert_eq!(error_code(m.validate()), u32::from(MarketplaceError::InvalidTag));
+
+    let mut m = metadata();
+    m.uri = format!("https://{}", "u".repeat(MAX_METADATA_URI_LEN));
+    assert_eq!(error_code(m.validate()), u32::from(MarketplaceError::MetadataUriTooLong));
+    m.uri = "http://insecure.example".to_string();
+    assert_eq!(error_code(m.validate()), u32::from(MarketplaceError::UnsupportedUrl));
+
+    let mut m = metadata();
+    m.images = vec!["ipfs://x".to_string(); MAX_EXTRA_IMAGES + 1];
+    assert_eq!(error_code(m.validate()), u32::from(MarketplaceError::TooManyImages));
+    m.images = vec![String::new()];
+    assert_eq!(error_code(m.validate()), u32::from(MarketplaceError::UnsupportedUrl));
+}
+
+#[test]
+fn names_are_bounded_and_printable() {
+    assert!(Listing::validate_name("Trail shoes").is_ok());
+    assert!(Listing::validate_name("Ünïcødé 靴").is_ok());
+    assert!(Listing::validate_name(&"n".repeat(MAX_NAME_LEN)).is_ok());
+
+    assert_eq!(
+        error_code(Listing::validate_name(&"n".repeat(MAX_NAME_LEN + 1))),
+        u32::from(MarketplaceError::NameTooLong)
+    );
+    // the limit is in bytes, which is what the account is sized for
+    assert_eq!(
+        error_code(Listing::validate_name(&"靴".repeat(MAX_NAME_LEN / 3 + 1))),
+        u32::from(MarketplaceError::NameTooLong)
+    );
+    for bad in ["", "   ", "tab\tname", "line\nbreak"] {
+        assert_eq!(
+            error_code(Listing::validate_name(bad)),
+            u32::from(MarketplaceError::InvalidName),
+            "{bad:?}"
+        );
+    }
+}
+
+#[test]
+fn image_urls_use_supported_schemes() {
+    for ok in ["", "https://cdn.example.com/a.png", "ipfs://bafy", "ar://tx"] {
+        assert!(Listing::validate_image_url(ok).is_ok(), "{ok:?}");
+    }
+    let longest = format!("https://{}", "i".repeat(MAX_IMAGE_URL_LEN - 8));
+    assert!(Listing::validate_image_url(&longest).is_ok());
+    assert_eq!(
+        error_code(Listing::validate_image_url(&format!("{longest}i"))),
+        u32::from(MarketplaceError::ImageUrlTooLong)
+    );
+
+    for bad in [
+        "http://example.com/a.png",
+        "javascript:alert(1)",
+        "data:image/png;base64,AAAA",
+        "https://",
+        "HTTPS://example.com/a.png",
+        "https://example.com/a b.png",
+        "ipfs://bafy\n",
+    ] {
+        assert_eq!(
+            error_code(Listing::validate_image_url(bad)),
+            u32::from(MarketplaceError::UnsupportedUrl),
+            "{bad:?}"
+        );
+    }
+}
@@ -0,0 +1,207 @@
+// Fixtures are raw account data (discriminator included) serialized with older layouts,
+// zero padded to the size the accounts were allocated with.
+mod common;
+
+use ::konnect::*;
+use anchor_lang::prelude::*;
+use common::error_code;
+
+fn key(n: u8) -> Pubkey {
+    Pubkey::new_from_array([n; 32])
+}
+
+fn read_fixture<T: AnchorDeserialize>(data: &[u8], discriminator: &[u8]) -> Result<T> {
+    let owner = ::konnect::ID;
+    let address = key(0);
+    let mut lamports = 0;
+    let mut data = data.to_vec();
+    let info = AccountInfo::new(&address, false, true, &mut lamports, &mut data, &owner, false, 0);
+    read_legacy(&info, discriminator)
+}
+
+// serializes the upgraded account and reads it back through the current layout
+fn roundtrip<T: AccountSerialize + AccountDeserialize>(account: &T, len: usize) -> T {
+    let mut data = vec![0u8; len];
+    account.try_serialize(&mut &mut data[..]).unwrap();
+    T::try_deserialize(&mut &data[..]).unwrap()
+}
+
+#[test]
+fn migrates_marketplace_v0() {
+    let data = include_bytes!("fixtures/marketplace_v0.bin");
+    assert_eq!(data.len(), 8 + MarketplaceV0::SIZE);
+
+    let old: MarketplaceV0 = read_fixture(data, Marketplace::DISCRIMINATOR).unwrap();
+    let mp = roundtrip(&old.upgrade(), 8 + Marketplace::SIZE);
+    assert_eq!(mp.version, Marketplace::VERSION);
+    assert_eq!(mp.authority, key(1));
+    assert_eq!(mp.fee_bps, 250);
+    assert_eq!(mp.bump, 254);
+    // fields added since version 0 start out as init_marketplace would set them
+    assert_eq!(mp.id, 0);
+    assert_eq!(mp.metadata.name, "");
+    assert_eq!(mp.metadata.default_mint, Pubkey::default());
+    assert_eq!(mp.return_window_secs, 0);
+    assert!(mp.fee_refund_policy == FeeRefundPolicy::RetainFee);
+    assert_eq!(mp.arbiter, key(1));
+    assert_eq!(mp.treasury_authority, key(1));
+    assert_eq!(mp.bond.mint, Pubkey::default());
+    assert_eq!(mp.bond.cooldown_secs, DEFAULT_BOND_COOLDOWN_SECS);
+    assert_eq!(mp.merchant_count, 0);
+    assert_eq!(mp.order_count, 0);
+    assert_eq!(mp.open_escrow_count, 0);
+    assert_eq!(mp.points_bps, 0);
+    assert_eq!(mp.oracle_program, Pubkey::default());
+}
+
+#[test]
+fn migrates_marketplace_v1() {
+    let data = include_bytes!("fixtures/marketplace_v1.bin");
+    assert_eq!(data.len(), 8 + Marketplace::V1_SIZE);
+
+    let mut grown = data.to_vec();
+    grown.resize(8 + Marketplace::SIZE, 0xff);
+    let mut mp: Marketplace = read_grown(&mut grown, data.len()).unwrap();
+    assert_eq!(mp.version, 1);
+    mp.version = Marketplace::VERSION;
+    let mp = roundtrip(&mp, 8 + Marketplace::SIZE);
+    assert_eq!(mp.id, 3);
+    assert_eq!(mp.metadata.name, "Konnect");
+    assert_eq!(mp.authority, key(1));
+    assert_eq!(mp.fee_bps, 250);
+    assert_eq!(mp.arbiter, key(2));
+    assert_eq!(mp.treasury_authority, key(3));
+    assert_eq!(mp.order_count, 17);
+    // version 1 kept the oracle program in its padding
+    assert_eq!(mp.oracle_program, key(7));
+    assert_eq!(mp.padding, [0; ACCOUNT_PADDING_LEN]);
+}
+
+#[test]
+fn migrates_merchant_v0() {
+    let data = include_bytes!("fixtures/merchant_v0.bin");
+    assert_eq!(data.len(), 8 + MerchantV0::SIZE);
+
+    let old: MerchantV0 = read_fixture(data, Merchant::DISCRIMINATOR).unwrap();
+    let m = roundtrip(&old.upgrade(), 8 + Merchant::SIZE);
+    assert_eq!(m.version, ACCOUNT_VERSION);
+    assert_eq!(m.marketplace, key(5));
+    assert_eq!(m.owner, key(6));
+    assert!(m.verified);
+    assert_eq!(m.bump, 253);
+    assert_eq!(m.next_nonce, 42);
+    assert_eq!(m.rating_sum, 0);
+    assert_eq!(m.rating_count, 0);
+    assert_eq!(m.sales_count, 0);
+    assert_eq!(m.sales_volume, 0);
+}
+
+#[test]
+fn migrates_listing_v0() {
+    let data = include_bytes!("fixtures/listing_v0.bin");
+    assert_eq!(data.len(), 8 + ListingV0::SIZE);
+
+    let old: ListingV0 = read_fixture(data, Listing::DISCRIMINATOR).unwrap();
+    let upgraded = old.upgrade();
+    let l = roundtrip(&upgraded, 8 + Listing::SIZE + upgraded.metadata.space());
+    assert_eq!(l.version, ACCOUNT_VERSION);
+    assert_eq!(l.marketplace, key(5));
+    assert_eq!(l.seller, key(6));
+    assert_eq!(l.mint, key(9));
+    assert_eq!(l.price, 2_500_000);
+    assert_eq!(l.quantity, 8);
+    assert!(!l.is_service);
+    assert!(l.active);
+    assert_eq!(l.bump, 252);
+    assert_eq!(l.name, "Ceramic mug");
+    assert_eq!(l.image_url, "https://konnect.example/mug.png");
+    assert_eq!(l.nonce, 41);
+    assert_eq!(l.open_escrows, 0);
+    assert_eq!(l.price_feed, None);
+    assert_eq!(l.revision, 0);
+    assert_eq!(l.reserved, 0);
+    assert_eq!(l.variant_count, 0);
+    assert!(l.metadata.tags.is_empty());
+    assert!(l.metadata.images.is_empty());
+    assert_eq!(l.sale_window.sale_start, None);
+    assert_eq!(l.limits.max_per_wallet, None);
+    assert_eq!(l.rating_count, 0);
+}
+
+#[test]
+fn migrates_escrow_v0() {
+    let data = include_bytes!("fixtures/escrow_v0.bin");
+    assert_eq!(data.len(), 8 + EscrowV0::SIZE);
+
+    let old: EscrowV0 = read_fixture(data, Escrow::DISCRIMINATOR).unwrap();
+    let e = roundtrip(&old.upgrade(), 8 + Escrow::SIZE);
+    assert_eq!(e.version, Escrow::VERSION);
+    assert_eq!(e.marketplace, key(5));
+    assert_eq!(e.listing, key(11));
+    assert_eq!(e.seller, key(6));
+    assert_eq!(e.buyer, key(12));
+    assert_eq!(e.mint, key(9));
+    assert_eq!(e.amount, 15_000_000);
+    assert_eq!(e.reference, key(13));
+    assert!(e.released);
+    assert_eq!(e.bump, 251);
+    // version 0 escrows were always funded by the buyer
+    assert_eq!(e.rent_payer, key(12));
+    assert_eq!(e.rent_destination(), key(12));
+    // without a recorded funding account any account of the buyer takes the refund
+    assert_eq!(e.funding_account, Pubkey::default());
+    assert!(e.is_refund_account(key(14), key(12)));
+    assert!(!e.is_refund_account(key(14), key(15)));
+}
+
+#[test]
+fn migrates_escrow_v1() {
+    let data = include_bytes!("fixtures/escrow_v1.bin");
+    assert_eq!(data.len(), 8 + Escrow::V1_SIZE);
+
+    // grown accounts may hold stale bytes past the old layout
+    let mut grown = data.to_vec();
+    grown.resize(8 + Escrow::SIZE, 0xff);
+    let mut e: Escrow = read_grown(&mut grown, data.len()).unwrap();
+    assert_eq!(e.version, 1);
+    e.version = Escrow::VERSION;
+    let e = roundtrip(&e, 8 + Escrow::SIZE);
+    assert_eq!(e.version, Escrow::VERSION);
+    assert_eq!(e.buyer, key(12));
+    assert_eq!(e.amount, 15_000_000);
+    assert!(!e.released);
+    // the fields version 1 kept in its padding carry over
+    assert_eq!(e.rent_payer, key(16));
+    assert_eq!(e.funding_account, key(14));
+    assert_eq!(e.padding, [0; ACCOUNT_PADDING_LEN]);
+}
+
+#[test]
+fn legacy_reader_rejects_wrong_discriminator() {
+    let data = include_bytes!("fixtures/merchant_v0.bin");
+    assert!(read_fixture::<EscrowV0>(data, Escrow::DISCRIMINATOR).is_err());
+}
+
+#[test]
+fn legacy_len_check_separates_migrated_and_unknown_layouts() {
+    let legacy = 8 + MerchantV0::SIZE;
+    let current = 8 + Merchant::SIZE;
+    assert!(check_legacy_len(legacy, legacy, current).is_ok());
+    assert_eq!(
+        error_code(check_legacy_len(current, legacy, current)),
+        u32::from(MarketplaceError::AlreadyMigrated)
+    );
+    // listings grow past their base size with metadata
+    assert_eq!(
+        error_code(check_legacy_len(current + 40, legacy, current)),
+        u32::from(MarketplaceError::AlreadyMigrated)
+    );
+    assert_eq!(
+        error_code(check_legacy_len(legacy + 1, legacy, current)),
+        u32::from(MarketplaceError::UnknownAccountLayout)
+    );
+    assert_eq!(
+        error_code(check_legacy_len(8, legacy, current)),
+        u32::from(MarketplaceError::UnknownAccountLayout)
+    );
+}
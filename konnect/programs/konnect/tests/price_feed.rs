@@ -0,0 +1,122 @@
+mod common;
+
+use ::konnect::*;
+use anchor_lang::prelude::*;
+use common::error_code;
+
+const ORACLE: Pubkey = Pubkey::new_from_array([9; 32]);
+const NOW: i64 = 1_700_000_000;
+
+fn feed(price: i64, conf: u64, expo: i32) -> PriceFeed {
+    PriceFeed {
+        price,
+        conf,
+        expo,
+        publish_time: NOW,
+    }
+}
+
+// a Pyth v2 price account with only the fields the program reads filled in
+fn price_account(price: i64, conf: u64, expo: i32, status: u32) -> Vec<u8> {
+    let mut data = vec![0u8; 3312];
+    data[0..4].copy_from_slice(&0xa1b2_c3d4u32.to_le_bytes());
+    data[8..12].copy_from_slice(&3u32.to_le_bytes());
+    data[20..24].copy_from_slice(&expo.to_le_bytes());
+    data[96..104].copy_from_slice(&NOW.to_le_bytes());
+    data[208..216].copy_from_slice(&price.to_le_bytes());
+    data[216..224].copy_from_slice(&conf.to_le_bytes());
+    data[224..228].copy_from_slice(&status.to_le_bytes());
+    data
+}
+
+fn load(data: &mut [u8], owner: &Pubkey, oracle_program: Pubkey) -> Result<PriceFeed> {
+    let key = Pubkey::new_unique();
+    let mut lamports = 0;
+    let info = AccountInfo::new(&key, false, false, &mut lamports, data, owner, false, 0);
+    PriceFeed::load(&info, oracle_program)
+}
+
+#[test]
+fn quote_converts_with_token_and_feed_decimals() {
+    // 150.00000000 quote per token, 6-decimal token: 30 quote units buy 0.2 tokens
+    let f = feed(15_000_000_000, 0, -8);
+    assert_eq!(f.quote_to_tokens(30_000_000, 6).unwrap(), 200_000);
+    // 9-decimal token at the same rate
+    assert_eq!(f.quote_to_tokens(30_000_000, 9).unwrap(), 200_000_000);
+    // positive exponent: 2 * 10^2 quote per token
+    assert_eq!(feed(2, 0, 2).quote_to_tokens(1_000_000, 6).unwrap(), 5_000);
+}
+
+#[test]
+fn quote_rounds_up_for_the_seller() {
+    // 3.00000000 quote per token: 1 quote unit is 0.333... tokens
+    let f = feed(300_000_000, 0, -8);
+    assert_eq!(f.quote_to_tokens(1_000_000, 6).unwrap(), 333_334);
+    assert_eq!(f.quote_to_tokens(3_000_000, 6).unwrap(), 1_000_000);
+    assert_eq!(f.quote_to_tokens(1, 0).unwrap(), 1);
+    assert_eq!(f.quote_to_tokens(0, 6).unwrap(), 0);
+}
+
+#[test]
+fn quote_overflow_is_an_error() {
+    let f = feed(1, 0, -30);
+    assert_eq!(
+        error_code(f.quote_to_tokens(u64::MAX, 9)),
+        u32::from(MarketplaceError::MathOverflow)
+    );
+}
+
+#[test]
+fn stale_prices_are_rejected() {
+    let f = feed(100, 0, 0);
+    assert!(f.check_usable(NOW).is_ok());
+    assert!(f.check_usable(NOW + MAX_PRICE_AGE_SECS).is_ok());
+    assert_eq!(
+        error_code(f.check_usable(NOW + MAX_PRICE_AGE_SECS + 1)),
+        u32::from(MarketplaceError::StalePrice)
+    );
+}
+
+#[test]
+fn wide_confidence_intervals_are_rejected() {
+    let price = 10_000_000_000;
+    let at_limit = price as u64 * MAX_PRICE_CONF_BPS / 10_000;
+    assert!(feed(price, at_limit, -8).check_usable(NOW).is_ok());
+    assert_eq!(
+        error_code(feed(price, at_limit + 1, -8).check_usable(NOW)),
+        u32::from(MarketplaceError::PriceTooUncertain)
+    );
+}
+
+#[test]
+fn loads_price_accounts_owned_by_the_oracle_program() {
+    let mut data = price_account(15_000_000_000, 1_000_000, -8, 1);
+    let f = load(&mut data, &ORACLE, ORACLE).unwrap();
+    assert_eq!((f.price, f.conf, f.expo, f.publish_time), (15_000_000_000, 1_000_000, -8, NOW));
+}
+
+#[test]
+fn feeds_from_other_programs_are_untrusted() {
+    let untrusted = u32::from(MarketplaceError::UntrustedPriceFeed);
+    let mut data = price_account(100, 0, 0, 1);
+    assert_eq!(error_code(load(&mut data, &Pubkey::new_unique(), ORACLE)), untrusted);
+    // nothing is trusted until the marketplace configures an oracle program
+    assert_eq!(
+        error_code(load(&mut data, &Pubkey::default(), Pubkey::default())),
+        untrusted
+    );
+}
+
+#[test]
+fn malformed_or_halted_feeds_are_invalid() {
+    let invalid = u32::from(MarketplaceError::InvalidPriceFeed);
+    // not trading
+    let mut data = price_account(100, 0, 0, 2);
+    assert_eq!(error_code(load(&mut data, &ORACLE, ORACLE)), invalid);
+    // non-positive price
+    let mut data = price_account(0, 0, 0, 1);
+    assert_eq!(error_code(load(&mut data, &ORACLE, ORACLE)), invalid);
+    // truncated account
+    let mut data = price_account(100, 0, 0, 1)[..200].to_vec();
+    assert_eq!(error_code(load(&mut data, &ORACLE, ORACLE)), invalid);
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use ::konnect::*;
+use anchor_lang::prelude::*;
+use common::{error_code, zeroed};
+
+#[test]
+fn refunds_take_the_sale_off_the_merchant() {
+    let mut m: Merchant = zeroed(Merchant::SIZE);
+    m.record_sale(5_000, 6).unwrap();
+    m.record_sale(2_000, 6).unwrap();
+    m.reverse_sale(2_000, 6).unwrap();
+    assert_eq!((m.sales_count, m.sales_volume), (1, 5_000));
+
+    // sales recorded before the counters existed can't push them below zero
+    m.reverse_sale(9_000, 6).unwrap();
+    m.reverse_sale(1, 6).unwrap();
+    assert_eq!((m.sales_count, m.sales_volume), (0, 0));
+}
+
+#[test]
+fn refunds_claw_back_earned_points_and_return_spent_ones() {
+    let mut p: BuyerProfile = zeroed(BuyerProfile::SIZE);
+    p.points = 500;
+    p.lifetime_points = 800;
+
+    assert_eq!(p.reverse_points(300, 40).unwrap(), 300);
+    assert_eq!(p.points, 240);
+    assert_eq!(p.lifetime_points, 500);
+}
+
+#[test]
+fn points_already_spent_are_not_clawed_back() {
+    let mut p: BuyerProfile = zeroed(BuyerProfile::SIZE);
+    p.points = 100;
+    p.lifetime_points = 300;
+
+    assert_eq!(p.reverse_points(300, 0).unwrap(), 100);
+    assert_eq!(p.points, 0);
+    assert_eq!(p.lifetime_points, 0);
+}
+
+#[test]
+fn points_are_worth_the_same_in_every_mint() {
+    let mut p: BuyerProfile = zeroed(BuyerProfile::SIZE);
+    // 1% back on 10 tokens of a 6-decimal mint and on 10 tokens of a 9-decimal one
+    assert_eq!(p.credit(10_000_000, 6, 100).unwrap(), 100_000);
+    assert_eq!(p.credit(10_000_000_000, 9, 100).unwrap(), 100_000);
+    assert_eq!(p.points, 200_000);
+
+    // 0.15 tokens staged against a 0.1 token fee in a 9-decimal mint
+    p.pending_discount = 150_000;
+    assert_eq!(p.take_discount(100_000_000, 9).unwrap(), (100_000, 100_000_000));
+    assert_eq!(p.pending_discount, 50_000);
+
+    // a 2-decimal mint can only take whole cents off, the rest stays staged
+    p.pending_discount = 12_345;
+    assert_eq!(p.take_discount(1_000, 2).unwrap(), (10_000, 1));
+    assert_eq!(p.pending_discount, 2_345);
+}
+
+// a finance wallet's account funded the order placed by an employee
+#[test]
+fn delegated_orders_refund_the_funding_account() {
+    let employee = Pubkey::new_from_array([1; 32]);
+    let finance = Pubkey::new_from_array([2; 32]);
+    let finance_ata = Pubkey::new_from_array([3; 32]);
+    let employee_ata = Pubkey::new_from_array([4; 32]);
+
+    let mut e: Escrow = zeroed(Escrow::SIZE);
+    e.buyer = employee;
+    e.funding_account = finance_ata;
+    assert!(e.is_refund_account(finance_ata, finance));
+    // not even the beneficiary's own account
+    assert!(!e.is_refund_account(employee_ata, employee));
+
+    let mut r: Receipt = zeroed(Receipt::SIZE);
+    r.buyer = employee;
+    r.funding_account = finance_ata;
+    assert!(r.is_refund_account(finance_ata, finance));
+    assert!(!r.is_refund_account(employee_ata, employee));
+}
+
+#[test]
+fn unrecorded_funding_accounts_refund_any_buyer_account() {
+    let buyer = Pubkey::new_from_array([1; 32]);
+    let buyer_ata = Pubkey::new_from_array([3; 32]);
+    let other = Pubkey::new_from_array([5; 32]);
+
+    let mut e: Escrow = zeroed(Escrow::SIZE);
+    e.buyer = buyer;
+    assert!(e.is_refund_account(buyer_ata, buyer));
+    assert!(!e.is_refund_account(buyer_ata, other));
+
+    let mut r: Receipt = zeroed(Receipt::SIZE);
+    r.buyer = buyer;
+    assert!(r.is_refund_account(buyer_ata, buyer));
+    assert!(!r.is_refund_account(buyer_ata, other));
+}
+
+#[test]
+fn service_receipts_are_not_returnable() {
+    let mut r: Receipt = zeroed(Receipt::SIZE);
+    assert!(r.check_returnable().is_ok());
+    r.is_service = true;
+    assert_eq!(
+        error_code(r.check_returnable()),
+        u32::from(MarketplaceError::WrongFlowForService)
+    );
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use ::konnect::*;
+use common::error_code;
+
+fn window(sale_start: Option<i64>, sale_end: Option<i64>) -> SaleWindow {
+    SaleWindow {
+        sale_start,
+        sale_end,
+    }
+}
+
+#[test]
+fn unbounded_windows_are_always_on_sale() {
+    let w = SaleWindow::default();
+    assert!(w.is_unbounded());
+    assert!(w.validate().is_ok());
+    assert!(w.check(i64::MIN).is_ok());
+    assert!(w.check(i64::MAX).is_ok());
+}
+
+#[test]
+fn start_is_inclusive_and_end_exclusive() {
+    let w = window(Some(100), Some(200));
+    assert!(!w.is_unbounded());
+    assert_eq!(error_code(w.check(99)), u32::from(MarketplaceError::SaleNotStarted));
+    assert!(w.check(100).is_ok());
+    assert!(w.check(199).is_ok());
+    assert_eq!(error_code(w.check(200)), u32::from(MarketplaceError::SaleEnded));
+}
+
+#[test]
+fn either_bound_may_be_left_open() {
+    let drop = window(Some(100), None);
+    assert_eq!(error_code(drop.check(0)), u32::from(MarketplaceError::SaleNotStarted));
+    assert!(drop.check(i64::MAX).is_ok());
+
+    let promo = window(None, Some(100));
+    assert!(promo.check(i64::MIN).is_ok());
+    assert_eq!(error_code(promo.check(100)), u32::from(MarketplaceError::SaleEnded));
+}
+
+#[test]
+fn windows_must_end_after_they_start() {
+    assert!(window(Some(1), Some(2)).validate().is_ok());
+    for w in [window(Some(2), Some(2)), window(Some(3), Some(2))] {
+        assert_eq!(error_code(w.validate()), u32::from(MarketplaceError::InvalidSaleWindow));
+    }
+}
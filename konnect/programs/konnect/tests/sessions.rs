@@ -0,0 +1,55 @@
+mod common;
+
+use ::konnect::*;
+use anchor_lang::prelude::*;
+use common::{error_code, zeroed};
+
+fn session(spend_limit: u64, expires_at: i64) -> SessionKey {
+    let mut s: SessionKey = zeroed(SessionKey::SIZE);
+    s.buyer = Pubkey::new_from_array([1; 32]);
+    s.signer = Pubkey::new_from_array([2; 32]);
+    s.mint = Pubkey::new_from_array([3; 32]);
+    s.spend_limit = spend_limit;
+    s.expires_at = expires_at;
+    s
+}
+
+fn spend(s: &mut SessionKey, now: i64, amount: u64) -> Result<()> {
+    let (buyer, signer, mint) = (s.buyer, s.signer, s.mint);
+    s.authorize_spend(buyer, signer, mint, now, amount)
+}
+
+#[test]
+fn spends_add_up_to_the_cap() {
+    let mut s = session(1_000, 100);
+    spend(&mut s, 10, 600).unwrap();
+    spend(&mut s, 10, 400).unwrap();
+    assert_eq!(s.spent, 1_000);
+
+    assert_eq!(
+        error_code(spend(&mut s, 10, 1)),
+        u32::from(MarketplaceError::SessionLimitExceeded)
+    );
+    // a rejected spend is not counted
+    assert_eq!(s.spent, 1_000);
+}
+
+#[test]
+fn expired_sessions_are_rejected() {
+    let mut s = session(1_000, 100);
+    spend(&mut s, 99, 1).unwrap();
+    assert_eq!(error_code(spend(&mut s, 100, 1)), u32::from(MarketplaceError::InvalidSession));
+}
+
+#[test]
+fn sessions_only_authorize_their_own_signer_buyer_and_mint() {
+    let mut s = session(1_000, 100);
+    let other = Pubkey::new_from_array([9; 32]);
+    let (buyer, signer, mint) = (s.buyer, s.signer, s.mint);
+    let invalid = u32::from(MarketplaceError::InvalidSession);
+
+    assert_eq!(error_code(s.authorize_spend(buyer, other, mint, 10, 1)), invalid);
+    assert_eq!(error_code(s.authorize_spend(other, signer, mint, 10, 1)), invalid);
+    assert_eq!(error_code(s.authorize_spend(buyer, signer, other, 10, 1)), invalid);
+    assert_eq!(s.spent, 0);
+}
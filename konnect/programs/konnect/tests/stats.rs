@@ -0,0 +1,47 @@
+mod common;
+
+use ::konnect::*;
+use common::{error_code, zeroed};
+
+#[test]
+fn amounts_are_normalized_to_six_decimals() {
+    assert_eq!(normalize_amount(1_500_000, 6).unwrap(), 1_500_000);
+    // 1.5 tokens of a 9-decimal mint, dust below a micro-unit is dropped
+    assert_eq!(normalize_amount(1_500_000_999, 9).unwrap(), 1_500_000);
+    // 1.5 tokens of a 2-decimal mint
+    assert_eq!(normalize_amount(150, 2).unwrap(), 1_500_000);
+    assert!(normalize_amount(u64::MAX, 0).is_err());
+    assert_eq!(normalize_amount(u64::MAX, 255).unwrap(), 0);
+}
+
+#[test]
+fn merchant_volume_adds_up_across_mints() {
+    let mut m: Merchant = zeroed(Merchant::SIZE);
+    m.record_sale(2_000_000, 6).unwrap();
+    m.record_sale(3_000_000_000, 9).unwrap();
+    m.record_sale(100, 2).unwrap();
+    assert_eq!((m.sales_count, m.sales_volume), (3, 6_000_000));
+
+    m.reverse_sale(3_000_000_000, 9).unwrap();
+    assert_eq!((m.sales_count, m.sales_volume), (2, 3_000_000));
+
+    // an amount that can't be normalized is an error rather than wiping the volume
+    assert_eq!(error_code(m.reverse_sale(u64::MAX, 0)), u32::from(MarketplaceError::MathOverflow));
+    assert_eq!((m.sales_count, m.sales_volume), (2, 3_000_000));
+}
+
+#[test]
+fn escrow_counters_never_underflow() {
+    let mut mp: Marketplace = zeroed(Marketplace::SIZE);
+    mp.open_escrow().unwrap();
+    mp.close_escrow();
+    // an escrow opened before the counter existed
+    mp.close_escrow();
+    assert_eq!(mp.open_escrow_count, 0);
+
+    let mut t: Treasury = zeroed(Treasury::SIZE);
+    t.lock_escrow(1_000).unwrap();
+    t.unlock_escrow(1_000);
+    t.unlock_escrow(5_000);
+    assert_eq!(t.open_escrow_amount, 0);
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use ::konnect::*;
+use anchor_lang::prelude::*;
+use common::{error_code, zeroed};
+
+fn split(bps: u16) -> FeeSplit {
+    FeeSplit { recipient: Pubkey::new_unique(), bps }
+}
+
+#[test]
+fn splits_must_cover_the_whole_vault() {
+    let invalid = u32::from(MarketplaceError::InvalidFeeSplits);
+
+    FeeSplit::validate(&[]).unwrap();
+    FeeSplit::validate(&[split(10_000)]).unwrap();
+    FeeSplit::validate(&[split(7_000), split(3_000)]).unwrap();
+    assert_eq!(error_code(FeeSplit::validate(&[split(7_000), split(2_999)])), invalid);
+    assert_eq!(error_code(FeeSplit::validate(&[split(7_000), split(3_001)])), invalid);
+
+    let too_many: Vec<FeeSplit> = (0..MAX_FEE_SPLITS + 1).map(|_| split(0)).collect();
+    assert_eq!(error_code(FeeSplit::validate(&too_many)), invalid);
+    let mut most: Vec<FeeSplit> = (0..MAX_FEE_SPLITS).map(|_| split(0)).collect();
+    most[0].bps = 10_000;
+    FeeSplit::validate(&most).unwrap();
+}
+
+#[test]
+fn shares_round_down_and_never_exceed_the_balance() {
+    let splits = [split(3_333), split(3_333), split(3_334)];
+    let shares: Vec<u64> = splits.iter().map(|s| s.share_of(100)).collect();
+    assert_eq!(shares, vec![33, 33, 33]);
+
+    let shares: Vec<u64> = splits.iter().map(|s| s.share_of(u64::MAX)).collect();
+    assert!(shares.iter().map(|&s| s as u128).sum::<u128>() <= u64::MAX as u128);
+    assert_eq!(split(10_000).share_of(u64::MAX), u64::MAX);
+    assert_eq!(split(1).share_of(9_999), 0);
+}
+
+#[test]
+fn treasury_accounting_tracks_fees_volume_and_escrows() {
+    let mut t: Treasury = zeroed(Treasury::SIZE);
+    t.record_fee(200).unwrap();
+    t.record_fee(50).unwrap();
+    t.record_volume(10_000).unwrap();
+    t.lock_escrow(5_000).unwrap();
+    t.lock_escrow(1_000).unwrap();
+    t.unlock_escrow(5_000);
+    assert_eq!((t.total_collected, t.volume, t.open_escrow_amount), (250, 10_000, 1_000));
+
+    t.total_collected = u64::MAX;
+    assert_eq!(error_code(t.record_fee(1)), u32::from(MarketplaceError::MathOverflow));
+}
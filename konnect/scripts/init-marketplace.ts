@@ -23,7 +23,7 @@ async function initializeMarketplace() {
 
   //Generate marketplace pda
   const marketplacePda = anchor.web3.PublicKey.findProgramAddressSync(
-    [Buffer.from("marketplace"), provider.wallet.publicKey.toBuffer()],
+    [Buffer.from("marketplace"), provider.wallet.publicKey.toBuffer(), new anchor.BN(0).toArrayLike(Buffer, "le", 2)],
     program.programId
   )[0];
 
@@ -32,7 +32,11 @@ async function initializeMarketplace() {
   try {
     //initialize marketplace with 2% fee (200 bpS)
     const tx = await program.methods
-      .initMarketplace(200)
+      .initMarketplace(0, 200, {
+        name: "Konnect",
+        uri: "",
+        defaultMint: anchor.web3.PublicKey.default,
+      })
       .accounts({
         marketplace: marketplacePda,
         authority: provider.wallet.publicKey,
@@ -55,7 +55,7 @@ async function main() {
 
   //initialize marketplace
   const [marketplacePda] = anchor.web3.PublicKey.findProgramAddressSync(
-    [Buffer.from("marketplace"), wallet.publicKey.toBuffer()],
+    [Buffer.from("marketplace"), wallet.publicKey.toBuffer(), new anchor.BN(0).toArrayLike(Buffer, "le", 2)],
     program.programId
   );
   console.log(`Marketplace PDA: ${marketplacePda.toString()}`);
@@ -65,7 +65,11 @@ async function main() {
     console.log("Marketplace already exists\n");
   } catch {
     const tx = await program.methods
-      .initMarketplace(500)
+      .initMarketplace(0, 500, {
+        name: "Konnect",
+        uri: "",
+        defaultMint: anchor.web3.PublicKey.default,
+      })
       .accounts({
         marketplace: marketplacePda,
         authority: wallet.publicKey,
@@ -0,0 +1,112 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  getAccount,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import { Market, eventOf, expectError, pda, u16 } from "./utils/market";
+
+const { SystemProgram } = anchor.web3;
+type PublicKey = anchor.web3.PublicKey;
+
+describe("badges", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  let m: Market;
+
+  before(async () => {
+    m = await Market.create();
+  });
+
+  const badgeOf = (id: number) => {
+    const badge = pda(Buffer.from("badge"), m.marketplace.toBuffer(), u16(id));
+    return { badge, mint: pda(Buffer.from("badge_mint"), badge.toBuffer()) };
+  };
+
+  const badgeAccountOf = (id: number, user: PublicKey) =>
+    getAssociatedTokenAddressSync(badgeOf(id).mint, user, true, TOKEN_2022_PROGRAM_ID);
+
+  const createBadge = (id: number, kind: object, threshold: number) =>
+    m.program.methods
+      .createBadge(id, `Badge ${id}`, kind as any, new anchor.BN(threshold))
+      .accountsPartial({
+        marketplace: m.marketplace,
+        badge: badgeOf(id).badge,
+        badgeMint: badgeOf(id).mint,
+        authority: m.authority.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  // measured against the buyer profile, or the merchant account when `merchant` is set
+  const award = (id: number, user: PublicKey, opts: { buyerProfile?: PublicKey; merchant?: PublicKey } = {}) => {
+    const { badge, mint } = badgeOf(id);
+    return m.program.methods
+      .awardBadge()
+      .accountsPartial({
+        marketplace: m.marketplace,
+        badge,
+        badgeMint: mint,
+        user,
+        badgeAward: pda(Buffer.from("badge_award"), badge.toBuffer(), user.toBuffer()),
+        badgeAccount: badgeAccountOf(id, user),
+        buyerProfile: opts.buyerProfile ?? null,
+        merchant: opts.merchant ?? null,
+        payer: m.authority.publicKey,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      });
+  };
+
+  it("Awards a first-purchase badge once the buyer has ordered", async () => {
+    await createBadge(1, { buyerOrders: {} }, 1);
+    const listing = await m.createListing(1_000_000, 10);
+    const buyer = await m.buyer(10_000_000);
+    const user = buyer.kp.publicKey;
+
+    // no profile yet, so nothing earned
+    await expectError(award(1, user).rpc(), "BadgeNotEarned");
+
+    await m.buyNow(listing, buyer, 1).rpc();
+    const sig = await award(1, user, { buyerProfile: m.profileOf(user) }).rpc();
+
+    expect((await eventOf(sig, "badgeAwarded")).id).to.equal(1);
+    const account = await getAccount(m.connection, badgeAccountOf(1, user), "confirmed", TOKEN_2022_PROGRAM_ID);
+    expect(Number(account.amount)).to.equal(1);
+    expect((await m.program.account.badgeDefinition.fetch(badgeOf(1).badge)).awardedCount).to.equal(1);
+
+    // the award record is created once per user
+    await expectError(award(1, user, { buyerProfile: m.profileOf(user) }).rpc(), "already in use");
+  });
+
+  it("Measures merchant badges on the seller's sales", async () => {
+    await createBadge(2, { merchantSales: {} }, 2);
+    const seller = m.seller.publicKey;
+    // one sale so far, from the first test
+    await expectError(award(2, seller, { merchant: m.merchant }).rpc(), "BadgeNotEarned");
+
+    const listing = await m.createListing(1_000_000, 1);
+    await m.buyNow(listing, await m.buyer(1_000_000), 1).rpc();
+    await award(2, seller, { merchant: m.merchant }).rpc();
+  });
+
+  it("Stops awarding retired badges", async () => {
+    await createBadge(3, { buyerOrders: {} }, 1);
+    await m.program.methods
+      .setBadgeStatus(false)
+      .accountsPartial({ badge: badgeOf(3).badge, marketplace: m.marketplace, authority: m.authority.publicKey })
+      .rpc();
+
+    const listing = await m.createListing(1_000_000, 1);
+    const buyer = await m.buyer(1_000_000);
+    await m.buyNow(listing, buyer, 1).rpc();
+    await expectError(
+      award(3, buyer.kp.publicKey, { buyerProfile: m.profileOf(buyer.kp.publicKey) }).rpc(),
+      "BadgeInactive"
+    );
+  });
+});
@@ -0,0 +1,152 @@
+import * as anchor from "@coral-xyz/anchor";
+import { ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_PROGRAM_ID, createMint, mintTo } from "@solana/spl-token";
+import { expect } from "chai";
+import { Market, balance, eventOf, expectError, pda, sleep } from "./utils/market";
+
+const { Keypair, SystemProgram } = anchor.web3;
+type PublicKey = anchor.web3.PublicKey;
+
+const MIN_BOND = 1_000_000;
+const COOLDOWN_SECS = 2;
+
+describe("merchant bonds", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  let m: Market;
+  let bond: PublicKey;
+
+  const bondConfig = (mint: PublicKey) => ({
+    mint,
+    minAmount: new anchor.BN(MIN_BOND),
+    cooldownSecs: new anchor.BN(COOLDOWN_SECS),
+  });
+
+  const vaultOf = (mint: PublicKey) => anchor.utils.token.associatedAddress({ mint, owner: bond });
+
+  const ownerAtaOf = (mint: PublicKey) =>
+    anchor.utils.token.associatedAddress({ mint, owner: m.seller.publicKey });
+
+  const deposit = (amount: number, mint = m.mint) =>
+    m.program.methods
+      .depositBond(new anchor.BN(amount))
+      .accountsPartial({
+        marketplace: m.marketplace,
+        merchant: m.merchant,
+        bond,
+        vault: vaultOf(mint),
+        owner: m.seller.publicKey,
+        ownerAta: ownerAtaOf(mint),
+        mint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([m.seller]);
+
+  const requestWithdrawal = () =>
+    m.program.methods
+      .requestBondWithdrawal()
+      .accountsPartial({ bond, merchant: m.merchant, marketplace: m.marketplace, owner: m.seller.publicKey })
+      .signers([m.seller]);
+
+  const withdraw = (amount: number, mint = m.mint) =>
+    m.program.methods
+      .withdrawBond(new anchor.BN(amount))
+      .accountsPartial({
+        bond,
+        vault: vaultOf(mint),
+        owner: m.seller.publicKey,
+        ownerAta: ownerAtaOf(mint),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([m.seller]);
+
+  const list = () => m.createListing(1_000_000, 1, false, {}, { bond });
+
+  before(async () => {
+    m = await Market.create();
+    bond = pda(Buffer.from("bond"), m.merchant.toBuffer());
+    await m.updateMarketplace({ bond: bondConfig(m.mint) }).rpc();
+    await m.fund(m.sellerAta, 5_000_000);
+  });
+
+  it("Lets unverified merchants list only against a sufficient bond", async () => {
+    await expectError(m.createListing(1_000_000, 1), "BondRequired");
+    await deposit(MIN_BOND / 2).rpc();
+    await expectError(list(), "BondRequired");
+
+    await deposit(MIN_BOND / 2).rpc();
+    await list();
+    const b = await m.program.account.merchantBond.fetch(bond);
+    expect(b.amount.toNumber()).to.equal(MIN_BOND);
+    expect(await balance(vaultOf(m.mint))).to.equal(MIN_BOND);
+  });
+
+  it("Releases the bond only after the cooldown", async () => {
+    await expectError(withdraw(100_000).rpc(), "BondLocked");
+    await requestWithdrawal().rpc();
+    await expectError(withdraw(100_000).rpc(), "BondLocked");
+    // a bond on its way out no longer counts for listing
+    await expectError(list(), "BondRequired");
+
+    await sleep((COOLDOWN_SECS + 2) * 1_000);
+    const before = await balance(m.sellerAta);
+    await withdraw(100_000).rpc();
+    expect((await balance(m.sellerAta)) - before).to.equal(100_000);
+
+    // topping back up makes it count again
+    await deposit(100_000).rpc();
+    await list();
+  });
+
+  it("Lets the arbiter slash the bond to a buyer", async () => {
+    const buyer = await m.buyer(0);
+    const reference = Keypair.generate().publicKey;
+    const sig = await m.program.methods
+      .slashBond(new anchor.BN(250_000), reference)
+      .accountsPartial({
+        bond,
+        merchant: m.merchant,
+        marketplace: m.marketplace,
+        arbiter: m.authority.publicKey,
+        vault: vaultOf(m.mint),
+        recipientAta: buyer.ata,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    expect(await balance(buyer.ata)).to.equal(250_000);
+    const slashed = await eventOf(sig, "bondSlashed");
+    expect(slashed.remaining.toNumber()).to.equal(MIN_BOND - 250_000);
+    expect(slashed.reference.toString()).to.equal(reference.toString());
+  });
+
+  it("Keeps topping up in the bond's mint after the marketplace switches mints", async () => {
+    const newMint = await createMint(m.connection, m.authority, m.authority.publicKey, null, 6);
+    await mintTo(
+      m.connection,
+      m.authority,
+      newMint,
+      await m.ata(newMint, m.seller.publicKey),
+      m.authority,
+      5_000_000
+    );
+    await m.updateMarketplace({ bond: bondConfig(newMint) }).rpc();
+
+    // a funded bond stays in the mint it was opened in
+    await expectError(deposit(MIN_BOND, newMint).rpc(), "InvalidAccount");
+    await deposit(250_000).rpc();
+    expect((await m.program.account.merchantBond.fetch(bond)).amount.toNumber()).to.equal(MIN_BOND);
+    // but no longer counts for listing
+    await expectError(list(), "BondRequired");
+
+    // once emptied it reopens in the marketplace's new mint
+    await requestWithdrawal().rpc();
+    await sleep((COOLDOWN_SECS + 2) * 1_000);
+    await withdraw(MIN_BOND).rpc();
+    await deposit(MIN_BOND, newMint).rpc();
+    const b = await m.program.account.merchantBond.fetch(bond);
+    expect(b.mint.toString()).to.equal(newMint.toString());
+    await list();
+  });
+});
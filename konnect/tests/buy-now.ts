@@ -0,0 +1,92 @@
+import * as anchor from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { FEE_BPS, Market, balance, eventOf, expectError } from "./utils/market";
+
+describe("buy now", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  let m: Market;
+
+  before(async () => {
+    m = await Market.create();
+  });
+
+  it("Pays the seller and the treasury and takes stock", async () => {
+    const listing = await m.createListing(2_000_000, 3);
+    const buyer = await m.buyer(10_000_000);
+    const sellerBefore = await balance(m.sellerAta);
+    const treasuryBefore = await balance(m.treasuryVault);
+
+    const sig = await m.buyNow(listing, buyer, 2).rpc();
+
+    const fee = (4_000_000 * FEE_BPS) / 10_000;
+    expect((await balance(m.sellerAta)) - sellerBefore).to.equal(4_000_000 - fee);
+    expect((await balance(m.treasuryVault)) - treasuryBefore).to.equal(fee);
+    expect(await balance(buyer.ata)).to.equal(6_000_000);
+    expect((await m.program.account.listing.fetch(listing)).quantity).to.equal(1);
+
+    const order = await eventOf(sig, "orderCompleted");
+    expect(order.quantity).to.equal(2);
+    expect(order.totalAmount.toNumber()).to.equal(4_000_000);
+    const profile = await m.program.account.buyerProfile.fetch(m.profileOf(buyer.kp.publicKey));
+    expect(profile.ordersCompleted).to.equal(1);
+  });
+
+  it("Deactivates a listing once it sells out", async () => {
+    const listing = await m.createListing(1_000_000, 1);
+    const buyer = await m.buyer(10_000_000);
+    await m.buyNow(listing, buyer, 1).rpc();
+
+    const l = await m.program.account.listing.fetch(listing);
+    expect(l.quantity).to.equal(0);
+    expect(l.active).to.be.false;
+    await expectError(m.buyNow(listing, buyer, 1).rpc(), "ListingInactive");
+  });
+
+  it("Rejects bad quantities and goods-only misuse", async () => {
+    const listing = await m.createListing(1_000_000, 2);
+    const buyer = await m.buyer(10_000_000);
+    await expectError(m.buyNow(listing, buyer, 0).rpc(), "InvalidQuantity");
+    await expectError(m.buyNow(listing, buyer, 3).rpc(), "InvalidQuantity");
+
+    const service = await m.createListing(1_000_000, 0, true);
+    await expectError(m.buyNow(service, buyer, 1).rpc(), "WrongFlowForService");
+  });
+
+  it("Fails when the listing changed since the buyer saw it", async () => {
+    const listing = await m.createListing(1_000_000, 5);
+    const buyer = await m.buyer(10_000_000);
+    const { revision } = await m.program.account.listing.fetch(listing);
+
+    // the seller raises the price between the buyer loading the page and signing
+    await m.updateListing(listing, { price: 3_000_000 }).rpc();
+    await expectError(
+      m.buyNow(listing, buyer, 1, { expectedRevision: revision }).rpc(),
+      "ListingChanged"
+    );
+    await m.buyNow(listing, buyer, 1, { expectedRevision: revision + 1 }).rpc();
+  });
+
+  it("Caps the total the buyer pays", async () => {
+    const listing = await m.createListing(1_000_000, 5);
+    const buyer = await m.buyer(10_000_000);
+
+    await m.updateListing(listing, { price: 1_500_000 }).rpc();
+    await expectError(m.buyNow(listing, buyer, 2, { maxTotal: 2_000_000 }).rpc(), "MaxTotalExceeded");
+    expect(await balance(buyer.ata)).to.equal(10_000_000);
+
+    await m.buyNow(listing, buyer, 2, { maxTotal: 3_000_000 }).rpc();
+    expect(await balance(buyer.ata)).to.equal(7_000_000);
+  });
+
+  it("Requires the reference and a matching seller account", async () => {
+    const listing = await m.createListing(1_000_000, 5);
+    const buyer = await m.buyer(10_000_000);
+
+    await expectError(m.buyNow(listing, buyer, 1, { remaining: [] }).rpc(), "MissingReference");
+    await expectError(
+      m.buyNow(listing, buyer, 1, { accounts: { sellerAta: buyer.ata } }).rpc(),
+      "InvalidAccount"
+    );
+  });
+});
@@ -0,0 +1,225 @@
+import * as anchor from "@coral-xyz/anchor";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { expect } from "chai";
+import { FEE_BPS, Market, balance, eventsOf, expectError, pda, u16, wallet } from "./utils/market";
+
+type PublicKey = anchor.web3.PublicKey;
+
+describe("cart checkout", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  let m: Market;
+  let secondSeller: anchor.web3.Keypair;
+  let secondSellerAta: PublicKey;
+
+  before(async () => {
+    m = await Market.create();
+    await m.updateMarketplace({ pointsBps: 100 }).rpc();
+    secondSeller = await wallet();
+    await m.registerMerchant(secondSeller);
+    secondSellerAta = await m.ata(m.mint, secondSeller.publicKey);
+  });
+
+  type Line = { listing: PublicKey; quantity: number; sellerAta?: PublicKey; merchant?: PublicKey };
+
+  const checkout = (
+    buyer: { kp: anchor.web3.Keypair; ata: PublicKey },
+    lines: Line[],
+    // replaces the reference and per-line accounts
+    remaining?: (reference: PublicKey) => anchor.web3.AccountMeta[]
+  ) => {
+    const reference = anchor.web3.Keypair.generate().publicKey;
+    return m.program.methods
+      .checkoutCart(
+        lines.map(({ listing, quantity }) => ({ listing, quantity })),
+        reference
+      )
+      .accountsPartial({
+        marketplace: m.marketplace,
+        buyer: buyer.kp.publicKey,
+        buyerAta: buyer.ata,
+        treasury: m.treasury,
+        treasuryVault: m.treasuryVault,
+        mint: m.mint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        remaining?.(reference) ?? [
+          { pubkey: reference, isSigner: false, isWritable: false },
+          ...lines.flatMap((line) => [
+            { pubkey: line.listing, isSigner: false, isWritable: true },
+            { pubkey: line.sellerAta ?? m.sellerAta, isSigner: false, isWritable: true },
+            { pubkey: line.merchant ?? m.merchant, isSigner: false, isWritable: true },
+          ]),
+        ]
+      )
+      .signers([buyer.kp]);
+  };
+
+  // a listing of the second seller, created through the shared helper's seller slot
+  const secondSellerListing = async (price: number, quantity: number) => {
+    const [seller, merchant] = [m.seller, m.merchant];
+    m.seller = secondSeller;
+    m.merchant = m.merchantOf(secondSeller.publicKey);
+    try {
+      return await m.createListing(price, quantity);
+    } finally {
+      [m.seller, m.merchant] = [seller, merchant];
+    }
+  };
+
+  it("Buys from several sellers in one transaction", async () => {
+    const a = await m.createListing(1_000_000, 5);
+    const b = await secondSellerListing(3_000_000, 2);
+    const buyer = await m.buyer(10_000_000);
+    const [sellerBefore, secondBefore, treasuryBefore] = [
+      await balance(m.sellerAta),
+      await balance(secondSellerAta),
+      await balance(m.treasuryVault),
+    ];
+
+    const sig = await checkout(buyer, [
+      { listing: a, quantity: 3 },
+      {
+        listing: b,
+        quantity: 1,
+        sellerAta: secondSellerAta,
+        merchant: m.merchantOf(secondSeller.publicKey),
+      },
+    ]).rpc();
+
+    // each line pays its own 2% fee, as it would through buy_now
+    const fee = (line: number) => (line * FEE_BPS) / 10_000;
+    expect((await balance(m.sellerAta)) - sellerBefore).to.equal(3_000_000 - fee(3_000_000));
+    expect((await balance(secondSellerAta)) - secondBefore).to.equal(3_000_000 - fee(3_000_000));
+    expect((await balance(m.treasuryVault)) - treasuryBefore).to.equal(2 * fee(3_000_000));
+    expect(await balance(buyer.ata)).to.equal(4_000_000);
+
+    expect((await m.program.account.listing.fetch(a)).quantity).to.equal(2);
+    expect((await m.program.account.listing.fetch(b)).quantity).to.equal(1);
+
+    const events = await eventsOf(sig);
+    expect(events.filter((e) => e.name === "orderCompleted")).to.have.length(2);
+    const cart = events.find((e) => e.name === "cartCompleted").data as any;
+    expect(cart.itemCount).to.equal(2);
+    expect(cart.totalAmount.toNumber()).to.equal(6_000_000);
+
+    // each line counts towards the buyer's order badges
+    const profile = await m.program.account.buyerProfile.fetch(m.profileOf(buyer.kp.publicKey));
+    expect(profile.ordersCompleted).to.equal(2);
+    // and earns points on the whole cart at 1%, like an order through buy_now
+    expect(profile.points.toNumber()).to.equal(60_000);
+    const points = events.find((e) => e.name === "pointsUpdated").data as any;
+    expect(points.earned.toNumber()).to.equal(60_000);
+    // the buyer's own account funds every line
+    for (const e of events.filter((e) => e.name === "orderCompleted")) {
+      expect((e.data as any).payer.toString()).to.equal(buyer.kp.publicKey.toString());
+    }
+  });
+
+  it("Draws repeated lines of one listing from the same stock", async () => {
+    const listing = await m.createListing(1_000_000, 3);
+    const buyer = await m.buyer(10_000_000);
+
+    await expectError(
+      checkout(buyer, [
+        { listing, quantity: 2 },
+        { listing, quantity: 2 },
+      ]).rpc(),
+      "InvalidQuantity"
+    );
+    await checkout(buyer, [
+      { listing, quantity: 2 },
+      { listing, quantity: 1 },
+    ]).rpc();
+    expect((await m.program.account.listing.fetch(listing)).quantity).to.equal(0);
+  });
+
+  it("Rejects empty, oversized and malformed carts", async () => {
+    const listing = await m.createListing(1_000_000, 20);
+    const buyer = await m.buyer(20_000_000);
+
+    await expectError(checkout(buyer, []).rpc(), "InvalidCart");
+    await expectError(
+      checkout(buyer, Array.from({ length: 11 }, () => ({ listing, quantity: 1 }))).rpc(),
+      "InvalidCart"
+    );
+    // one account triple short
+    await expectError(
+      checkout(buyer, [{ listing, quantity: 1 }], (reference) => [
+        { pubkey: reference, isSigner: false, isWritable: false },
+        { pubkey: listing, isSigner: false, isWritable: true },
+      ]).rpc(),
+      "InvalidCart"
+    );
+    await expectError(checkout(buyer, [{ listing, quantity: 0 }]).rpc(), "InvalidQuantity");
+  });
+
+  it("Rejects lines whose accounts don't match the listing", async () => {
+    const listing = await m.createListing(1_000_000, 5);
+    const buyer = await m.buyer(10_000_000);
+
+    await expectError(
+      checkout(buyer, [{ listing, quantity: 1, sellerAta: secondSellerAta }]).rpc(),
+      "InvalidAccount"
+    );
+    await expectError(
+      checkout(buyer, [
+        { listing, quantity: 1, merchant: m.merchantOf(secondSeller.publicKey) },
+      ]).rpc(),
+      "InvalidAccount"
+    );
+
+    // listings of another marketplace can't be mixed in
+    const other = await Market.create();
+    const foreign = await other.createListing(1_000_000, 5);
+    await expectError(
+      checkout(buyer, [
+        { listing: foreign, quantity: 1, sellerAta: other.sellerAta, merchant: other.merchant },
+      ]).rpc(),
+      "WrongMarketplace"
+    );
+  });
+
+  it("Leaves limited, variant and oracle-priced listings to buy_now", async () => {
+    const buyer = await m.buyer(10_000_000);
+    const limited = await m.createListing(1_000_000, 5, false, {
+      limits: { maxPerWallet: 1, allowlistRoot: null },
+    });
+    await expectError(checkout(buyer, [{ listing: limited, quantity: 1 }]).rpc(), "UnsupportedInCart");
+
+    const quoted = await m.createListing(1_000_000, 5, false, {
+      priceFeed: anchor.web3.Keypair.generate().publicKey,
+    });
+    await expectError(checkout(buyer, [{ listing: quoted, quantity: 1 }]).rpc(), "UnsupportedInCart");
+
+    const withVariants = await m.createListing(1_000_000, 5);
+    await m.program.methods
+      .addVariant(1, new anchor.BN(1_000_000), 5)
+      .accountsPartial({
+        listing: withVariants,
+        seller: m.seller.publicKey,
+        variant: pda(Buffer.from("variant"), withVariants.toBuffer(), u16(1)),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([m.seller])
+      .rpc();
+    await expectError(
+      checkout(buyer, [{ listing: withVariants, quantity: 1 }]).rpc(),
+      "UnsupportedInCart"
+    );
+
+    const inactive = await m.createListing(1_000_000, 5);
+    await m.updateListing(inactive, { active: false }).rpc();
+    await expectError(checkout(buyer, [{ listing: inactive, quantity: 1 }]).rpc(), "ListingInactive");
+  });
+
+  it("Fails atomically when the buyer can't cover the cart", async () => {
+    const listing = await m.createListing(1_000_000, 5);
+    const buyer = await m.buyer(1_500_000);
+
+    await expectError(checkout(buyer, [{ listing, quantity: 2 }]).rpc(), "insufficient funds");
+    expect((await m.program.account.listing.fetch(listing)).quantity).to.equal(5);
+    expect(await balance(buyer.ata)).to.equal(1_500_000);
+  });
+});
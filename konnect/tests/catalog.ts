@@ -0,0 +1,169 @@
+import * as anchor from "@coral-xyz/anchor";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { expect } from "chai";
+import { Market, balance, computeUnitsOf, eventOf, exists, expectError, pda } from "./utils/market";
+
+const { Keypair, SystemProgram } = anchor.web3;
+type PublicKey = anchor.web3.PublicKey;
+type Keypair = anchor.web3.Keypair;
+
+const CAPACITY = 150;
+
+describe("catalog", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  let m: Market;
+  let catalog: PublicKey;
+
+  const addEntry = (price: number, quantity: number) =>
+    m.program.methods
+      .addCatalogEntry(new anchor.BN(price), quantity, Array(32).fill(1))
+      .accountsPartial({
+        marketplace: m.marketplace,
+        merchant: m.merchant,
+        catalog,
+        owner: m.seller.publicKey,
+        bond: null,
+      })
+      .signers([m.seller])
+      .rpc();
+
+  const buyEntry = (index: number, buyer: { kp: Keypair; ata: PublicKey }, quantity: number) => {
+    const reference = Keypair.generate().publicKey;
+    return m.program.methods
+      .buyCatalogEntry(index, quantity, reference, null, null)
+      .accountsPartial({
+        catalog,
+        marketplace: m.marketplace,
+        merchant: m.merchant,
+        buyer: buyer.kp.publicKey,
+        buyerAta: buyer.ata,
+        sellerAta: m.sellerAta,
+        treasury: m.treasury,
+        treasuryVault: m.treasuryVault,
+        mint: m.mint,
+        buyerProfile: m.profileOf(buyer.kp.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts([{ pubkey: reference, isSigner: false, isWritable: false }])
+      .signers([buyer.kp]);
+  };
+
+  const removeEntry = (index: number) =>
+    m.program.methods
+      .removeCatalogEntry(index)
+      .accountsPartial({ catalog, seller: m.seller.publicKey })
+      .signers([m.seller]);
+
+  before(async () => {
+    m = await Market.create();
+    await m.updateMarketplace({ pointsBps: 100 }).rpc();
+    catalog = pda(Buffer.from("catalog"), m.merchant.toBuffer(), m.mint.toBuffer());
+    await m.program.methods
+      .initCatalog(CAPACITY, "https://konnect.example/catalog")
+      .accountsPartial({
+        marketplace: m.marketplace,
+        merchant: m.merchant,
+        catalog,
+        mint: m.mint,
+        owner: m.seller.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([m.seller])
+      .rpc();
+    for (let i = 0; i < CAPACITY; i++) {
+      await addEntry(1_000_000, 10);
+    }
+  });
+
+  it("Sells an entry by index", async () => {
+    const buyer = await m.buyer(10_000_000);
+    const sig = await buyEntry(3, buyer, 2).rpc();
+
+    expect(await balance(buyer.ata)).to.equal(8_000_000);
+    const order = await eventOf(sig, "catalogOrderCompleted");
+    expect(order.index).to.equal(3);
+    expect(order.totalAmount.toNumber()).to.equal(2_000_000);
+    const profile = await m.program.account.buyerProfile.fetch(m.profileOf(buyer.kp.publicKey));
+    expect(profile.ordersCompleted).to.equal(1);
+    // points are earned at 1% as through buy_now
+    expect(profile.points.toNumber()).to.equal(20_000);
+    expect((await eventOf(sig, "pointsUpdated")).earned.toNumber()).to.equal(20_000);
+  });
+
+  it("Rejects full catalogs and unknown entries", async () => {
+    await expectError(addEntry(1_000_000, 1), "CatalogFull");
+    const buyer = await m.buyer(10_000_000);
+    await expectError(buyEntry(CAPACITY, buyer, 1).rpc(), "InvalidCatalogEntry");
+  });
+
+  // Compute units of a purchase against buy_now, and rent per sellable item against a Listing.
+  // The entry sits at the end of the catalog to show the lookup does not depend on the index.
+  it("Costs less than a listing to buy from and to store", async () => {
+    const listing = await m.createListing(1_000_000, 10);
+    const buyer = await m.buyer(10_000_000);
+    // create the buyer profile first so neither measured purchase pays for its init
+    await m.buyNow(listing, buyer, 1).rpc();
+
+    const listingCu = await computeUnitsOf(await m.buyNow(listing, buyer, 1).rpc());
+    const catalogCu = await computeUnitsOf(await buyEntry(CAPACITY - 1, buyer, 1).rpc());
+
+    const listingLen = (await m.connection.getAccountInfo(listing)).data.length;
+    const catalogLen = (await m.connection.getAccountInfo(catalog)).data.length;
+    const listingRent = await m.connection.getMinimumBalanceForRentExemption(listingLen);
+    const entryRent =
+      (await m.connection.getMinimumBalanceForRentExemption(catalogLen)) / CAPACITY;
+
+    console.log(`      buy_now: ${listingCu} CU, ${listingLen} bytes, ${listingRent} lamports per listing`);
+    console.log(
+      `      buy_catalog_entry: ${catalogCu} CU, ${catalogLen / CAPACITY} bytes, ${Math.ceil(entryRent)} lamports per entry`
+    );
+    expect(catalogCu).to.be.at.most(listingCu);
+    expect(entryRent * 10).to.be.below(listingRent);
+  });
+
+  it("Removes an entry for good", async () => {
+    const sig = await removeEntry(5).rpc();
+    expect((await eventOf(sig, "catalogEntryRemoved")).index).to.equal(5);
+
+    const buyer = await m.buyer(10_000_000);
+    await expectError(buyEntry(5, buyer, 1).rpc(), "InvalidCatalogEntry");
+    await expectError(
+      m.program.methods
+        .updateCatalogEntry(5, null, null, true, null)
+        .accountsPartial({ catalog, seller: m.seller.publicKey })
+        .signers([m.seller])
+        .rpc(),
+      "InvalidCatalogEntry"
+    );
+    await expectError(removeEntry(5).rpc(), "InvalidCatalogEntry");
+    // the other entries keep their indices
+    await buyEntry(6, buyer, 1).rpc();
+  });
+
+  it("Closes the catalog and returns its rent to the seller", async () => {
+    const stranger = await m.buyer(0);
+    await expectError(
+      m.program.methods
+        .closeCatalog()
+        .accountsPartial({ catalog, seller: stranger.kp.publicKey })
+        .signers([stranger.kp])
+        .rpc(),
+      "ConstraintHasOne"
+    );
+
+    const rent = await m.connection.getBalance(catalog);
+    const before = await m.connection.getBalance(m.seller.publicKey);
+    const sig = await m.program.methods
+      .closeCatalog()
+      .accountsPartial({ catalog, seller: m.seller.publicKey })
+      .signers([m.seller])
+      .rpc();
+
+    expect((await eventOf(sig, "catalogClosed")).entryCount).to.equal(CAPACITY);
+    expect(await exists(catalog)).to.be.false;
+    // the seller signs but the provider wallet pays the transaction fee
+    expect((await m.connection.getBalance(m.seller.publicKey)) - before).to.equal(rent);
+  });
+});
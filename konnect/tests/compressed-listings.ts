@@ -0,0 +1,286 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Konnect } from "../target/types/konnect";
+import {
+  MerkleTree,
+  SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+  SPL_NOOP_PROGRAM_ID,
+  allocTreeIx,
+  onChainRoot,
+} from "./utils/merkle";
+import {
+  TOKEN_PROGRAM_ID,
+  createMint,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { expect } from "chai";
+
+// Needs the compression and noop programs, loaded from tests/fixtures by Anchor.toml's [[test.genesis]]
+describe("compressed listings", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.Konnect as Program<Konnect>;
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const connection = provider.connection;
+  const authority = provider.wallet.payer;
+  const buyer = anchor.web3.Keypair.generate();
+  const eventParser = new anchor.EventParser(program.programId, program.coder);
+
+  const maxDepth = 14;
+  const maxBufferSize = 64;
+  const merkleTree = anchor.web3.Keypair.generate();
+
+  const marketplaceId = 1;
+  const marketplace = anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("marketplace"),
+      authority.publicKey.toBuffer(),
+      new anchor.BN(marketplaceId).toArrayLike(Buffer, "le", 2),
+    ],
+    program.programId
+  )[0];
+  const merchant = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("merchant"), marketplace.toBuffer(), authority.publicKey.toBuffer()],
+    program.programId
+  )[0];
+  const listingTree = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("listing_tree"), merkleTree.publicKey.toBuffer()],
+    program.programId
+  )[0];
+
+  let mint: anchor.web3.PublicKey;
+  let treasury: anchor.web3.PublicKey;
+  let treasuryVault: anchor.web3.PublicKey;
+  let sellerAta: anchor.web3.PublicKey;
+  let buyerAta: anchor.web3.PublicKey;
+
+  // leaves as emitted by the program, indexed by leaf index
+  const leaves: Buffer[] = [];
+  let listing: any;
+
+  const eventsOf = async (signature: string, name: string) => {
+    await connection.confirmTransaction(signature, "confirmed");
+    const tx = await connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    return [...eventParser.parseLogs(tx.meta.logMessages)].filter((e) => e.name === name);
+  };
+
+  const lastUpdate = async (signature: string) => {
+    const events = await eventsOf(signature, "compressedListingUpdated");
+    const { listing, leaf } = events[events.length - 1].data as any;
+    leaves[listing.index] = Buffer.from(leaf);
+    return listing;
+  };
+
+  // proof against the local copy of the tree, checked against the on-chain root
+  const proofFor = async (index: number) => {
+    const tree = new MerkleTree(leaves, maxDepth);
+    const root = await onChainRoot(connection, merkleTree.publicKey, maxDepth);
+    expect(root.equals(tree.root)).to.be.true;
+    return {
+      root: Array.from(tree.root),
+      proof: tree.proof(index).map((node) => ({
+        pubkey: new anchor.web3.PublicKey(node),
+        isSigner: false,
+        isWritable: false,
+      })),
+    };
+  };
+
+  before(async () => {
+    await connection.confirmTransaction(
+      await connection.requestAirdrop(buyer.publicKey, anchor.web3.LAMPORTS_PER_SOL),
+      "confirmed"
+    );
+
+    await program.methods
+      .initMarketplace(marketplaceId, 200, {
+        name: "Konnect compressed",
+        uri: "",
+        defaultMint: anchor.web3.PublicKey.default,
+      })
+      .accounts({
+        marketplace,
+        authority: authority.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+    // 1% back in points
+    await program.methods
+      .updateMarketplace(null, null, null, null, null, null, null, 100, null)
+      .accounts({ marketplace, authority: authority.publicKey })
+      .rpc();
+    await program.methods
+      .registerMerchant()
+      .accounts({
+        marketplace,
+        merchant,
+        owner: authority.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .rpc();
+
+    mint = await createMint(connection, authority, authority.publicKey, null, 6);
+    treasury = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury"), marketplace.toBuffer(), mint.toBuffer()],
+      program.programId
+    )[0];
+    treasuryVault = anchor.utils.token.associatedAddress({ mint, owner: treasury });
+    await program.methods
+      .initTreasury()
+      .accounts({
+        marketplace,
+        authority: authority.publicKey,
+        treasury,
+        treasuryVault,
+        mint,
+      })
+      .rpc();
+
+    sellerAta = (await getOrCreateAssociatedTokenAccount(connection, authority, mint, authority.publicKey))
+      .address;
+    buyerAta = (await getOrCreateAssociatedTokenAccount(connection, authority, mint, buyer.publicKey))
+      .address;
+    await mintTo(connection, authority, mint, buyerAta, authority, 10_000_000);
+  });
+
+  it("Initializes a listing tree", async () => {
+    const allocIx = await allocTreeIx(
+      connection,
+      merkleTree.publicKey,
+      authority.publicKey,
+      maxDepth,
+      maxBufferSize
+    );
+    await program.methods
+      .initListingTree(maxDepth, maxBufferSize)
+      .accounts({
+        marketplace,
+        merchant,
+        listingTree,
+        merkleTree: merkleTree.publicKey,
+        owner: authority.publicKey,
+        compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+        noopProgram: SPL_NOOP_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .preInstructions([allocIx])
+      .signers([merkleTree])
+      .rpc();
+
+    const tree = await program.account.listingTree.fetch(listingTree);
+    expect(tree.merkleTree.toString()).to.equal(merkleTree.publicKey.toString());
+    expect(tree.leafCount.toNumber()).to.equal(0);
+  });
+
+  it("Appends compressed listings", async () => {
+    for (const price of [1_000_000, 2_500_000]) {
+      const sig = await program.methods
+        .createCompressedListing(new anchor.BN(price), 5, Array(32).fill(1))
+        .accounts({
+          marketplace,
+          merchant,
+          listingTree,
+          merkleTree: merkleTree.publicKey,
+          mint,
+          owner: authority.publicKey,
+          bond: null,
+          compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+          noopProgram: SPL_NOOP_PROGRAM_ID,
+        })
+        .rpc();
+      listing = await lastUpdate(sig);
+    }
+
+    expect(listing.index).to.equal(1);
+    expect((await program.account.listingTree.fetch(listingTree)).leafCount.toNumber()).to.equal(2);
+    await proofFor(1);
+  });
+
+  it("Updates a compressed listing with a proof", async () => {
+    const { root, proof } = await proofFor(listing.index);
+    const sig = await program.methods
+      .updateCompressedListing(root, listing, new anchor.BN(2_000_000), null, null)
+      .accounts({
+        listingTree,
+        merkleTree: merkleTree.publicKey,
+        seller: authority.publicKey,
+        compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+        noopProgram: SPL_NOOP_PROGRAM_ID,
+      })
+      .remainingAccounts(proof)
+      .rpc();
+    listing = await lastUpdate(sig);
+    expect(listing.price.toNumber()).to.equal(2_000_000);
+  });
+
+  it("Buys from a compressed listing", async () => {
+    const reference = anchor.web3.Keypair.generate().publicKey;
+    const { root, proof } = await proofFor(listing.index);
+    const buyAccounts = {
+      listingTree,
+      merkleTree: merkleTree.publicKey,
+      marketplace,
+      merchant,
+      buyer: buyer.publicKey,
+      buyerAta,
+      sellerAta,
+      treasury,
+      treasuryVault,
+      mint,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      compressionProgram: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+      noopProgram: SPL_NOOP_PROGRAM_ID,
+    };
+    const sellerBefore = (await getAccount(connection, sellerAta)).amount;
+
+    const sig = await program.methods
+      .buyCompressedListing(root, listing, 2, reference, new anchor.BN(4_000_000))
+      .accounts(buyAccounts)
+      .remainingAccounts([{ pubkey: reference, isSigner: false, isWritable: false }, ...proof])
+      .signers([buyer])
+      .rpc();
+    const stale = listing;
+    listing = await lastUpdate(sig);
+
+    // the order names the listing by its asset id, which stays the same across leaf updates
+    const assetId = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("compressed_listing"),
+        listingTree.toBuffer(),
+        new anchor.BN(listing.index).toArrayLike(Buffer, "le", 4),
+      ],
+      program.programId
+    )[0];
+    const [order] = await eventsOf(sig, "orderCompleted");
+    expect((order.data as any).listing.toString()).to.equal(assetId.toString());
+    expect((order.data as any).quantity).to.equal(2);
+    expect((order.data as any).payer.toString()).to.equal(buyer.publicKey.toString());
+
+    // 2 x 2_000_000 at 2%
+    expect(listing.quantity).to.equal(3);
+    expect((await getAccount(connection, treasuryVault)).amount).to.equal(BigInt(80_000));
+    expect((await getAccount(connection, sellerAta)).amount - sellerBefore).to.equal(BigInt(3_920_000));
+    expect((await program.account.merchant.fetch(merchant)).salesCount.toNumber()).to.equal(1);
+    // and the buyer earns points on it as through buy_now
+    const [points] = await eventsOf(sig, "pointsUpdated");
+    expect((points.data as any).earned.toNumber()).to.equal(40_000);
+
+    // the old leaf no longer verifies, so stock cannot be spent twice
+    try {
+      await program.methods
+        .buyCompressedListing(root, stale, 2, reference, null)
+        .accounts(buyAccounts)
+        .remainingAccounts([{ pubkey: reference, isSigner: false, isWritable: false }, ...proof])
+        .signers([buyer])
+        .rpc();
+      expect.fail("stale leaf was accepted");
+    } catch (e) {
+      expect(e.toString()).to.not.contain("stale leaf was accepted");
+    }
+  });
+});
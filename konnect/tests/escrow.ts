@@ -0,0 +1,197 @@
+import * as anchor from "@coral-xyz/anchor";
+import { approve } from "@solana/spl-token";
+import { expect } from "chai";
+import { FEE_BPS, Market, balance, eventOf, exists, expectError, wallet } from "./utils/market";
+
+describe("service escrow and listing close", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const price = 5_000_000;
+  const fee = (price * FEE_BPS) / 10_000;
+  let m: Market;
+
+  before(async () => {
+    m = await Market.create();
+  });
+
+  const order = async () => {
+    const listing = await m.createListing(price, 0, true);
+    const buyer = await m.buyer(price);
+    const sig = await m.createServiceOrder(listing, buyer).rpc();
+    const [escrow, vault] = m.escrowOf(listing, buyer.kp.publicKey);
+    return { listing, buyer, escrow, vault, sig };
+  };
+
+  it("Locks the payment in an escrow", async () => {
+    const { listing, buyer, escrow, vault, sig } = await order();
+
+    expect(await balance(buyer.ata)).to.equal(0);
+    expect(await balance(vault)).to.equal(price);
+    const e = await m.program.account.escrow.fetch(escrow);
+    expect(e.amount.toNumber()).to.equal(price);
+    expect(e.released).to.be.false;
+    expect((await m.program.account.listing.fetch(listing)).openEscrows).to.equal(1);
+    expect((await eventOf(sig, "serviceOrderCreated")).escrow.toString()).to.equal(escrow.toString());
+  });
+
+  it("Rejects a missing or wrong reference and goods listings", async () => {
+    const listing = await m.createListing(price, 0, true);
+    const buyer = await m.buyer(price);
+
+    await expectError(
+      m.createServiceOrder(listing, buyer, { remaining: [] }).rpc(),
+      "MissingReference"
+    );
+    // the reference key must be the one in the instruction data
+    await expectError(
+      m
+        .createServiceOrder(listing, buyer, {
+          remaining: [
+            { pubkey: anchor.web3.Keypair.generate().publicKey, isSigner: false, isWritable: false },
+          ],
+        })
+        .rpc(),
+      "WrongReference"
+    );
+
+    const goods = await m.createListing(price, 1);
+    await expectError(m.createServiceOrder(goods, buyer).rpc(), "WrongFlowForGoods");
+  });
+
+  it("Releases to the seller and the treasury and leaves a receipt", async () => {
+    const { listing, buyer, escrow, vault } = await order();
+    const { reference } = await m.program.account.escrow.fetch(escrow);
+    const sellerBefore = await balance(m.sellerAta);
+    const treasuryBefore = await balance(m.treasuryVault);
+
+    await (await m.releaseServiceOrder(escrow, buyer.kp)).rpc();
+
+    expect((await balance(m.sellerAta)) - sellerBefore).to.equal(price - fee);
+    expect((await balance(m.treasuryVault)) - treasuryBefore).to.equal(fee);
+    expect(await exists(vault)).to.be.false;
+    expect(await exists(escrow)).to.be.false;
+    expect((await m.program.account.listing.fetch(listing)).openEscrows).to.equal(0);
+
+    const receipt = await m.program.account.receipt.fetch(m.receiptOf(reference, escrow));
+    expect(receipt.buyer.toString()).to.equal(buyer.kp.publicKey.toString());
+    expect(receipt.amount.toNumber()).to.equal(price);
+    expect(receipt.fee.toNumber()).to.equal(fee);
+    expect(receipt.reference.toString()).to.equal(reference.toString());
+    expect(receipt.status).to.deep.equal({ completed: {} });
+  });
+
+  it("Lets the buyer order the same service again once released", async () => {
+    const { listing, buyer, escrow } = await order();
+    await (await m.releaseServiceOrder(escrow, buyer.kp)).rpc();
+
+    await m.fund(buyer.ata, price);
+    await m.createServiceOrder(listing, buyer).rpc();
+    await (await m.releaseServiceOrder(escrow, m.authority)).rpc();
+    expect((await m.program.account.listing.fetch(listing)).openEscrows).to.equal(0);
+  });
+
+  it("Only lets the buyer or the marketplace authority settle", async () => {
+    const { escrow } = await order();
+    const stranger = await wallet();
+
+    await expectError((await m.releaseServiceOrder(escrow, stranger)).rpc(), "ConstraintRaw");
+    await expectError((await m.cancelServiceOrder(escrow, stranger)).rpc(), "ConstraintRaw");
+    await (await m.releaseServiceOrder(escrow, m.authority)).rpc();
+  });
+
+  it("Returns receipt rent to whoever released the order", async () => {
+    const { buyer, escrow } = await order();
+    const { reference } = await m.program.account.escrow.fetch(escrow);
+    await (await m.releaseServiceOrder(escrow, m.authority)).rpc();
+
+    const receipt = m.receiptOf(reference, escrow);
+    const r = await m.program.account.receipt.fetch(receipt);
+    expect(r.rentPayer.toString()).to.equal(m.authority.publicKey.toString());
+
+    const closeReceipt = (rentPayer: anchor.web3.PublicKey) =>
+      m.program.methods
+        .closeReceipt()
+        .accountsPartial({ receipt, buyer: buyer.kp.publicKey, rentPayer })
+        .signers([buyer.kp]);
+    await expectError(closeReceipt(buyer.kp.publicKey).rpc(), "ConstraintHasOne");
+
+    const rent = await m.connection.getBalance(receipt);
+    const before = await m.connection.getBalance(buyer.kp.publicKey);
+    await closeReceipt(m.authority.publicKey).rpc();
+    expect(await exists(receipt)).to.be.false;
+    // the buyer only signs, the authority pays the fee and gets the rent back
+    expect(await m.connection.getBalance(buyer.kp.publicKey)).to.equal(before);
+    expect(rent).to.be.greaterThan(0);
+  });
+
+  it("Cancels back to the buyer and returns all rent", async () => {
+    const { listing, buyer, escrow, vault } = await order();
+    const rent =
+      (await m.connection.getBalance(escrow)) + (await m.connection.getBalance(vault));
+    const lamportsBefore = await m.connection.getBalance(buyer.kp.publicKey);
+
+    const sig = await (await m.cancelServiceOrder(escrow, m.authority)).rpc();
+
+    expect(await balance(buyer.ata)).to.equal(price);
+    expect(await exists(escrow)).to.be.false;
+    expect(await exists(vault)).to.be.false;
+    expect(await m.connection.getBalance(buyer.kp.publicKey)).to.equal(lamportsBefore + rent);
+    expect((await m.program.account.listing.fetch(listing)).openEscrows).to.equal(0);
+    expect((await eventOf(sig, "serviceOrderCancelled")).amount.toNumber()).to.equal(price);
+  });
+
+  it("Cancels a delegated order back to the paying wallet", async () => {
+    const listing = await m.createListing(price, 0, true);
+    const finance = await m.buyer(price);
+    const employee = await m.buyer(0);
+    await approve(m.connection, m.authority, finance.ata, employee.kp.publicKey, finance.kp, price);
+
+    const sig = await m.createServiceOrder(listing, { kp: employee.kp, ata: finance.ata }).rpc();
+    const created = await eventOf(sig, "serviceOrderCreated");
+    expect(created.buyer.toString()).to.equal(employee.kp.publicKey.toString());
+    expect(created.payer.toString()).to.equal(finance.kp.publicKey.toString());
+    const [escrow] = m.escrowOf(listing, employee.kp.publicKey);
+    expect((await m.program.account.escrow.fetch(escrow)).fundingAccount.toString()).to.equal(
+      finance.ata.toString()
+    );
+
+    await expectError(
+      (await m.cancelServiceOrder(escrow, employee.kp, { refundAta: employee.ata })).rpc(),
+      "InvalidAccount"
+    );
+    await (await m.cancelServiceOrder(escrow, employee.kp)).rpc();
+    expect(await balance(finance.ata)).to.equal(price);
+    expect(await balance(employee.ata)).to.equal(0);
+  });
+
+  it("Closes a listing only once it is inactive and settled", async () => {
+    const { listing, buyer, escrow } = await order();
+
+    await expectError(m.closeListing(listing).rpc(), "ListingStillActive");
+    await m.updateListing(listing, { active: false }).rpc();
+    await expectError(m.closeListing(listing).rpc(), "OpenEscrowsRemaining");
+
+    await (await m.cancelServiceOrder(escrow, buyer.kp)).rpc();
+    const rent = await m.connection.getBalance(listing);
+    const sellerBefore = await m.connection.getBalance(m.seller.publicKey);
+    await m.closeListing(listing).rpc();
+
+    expect(await exists(listing)).to.be.false;
+    // the provider wallet pays transaction fees, so the seller gains exactly the rent
+    expect(await m.connection.getBalance(m.seller.publicKey)).to.equal(sellerBefore + rent);
+  });
+
+  it("Only the seller can close a listing", async () => {
+    const listing = await m.createListing(price, 1);
+    await m.updateListing(listing, { active: false }).rpc();
+    const stranger = await wallet();
+    await expectError(
+      m.program.methods
+        .closeListing()
+        .accountsPartial({ listing, seller: stranger.publicKey })
+        .signers([stranger])
+        .rpc(),
+      "ConstraintHasOne"
+    );
+  });
+});
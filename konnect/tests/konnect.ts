@@ -9,15 +9,22 @@ describe("konnect", () => {
   const program = anchor.workspace.Konnect as Program<Konnect>;
   const provider = anchor.getProvider();
 
+  const marketplaceId = 0;
+  const marketplaceIdSeed = new anchor.BN(marketplaceId).toArrayLike(Buffer, "le", 2);
+
   it("Initialize marketplace", async () => {
     const authority = provider.wallet.payer;
     const feeBps = 200;
     
     const tx = await program.methods
-      .initMarketplace(feeBps)
+      .initMarketplace(marketplaceId, feeBps, {
+        name: "Konnect",
+        uri: "",
+        defaultMint: anchor.web3.PublicKey.default,
+      })
       .accounts({
         marketplace: anchor.web3.PublicKey.findProgramAddressSync(
-          [Buffer.from("marketplace"), authority.publicKey.toBuffer()],
+          [Buffer.from("marketplace"), authority.publicKey.toBuffer(), marketplaceIdSeed],
           program.programId
         )[0],
         authority: authority.publicKey,
@@ -29,13 +36,15 @@ describe("konnect", () => {
     
     const marketplaceAccount = await program.account.marketplace.fetch(
       anchor.web3.PublicKey.findProgramAddressSync(
-        [Buffer.from("marketplace"), authority.publicKey.toBuffer()],
+        [Buffer.from("marketplace"), authority.publicKey.toBuffer(), marketplaceIdSeed],
         program.programId
       )[0]
     );
     
     expect(marketplaceAccount.authority.toString()).to.equal(authority.publicKey.toString());
     expect(marketplaceAccount.feeBps).to.equal(feeBps);
+    expect(marketplaceAccount.id).to.equal(marketplaceId);
+    expect(marketplaceAccount.metadata.name).to.equal("Konnect");
   });
 
   it("Register merchant", async () => {
@@ -43,7 +52,7 @@ describe("konnect", () => {
     const merchantOwner = provider.wallet.payer;
     
     const marketplacePda = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("marketplace"), authority.publicKey.toBuffer()],
+      [Buffer.from("marketplace"), authority.publicKey.toBuffer(), marketplaceIdSeed],
       program.programId
     )[0];
     
@@ -74,7 +83,7 @@ describe("konnect", () => {
     const authority = provider.wallet.payer;
     
     const marketplacePda = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("marketplace"), authority.publicKey.toBuffer()],
+      [Buffer.from("marketplace"), authority.publicKey.toBuffer(), marketplaceIdSeed],
       program.programId
     )[0];
 
@@ -94,7 +103,7 @@ describe("konnect", () => {
     const merchantOwner = provider.wallet.payer;
     
     const marketplacePda = anchor.web3.PublicKey.findProgramAddressSync(
-      [Buffer.from("marketplace"), authority.publicKey.toBuffer()],
+      [Buffer.from("marketplace"), authority.publicKey.toBuffer(), marketplaceIdSeed],
       program.programId
     )[0];
     
@@ -0,0 +1,140 @@
+import * as anchor from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { Market, eventOf, eventsOf, pda, u16 } from "./utils/market";
+
+type PublicKey = anchor.web3.PublicKey;
+
+// an indexer should be able to rebuild every listing from these events alone
+describe("listing lifecycle events", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  let m: Market;
+
+  before(async () => {
+    m = await Market.create();
+  });
+
+  const variantOf = (listing: PublicKey, id: number) =>
+    pda(Buffer.from("variant"), listing.toBuffer(), u16(id));
+
+  it("Reports before and after values of a listing update", async () => {
+    const listing = await m.createListing(1_000_000, 5);
+    const saleWindow = { saleStart: new anchor.BN(1), saleEnd: null };
+    const limits = { maxPerWallet: 2, allowlistRoot: null };
+
+    const sig = await m
+      .updateListing(listing, { price: 2_000_000, quantity: 7, active: false, saleWindow, limits })
+      .rpc();
+
+    const e = await eventOf(sig, "listingUpdated");
+    expect(e.listing.toString()).to.equal(listing.toString());
+    expect(e.revision).to.equal((await m.program.account.listing.fetch(listing)).revision);
+    expect([e.oldPrice.toNumber(), e.newPrice.toNumber()]).to.deep.equal([1_000_000, 2_000_000]);
+    expect([e.oldQuantity, e.newQuantity]).to.deep.equal([5, 7]);
+    expect([e.oldActive, e.newActive]).to.deep.equal([true, false]);
+    expect(e.oldSaleWindow.saleStart).to.be.null;
+    expect(e.newSaleWindow.saleStart.toNumber()).to.equal(1);
+    expect(e.oldLimits.maxPerWallet).to.be.null;
+    expect(e.newLimits.maxPerWallet).to.equal(2);
+    // unchanged fields still carry their current value
+    expect(e.oldName).to.equal(e.newName);
+    expect(e.oldMetadata).to.be.null;
+  });
+
+  it("Reports stock moved by purchases and reservations", async () => {
+    const listing = await m.createListing(1_000_000, 3);
+    const buyer = await m.buyer(10_000_000);
+    const reservation = pda(
+      Buffer.from("reservation"),
+      listing.toBuffer(),
+      buyer.kp.publicKey.toBuffer()
+    );
+
+    const reserved = await eventOf(
+      await m.program.methods
+        .reserveInventory(2, new anchor.BN(600), null)
+        .accountsPartial({
+          listing,
+          buyer: buyer.kp.publicKey,
+          payer: buyer.kp.publicKey,
+          reservation,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([buyer.kp])
+        .rpc(),
+      "listingStockChanged"
+    );
+    expect([reserved.oldQuantity, reserved.newQuantity, reserved.reserved]).to.deep.equal([3, 1, 2]);
+
+    const bought = await eventOf(await m.buyNow(listing, buyer, 1).rpc(), "listingStockChanged");
+    expect([bought.oldQuantity, bought.newQuantity]).to.deep.equal([1, 0]);
+    // the held units keep the listing live
+    expect([bought.oldActive, bought.newActive]).to.deep.equal([true, true]);
+    expect(bought.variant).to.be.null;
+
+    const released = await eventOf(
+      await m.program.methods
+        .releaseReservation()
+        .accountsPartial({ reservation, listing, rentPayer: buyer.kp.publicKey, caller: buyer.kp.publicKey })
+        .signers([buyer.kp])
+        .rpc(),
+      "listingStockChanged"
+    );
+    expect([released.oldQuantity, released.newQuantity, released.reserved]).to.deep.equal([0, 2, 0]);
+  });
+
+  it("Reports variant changes and listing close", async () => {
+    const listing = await m.createListing(1_000_000, 0);
+    const variant = variantOf(listing, 7);
+
+    const added = await eventOf(
+      await m.program.methods
+        .addVariant(7, new anchor.BN(3_000_000), 4)
+        .accountsPartial({
+          listing,
+          seller: m.seller.publicKey,
+          variant,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([m.seller])
+        .rpc(),
+      "variantAdded"
+    );
+    expect(added.variant.toString()).to.equal(variant.toString());
+    expect([added.id, added.price.toNumber(), added.quantity]).to.deep.equal([7, 3_000_000, 4]);
+
+    const updated = await eventOf(
+      await m.program.methods
+        .updateVariant(new anchor.BN(2_500_000), null, false)
+        .accountsPartial({ variant, listing, seller: m.seller.publicKey })
+        .signers([m.seller])
+        .rpc(),
+      "variantUpdated"
+    );
+    expect([updated.oldPrice.toNumber(), updated.newPrice.toNumber()]).to.deep.equal([
+      3_000_000, 2_500_000,
+    ]);
+    expect([updated.oldQuantity, updated.newQuantity]).to.deep.equal([4, 4]);
+    expect([updated.oldActive, updated.newActive]).to.deep.equal([true, false]);
+
+    const removed = await eventOf(
+      await m.program.methods
+        .removeVariant()
+        .accountsPartial({ variant, listing, seller: m.seller.publicKey })
+        .signers([m.seller])
+        .rpc(),
+      "variantRemoved"
+    );
+    expect([removed.id, removed.oldQuantity]).to.deep.equal([7, 4]);
+    expect(removed.listingRevision).to.equal(
+      (await m.program.account.listing.fetch(listing)).revision
+    );
+
+    await m.updateListing(listing, { active: false }).rpc();
+    const sig = await m.closeListing(listing).rpc();
+    const closed = await eventOf(sig, "listingClosed");
+    expect(closed.listing.toString()).to.equal(listing.toString());
+    expect(closed.seller.toString()).to.equal(m.seller.publicKey.toString());
+    expect((await eventsOf(sig)).map((e) => e.name)).to.deep.equal(["listingClosed"]);
+  });
+});
@@ -0,0 +1,149 @@
+import * as anchor from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { Market, defaultListingArgs, eventOf, expectError } from "./utils/market";
+
+type PublicKey = anchor.web3.PublicKey;
+
+describe("listing metadata", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  let m: Market;
+
+  before(async () => {
+    m = await Market.create();
+  });
+
+  const metadata = (tags: string[], images: string[] = []) => ({
+    category: 4,
+    tags,
+    uri: "https://example.com/listing.json",
+    contentHash: Array(32).fill(9),
+    images,
+  });
+
+  const updateMetadata = (
+    listing: PublicKey,
+    changes: { name?: string; imageUrl?: string; metadata?: ReturnType<typeof metadata> }
+  ) =>
+    m.program.methods
+      .updateListingMetadata(changes.name ?? null, changes.imageUrl ?? null, changes.metadata ?? null)
+      .accountsPartial({
+        listing,
+        seller: m.seller.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([m.seller]);
+
+  const dataLen = async (account: PublicKey) => (await m.connection.getAccountInfo(account)).data.length;
+
+  it("Stores extended metadata sized to its contents", async () => {
+    const small = await m.createListing(1_000_000, 1);
+    const rich = await m.createListing(1_000_000, 1, false, { metadata: metadata(["shoes", "running"]) });
+
+    const l = await m.program.account.listing.fetch(rich);
+    expect(l.metadata.category).to.equal(4);
+    expect(l.metadata.tags).to.deep.equal(["shoes", "running"]);
+    expect(l.metadata.uri).to.equal("https://example.com/listing.json");
+    // 4 + 5 and 4 + 7 bytes of tags, 4 + 32 of uri
+    expect((await dataLen(rich)) - (await dataLen(small))).to.equal(9 + 11 + 36);
+  });
+
+  it("Grows and shrinks the account when metadata changes", async () => {
+    const listing = await m.createListing(1_000_000, 1);
+    const before = await dataLen(listing);
+    const sellerBefore = await m.connection.getBalance(m.seller.publicKey);
+
+    await updateMetadata(listing, {
+      metadata: metadata(["a", "b", "c"], ["ipfs://one", "ar://two"]),
+    }).rpc();
+    const grown = await dataLen(listing);
+    expect(grown).to.be.greaterThan(before);
+    expect(await m.connection.getBalance(m.seller.publicKey)).to.be.lessThan(sellerBefore);
+    expect((await m.program.account.listing.fetch(listing)).metadata.images).to.deep.equal([
+      "ipfs://one",
+      "ar://two",
+    ]);
+
+    // unrelated edits keep the current metadata and size
+    await updateMetadata(listing, { name: "Renamed" }).rpc();
+    expect(await dataLen(listing)).to.equal(grown);
+
+    await updateMetadata(listing, { metadata: defaultListingArgs().metadata }).rpc();
+    expect(await dataLen(listing)).to.equal(before);
+    // shrinking refunds the excess rent to the seller
+    expect(await m.connection.getBalance(m.seller.publicKey)).to.equal(sellerBefore);
+  });
+
+  it("Edits the name and image and reports the change", async () => {
+    const listing = await m.createListing(1_000_000, 1);
+    const { revision } = await m.program.account.listing.fetch(listing);
+
+    const sig = await updateMetadata(listing, {
+      name: "Trail shoes",
+      imageUrl: "ipfs://bafyimage",
+    }).rpc();
+
+    const l = await m.program.account.listing.fetch(listing);
+    expect(l.name).to.equal("Trail shoes");
+    expect(l.imageUrl).to.equal("ipfs://bafyimage");
+    expect(l.revision).to.equal(revision + 1);
+    const updated = await eventOf(sig, "listingUpdated");
+    expect(updated.oldName).to.equal(defaultListingArgs().name);
+    expect(updated.newName).to.equal("Trail shoes");
+    expect(updated.oldImageUrl).to.equal("");
+    expect(updated.newImageUrl).to.equal("ipfs://bafyimage");
+    expect(updated.newMetadata).to.be.null;
+
+    // an empty image url removes the image
+    await updateMetadata(listing, { imageUrl: "" }).rpc();
+    expect((await m.program.account.listing.fetch(listing)).imageUrl).to.equal("");
+  });
+
+  it("Rejects invalid names and image urls", async () => {
+    const listing = await m.createListing(1_000_000, 1);
+    await expectError(updateMetadata(listing, { name: " " }).rpc(), "InvalidName");
+    await expectError(updateMetadata(listing, { name: "n".repeat(101) }).rpc(), "NameTooLong");
+    await expectError(
+      updateMetadata(listing, { imageUrl: "http://example.com/a.png" }).rpc(),
+      "UnsupportedUrl"
+    );
+    await expectError(
+      m.createListing(1_000_000, 1, false, { imageUrl: `https://${"i".repeat(200)}` }),
+      "ImageUrlTooLong"
+    );
+    await expectError(m.createListing(1_000_000, 1, false, { name: "" }), "InvalidName");
+  });
+
+  it("Rejects metadata over the limits", async () => {
+    const listing = await m.createListing(1_000_000, 1);
+    await expectError(updateMetadata(listing, { metadata: metadata(Array(9).fill("t")) }).rpc(), "TooManyTags");
+    await expectError(updateMetadata(listing, { metadata: metadata([""]) }).rpc(), "InvalidTag");
+    await expectError(
+      updateMetadata(listing, { metadata: metadata([], Array(6).fill("ipfs://x")) }).rpc(),
+      "TooManyImages"
+    );
+    await expectError(
+      m.createListing(1_000_000, 1, false, {
+        metadata: { ...metadata([]), uri: "http://example.com/listing.json" },
+      }),
+      "UnsupportedUrl"
+    );
+  });
+
+  it("Only the seller can edit metadata", async () => {
+    const listing = await m.createListing(1_000_000, 1);
+    const stranger = anchor.web3.Keypair.generate();
+    await expectError(
+      m.program.methods
+        .updateListingMetadata("Mine now", null, null)
+        .accountsPartial({
+          listing,
+          seller: stranger.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([stranger])
+        .rpc(),
+      "ConstraintHasOne"
+    );
+  });
+});
@@ -0,0 +1,100 @@
+import * as anchor from "@coral-xyz/anchor";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { expect } from "chai";
+import { Market, balance, eventOf, expectError } from "./utils/market";
+
+type PublicKey = anchor.web3.PublicKey;
+type Buyer = { kp: anchor.web3.Keypair; ata: PublicKey };
+
+describe("loyalty points", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  let m: Market;
+  let listing: PublicKey;
+  let buyer: Buyer;
+
+  before(async () => {
+    m = await Market.create();
+    // 1% back in points
+    await m.updateMarketplace({ pointsBps: 100 }).rpc();
+    listing = await m.createListing(10_000_000, 10);
+    buyer = await m.buyer(100_000_000);
+  });
+
+  const profile = () => m.program.account.buyerProfile.fetch(m.profileOf(buyer.kp.publicKey));
+
+  const redeem = (points: number) =>
+    m.program.methods
+      .redeemPoints(new anchor.BN(points))
+      .accountsPartial({ buyerProfile: m.profileOf(buyer.kp.publicKey), buyer: buyer.kp.publicKey })
+      .signers([buyer.kp]);
+
+  const checkout = () => {
+    const reference = anchor.web3.Keypair.generate().publicKey;
+    return m.program.methods
+      .checkoutCart([{ listing, quantity: 1 }], reference)
+      .accountsPartial({
+        marketplace: m.marketplace,
+        buyer: buyer.kp.publicKey,
+        buyerAta: buyer.ata,
+        treasury: m.treasury,
+        treasuryVault: m.treasuryVault,
+        mint: m.mint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts([
+        { pubkey: reference, isSigner: false, isWritable: false },
+        { pubkey: listing, isSigner: false, isWritable: true },
+        { pubkey: m.sellerAta, isSigner: false, isWritable: true },
+        { pubkey: m.merchant, isSigner: false, isWritable: true },
+      ])
+      .signers([buyer.kp]);
+  };
+
+  it("Earns points on purchases", async () => {
+    const sig = await m.buyNow(listing, buyer, 1).rpc();
+
+    const points = await eventOf(sig, "pointsUpdated");
+    expect(points.earned.toNumber()).to.equal(100_000);
+    expect(points.redeemed.toNumber()).to.equal(0);
+    const p = await profile();
+    expect([p.points.toNumber(), p.lifetimePoints.toNumber()]).to.deep.equal([100_000, 100_000]);
+  });
+
+  it("Takes staged points off the marketplace fee of the next purchase", async () => {
+    await expectError(redeem(100_001).rpc(), "InsufficientPoints");
+    await redeem(50_000).rpc();
+    let p = await profile();
+    expect([p.points.toNumber(), p.pendingDiscount.toNumber()]).to.deep.equal([50_000, 50_000]);
+
+    const [buyerBefore, sellerBefore, treasuryBefore] = [
+      await balance(buyer.ata),
+      await balance(m.sellerAta),
+      await balance(m.treasuryVault),
+    ];
+    const sig = await m.buyNow(listing, buyer, 1).rpc();
+
+    // the seller is paid in full, the discount comes out of the 2% fee
+    expect(buyerBefore - (await balance(buyer.ata))).to.equal(9_950_000);
+    expect((await balance(m.sellerAta)) - sellerBefore).to.equal(9_800_000);
+    expect((await balance(m.treasuryVault)) - treasuryBefore).to.equal(150_000);
+
+    const points = await eventOf(sig, "pointsUpdated");
+    expect(points.redeemed.toNumber()).to.equal(50_000);
+    // earned on what the buyer actually paid
+    expect(points.earned.toNumber()).to.equal(99_500);
+    p = await profile();
+    expect([p.points.toNumber(), p.pendingDiscount.toNumber()]).to.deep.equal([149_500, 0]);
+  });
+
+  it("Leaves staged points to buy_now", async () => {
+    await redeem(10_000).rpc();
+    await expectError(checkout().rpc(), "RedemptionUnsupported");
+
+    // unstaging returns them, after which the cart goes through
+    await redeem(0).rpc();
+    expect((await profile()).points.toNumber()).to.equal(149_500);
+    await checkout().rpc();
+    expect((await profile()).points.toNumber()).to.equal(249_500);
+  });
+});
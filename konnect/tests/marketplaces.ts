@@ -0,0 +1,68 @@
+import * as anchor from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { Market, eventOf, pda } from "./utils/market";
+
+describe("marketplaces", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  let a: Market;
+  let b: Market;
+
+  before(async () => {
+    [a, b] = [await Market.create(), await Market.create()];
+  });
+
+  it("Runs several marketplaces from one authority", async () => {
+    expect(a.marketplace.toString()).to.not.equal(b.marketplace.toString());
+    const mp = await a.program.account.marketplace.fetch(b.marketplace);
+    expect(mp.id).to.equal(b.id);
+    expect(mp.authority.toString()).to.equal(b.authority.publicKey.toString());
+  });
+
+  it("Cross-registers a merchant with verification but without its ratings", async () => {
+    // a rated, verified merchant on the first marketplace
+    const listing = await a.createListing(1_000_000, 5);
+    const buyer = await a.buyer(1_000_000);
+    const reference = anchor.web3.Keypair.generate().publicKey;
+    const receipt = a.receiptOf(reference);
+    await a.buyNow(listing, buyer, 1, { reference, accounts: { receipt } }).rpc();
+    await a.program.methods
+      .submitReview(5, Array(32).fill(1))
+      .accountsPartial({
+        receipt,
+        listing,
+        merchant: a.merchant,
+        review: pda(Buffer.from("review"), receipt.toBuffer()),
+        buyer: buyer.kp.publicKey,
+        payer: buyer.kp.publicKey,
+      })
+      .signers([buyer.kp])
+      .rpc();
+    await a.program.methods
+      .setMerchantStatus(true)
+      .accountsPartial({ merchant: a.merchant, marketplace: a.marketplace, authority: a.authority.publicKey })
+      .rpc();
+    expect((await a.program.account.merchant.fetch(a.merchant)).ratingCount).to.equal(1);
+
+    const merchant = b.merchantOf(a.seller.publicKey);
+    const countBefore = (await b.program.account.marketplace.fetch(b.marketplace)).merchantCount;
+    const sig = await b.program.methods
+      .crossRegisterMerchant()
+      .accountsPartial({
+        sourceMerchant: a.merchant,
+        sourceMarketplace: a.marketplace,
+        marketplace: b.marketplace,
+        merchant,
+        owner: a.seller.publicKey,
+      })
+      .signers([a.seller])
+      .rpc();
+
+    const m = await b.program.account.merchant.fetch(merchant);
+    expect(m.verified).to.be.true;
+    expect([m.ratingSum.toNumber(), m.ratingCount]).to.deep.equal([0, 0]);
+    expect([m.salesCount.toNumber(), m.salesVolume.toNumber()]).to.deep.equal([0, 0]);
+    expect((await b.program.account.marketplace.fetch(b.marketplace)).merchantCount).to.equal(countBefore + 1);
+    expect((await eventOf(sig, "merchantRegistered")).source.toString()).to.equal(a.merchant.toString());
+  });
+});
@@ -0,0 +1,88 @@
+import * as anchor from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { readFileSync } from "fs";
+import { join } from "path";
+import { expectError, program } from "./utils/market";
+
+const { PublicKey, SystemProgram } = anchor.web3;
+type PublicKey = anchor.web3.PublicKey;
+
+// Accounts in older layouts, preloaded from tests/fixtures by Anchor.toml's [[test.validator.account]].
+// They hold the same data as the program's native migration fixtures.
+const fixture = (name: string) =>
+  new PublicKey(JSON.parse(readFileSync(join(__dirname, "fixtures", `${name}.json`), "utf8")).pubkey);
+
+// the fixtures fill keys with a single repeated byte
+const key = (n: number) => new PublicKey(Buffer.alloc(32, n));
+
+describe("account migrations", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const p = program();
+  const payer = (anchor.getProvider() as anchor.AnchorProvider).wallet.publicKey;
+
+  type Migration = "migrateMarketplace" | "migrateMerchant" | "migrateListing" | "migrateEscrow";
+
+  const migrate = (method: Migration, account: PublicKey) =>
+    p.methods[method]().accountsPartial({ account, payer, systemProgram: SystemProgram.programId });
+
+  it("Upgrades a version 0 marketplace and refuses to do it twice", async () => {
+    const account = fixture("marketplace_v0");
+    await migrate("migrateMarketplace", account).rpc();
+
+    const mp = await p.account.marketplace.fetch(account);
+    expect(mp.version).to.equal(2);
+    expect(mp.authority.toString()).to.equal(key(1).toString());
+    expect(mp.feeBps).to.equal(250);
+    // fields added since start out as init_marketplace would set them
+    expect(mp.arbiter.toString()).to.equal(key(1).toString());
+    expect(mp.orderCount.toNumber()).to.equal(0);
+
+    await expectError(migrate("migrateMarketplace", account).rpc(), "AlreadyMigrated");
+  });
+
+  it("Grows a version 1 marketplace around the fields in its padding", async () => {
+    const account = fixture("marketplace_v1");
+    await migrate("migrateMarketplace", account).rpc();
+
+    const mp = await p.account.marketplace.fetch(account);
+    expect(mp.version).to.equal(2);
+    expect(mp.id).to.equal(3);
+    expect(mp.orderCount.toNumber()).to.equal(17);
+    expect(mp.oracleProgram.toString()).to.equal(key(7).toString());
+  });
+
+  it("Upgrades version 0 merchants and listings", async () => {
+    const merchant = fixture("merchant_v0");
+    // a merchant isn't a listing
+    await expectError(migrate("migrateListing", merchant).rpc(), "UnknownAccountLayout");
+    await migrate("migrateMerchant", merchant).rpc();
+    const m = await p.account.merchant.fetch(merchant);
+    expect([m.version, m.nextNonce.toNumber(), m.verified]).to.deep.equal([1, 42, true]);
+    expect(m.salesCount.toNumber()).to.equal(0);
+
+    const listing = fixture("listing_v0");
+    await migrate("migrateListing", listing).rpc();
+    const l = await p.account.listing.fetch(listing);
+    expect(l.version).to.equal(1);
+    expect(l.name).to.equal("Ceramic mug");
+    expect(l.price.toNumber()).to.equal(2_500_000);
+    expect([l.quantity, l.reserved, l.variantCount]).to.deep.equal([8, 0, 0]);
+  });
+
+  it("Upgrades version 0 and 1 escrows with their rent payer", async () => {
+    const v0 = fixture("escrow_v0");
+    await migrate("migrateEscrow", v0).rpc();
+    const e0 = await p.account.escrow.fetch(v0);
+    expect(e0.version).to.equal(2);
+    // version 0 escrows were always funded by the buyer
+    expect(e0.rentPayer.toString()).to.equal(key(12).toString());
+
+    const v1 = fixture("escrow_v1");
+    await migrate("migrateEscrow", v1).rpc();
+    const e1 = await p.account.escrow.fetch(v1);
+    expect(e1.version).to.equal(2);
+    expect(e1.rentPayer.toString()).to.equal(key(16).toString());
+    expect(e1.fundingAccount.toString()).to.equal(key(14).toString());
+  });
+});
@@ -0,0 +1,80 @@
+import * as anchor from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { Market, eventOf, expectError } from "./utils/market";
+
+const { Keypair, PublicKey } = anchor.web3;
+
+// Settling against a live feed needs an oracle program on the validator; parsing, staleness,
+// confidence and rounding are covered by programs/konnect/tests/price_feed.rs.
+describe("oracle-priced listings", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const guard = { expectedRate: new anchor.BN(15_000_000_000), maxSlippageBps: 100 };
+  let m: Market;
+
+  before(async () => {
+    m = await Market.create();
+  });
+
+  const setOracleProgram = (oracleProgram: anchor.web3.PublicKey) =>
+    m.program.methods
+      .updateMarketplace(null, null, null, null, null, null, null, null, oracleProgram)
+      .accountsPartial({ marketplace: m.marketplace, authority: m.authority.publicKey })
+      .rpc();
+
+  it("Lets the authority configure the oracle program", async () => {
+    const oracleProgram = Keypair.generate().publicKey;
+    await setOracleProgram(oracleProgram);
+    const mp = await m.program.account.marketplace.fetch(m.marketplace);
+    expect(mp.oracleProgram.toString()).to.equal(oracleProgram.toString());
+  });
+
+  it("Switches a listing between fixed and oracle pricing", async () => {
+    const listing = await m.createListing(1_000_000, 5);
+    const feed = Keypair.generate().publicKey;
+
+    const sig = await m.updateListing(listing, { priceFeed: feed }).rpc();
+    expect((await m.program.account.listing.fetch(listing)).priceFeed.toString()).to.equal(
+      feed.toString()
+    );
+    const updated = await eventOf(sig, "listingUpdated");
+    expect(updated.oldPriceFeed).to.be.null;
+    expect(updated.newPriceFeed.toString()).to.equal(feed.toString());
+
+    await m.updateListing(listing, { priceFeed: PublicKey.default }).rpc();
+    expect((await m.program.account.listing.fetch(listing)).priceFeed).to.be.null;
+  });
+
+  it("Requires the listing's feed and a price guard", async () => {
+    const feed = Keypair.generate();
+    const listing = await m.createListing(1_000_000, 5, false, { priceFeed: feed.publicKey });
+    const buyer = await m.buyer(10_000_000);
+
+    await expectError(m.buyNow(listing, buyer, 1, { priceGuard: guard }).rpc(), "WrongPriceFeed");
+    await expectError(
+      m
+        .buyNow(listing, buyer, 1, {
+          priceGuard: guard,
+          accounts: { priceFeed: Keypair.generate().publicKey },
+        })
+        .rpc(),
+      "WrongPriceFeed"
+    );
+    await expectError(
+      m.buyNow(listing, buyer, 1, { accounts: { priceFeed: feed.publicKey } }).rpc(),
+      "MissingPriceGuard"
+    );
+  });
+
+  it("Rejects feeds the oracle program doesn't own", async () => {
+    // a plain system account stands in for a look-alike feed
+    const feed = Keypair.generate().publicKey;
+    const listing = await m.createListing(1_000_000, 5, false, { priceFeed: feed });
+    const buyer = await m.buyer(10_000_000);
+
+    await expectError(
+      m.buyNow(listing, buyer, 1, { priceGuard: guard, accounts: { priceFeed: feed } }).rpc(),
+      "UntrustedPriceFeed"
+    );
+  });
+});
@@ -0,0 +1,95 @@
+import * as anchor from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { Market, allowlist, expectError, pda } from "./utils/market";
+
+type PublicKey = anchor.web3.PublicKey;
+type Buyer = { kp: anchor.web3.Keypair; ata: PublicKey };
+
+describe("purchase limits and allowlists", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  let m: Market;
+
+  before(async () => {
+    m = await Market.create();
+  });
+
+  const recordOf = (listing: PublicKey, buyer: Buyer) =>
+    pda(Buffer.from("purchase"), listing.toBuffer(), buyer.kp.publicKey.toBuffer());
+
+  const reserve = (listing: PublicKey, buyer: Buyer, quantity: number, proof: number[][] | null) =>
+    m.program.methods
+      .reserveInventory(quantity, new anchor.BN(600), proof)
+      .accountsPartial({
+        listing,
+        buyer: buyer.kp.publicKey,
+        payer: buyer.kp.publicKey,
+        reservation: pda(
+          Buffer.from("reservation"),
+          listing.toBuffer(),
+          buyer.kp.publicKey.toBuffer()
+        ),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([buyer.kp]);
+
+  it("Caps what one wallet can buy across purchases", async () => {
+    const listing = await m.createListing(1_000_000, 10, false, {
+      limits: { maxPerWallet: 3, allowlistRoot: null },
+    });
+    const buyer = await m.buyer(10_000_000);
+    const purchaseRecord = recordOf(listing, buyer);
+
+    await expectError(m.buyNow(listing, buyer, 1).rpc(), "PurchaseRecordRequired");
+    await m.buyNow(listing, buyer, 2, { accounts: { purchaseRecord } }).rpc();
+    await expectError(
+      m.buyNow(listing, buyer, 2, { accounts: { purchaseRecord } }).rpc(),
+      "WalletLimitExceeded"
+    );
+    await m.buyNow(listing, buyer, 1, { accounts: { purchaseRecord } }).rpc();
+
+    const record = await m.program.account.purchaseRecord.fetch(purchaseRecord);
+    expect(record.purchased).to.equal(3);
+    expect(record.buyer.toString()).to.equal(buyer.kp.publicKey.toString());
+
+    // the cap is per wallet
+    const other = await m.buyer(10_000_000);
+    await m.buyNow(listing, other, 3, { accounts: { purchaseRecord: recordOf(listing, other) } }).rpc();
+  });
+
+  it("Sells a presale only to allowlisted wallets", async () => {
+    const [member, outsider] = [await m.buyer(10_000_000), await m.buyer(10_000_000)];
+    const list = allowlist([
+      member.kp.publicKey,
+      ...Array.from({ length: 4 }, () => anchor.web3.Keypair.generate().publicKey),
+    ]);
+    const listing = await m.createListing(1_000_000, 10, false, {
+      limits: { maxPerWallet: null, allowlistRoot: list.root },
+    });
+
+    await expectError(m.buyNow(listing, member, 1).rpc(), "NotAllowlisted");
+    await expectError(
+      m.buyNow(listing, outsider, 1, { allowlistProof: list.proof(member.kp.publicKey) }).rpc(),
+      "NotAllowlisted"
+    );
+    await m.buyNow(listing, member, 1, { allowlistProof: list.proof(member.kp.publicKey) }).rpc();
+  });
+
+  it("Only lets allowlisted wallets hold presale stock", async () => {
+    const [member, outsider] = [await m.buyer(10_000_000), await m.buyer(10_000_000)];
+    const list = allowlist([member.kp.publicKey, anchor.web3.Keypair.generate().publicKey]);
+    const listing = await m.createListing(1_000_000, 5, false, {
+      limits: { maxPerWallet: null, allowlistRoot: list.root },
+    });
+
+    await expectError(reserve(listing, outsider, 5, null).rpc(), "NotAllowlisted");
+    await expectError(
+      reserve(listing, outsider, 5, list.proof(member.kp.publicKey)).rpc(),
+      "NotAllowlisted"
+    );
+    expect((await m.program.account.listing.fetch(listing)).reserved).to.equal(0);
+
+    await reserve(listing, member, 2, list.proof(member.kp.publicKey)).rpc();
+    expect((await m.program.account.listing.fetch(listing)).reserved).to.equal(2);
+  });
+});
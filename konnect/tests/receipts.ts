@@ -0,0 +1,100 @@
+import * as anchor from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { FEE_BPS, Market, exists, expectError, pda, wallet } from "./utils/market";
+
+type PublicKey = anchor.web3.PublicKey;
+type Buyer = { kp: anchor.web3.Keypair; ata: PublicKey };
+
+describe("purchase receipts", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  let m: Market;
+
+  before(async () => {
+    m = await Market.create();
+    await m.updateMarketplace({ returnWindowSecs: 3600 }).rpc();
+  });
+
+  const purchase = async (listing: PublicKey, buyer: Buyer, quantity = 1) => {
+    const reference = anchor.web3.Keypair.generate().publicKey;
+    const receipt = m.receiptOf(reference);
+    await m.buyNow(listing, buyer, quantity, { reference, accounts: { receipt } }).rpc();
+    return { reference, receipt };
+  };
+
+  const closeReceipt = (receipt: PublicKey, buyer: anchor.web3.Keypair) =>
+    m.program.methods
+      .closeReceipt()
+      .accountsPartial({ receipt, buyer: buyer.publicKey, rentPayer: buyer.publicKey })
+      .signers([buyer]);
+
+  it("Records the order", async () => {
+    const listing = await m.createListing(2_000_000, 5);
+    const buyer = await m.buyer(10_000_000);
+    const { reference, receipt } = await purchase(listing, buyer, 2);
+
+    const r = await m.program.account.receipt.fetch(receipt);
+    expect(r.marketplace.toString()).to.equal(m.marketplace.toString());
+    expect(r.listing.toString()).to.equal(listing.toString());
+    expect(r.buyer.toString()).to.equal(buyer.kp.publicKey.toString());
+    expect(r.seller.toString()).to.equal(m.seller.publicKey.toString());
+    expect(r.mint.toString()).to.equal(m.mint.toString());
+    expect(r.quantity).to.equal(2);
+    expect(r.amount.toNumber()).to.equal(4_000_000);
+    expect(r.fee.toNumber()).to.equal((4_000_000 * FEE_BPS) / 10_000);
+    expect(r.reference.toString()).to.equal(reference.toString());
+    expect(r.status).to.deep.equal({ completed: {} });
+    expect(r.isService).to.be.false;
+    expect(r.timestamp.toNumber()).to.be.closeTo(Date.now() / 1000, 120);
+  });
+
+  it("Is optional and unique per reference", async () => {
+    const listing = await m.createListing(1_000_000, 5);
+    const buyer = await m.buyer(10_000_000);
+    const reference = anchor.web3.Keypair.generate().publicKey;
+
+    await m.buyNow(listing, buyer, 1, { reference }).rpc();
+    expect(await exists(m.receiptOf(reference))).to.be.false;
+
+    const receipt = m.receiptOf(reference);
+    await m.buyNow(listing, buyer, 1, { reference, accounts: { receipt } }).rpc();
+    await expectError(
+      m.buyNow(listing, buyer, 1, { reference, accounts: { receipt } }).rpc(),
+      "already in use"
+    );
+  });
+
+  it("Is closed by the buyer for its rent", async () => {
+    const listing = await m.createListing(1_000_000, 5);
+    const buyer = await m.buyer(10_000_000);
+    const { receipt } = await purchase(listing, buyer);
+    const rent = await m.connection.getBalance(receipt);
+
+    await expectError(closeReceipt(receipt, await wallet()).rpc(), "ConstraintHasOne");
+
+    const before = await m.connection.getBalance(buyer.kp.publicKey);
+    await closeReceipt(receipt, buyer.kp).rpc();
+    expect(await exists(receipt)).to.be.false;
+    // the buyer signs and pays the transaction fee
+    expect(await m.connection.getBalance(buyer.kp.publicKey)).to.be.greaterThan(before + rent - 10_000);
+  });
+
+  it("Can't be closed while a return is pending", async () => {
+    const listing = await m.createListing(1_000_000, 5);
+    const buyer = await m.buyer(10_000_000);
+    const { receipt } = await purchase(listing, buyer);
+
+    await m.program.methods
+      .requestReturn(Array(32).fill(1))
+      .accountsPartial({
+        receipt,
+        marketplace: m.marketplace,
+        returnRequest: pda(Buffer.from("return"), receipt.toBuffer()),
+        buyer: buyer.kp.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([buyer.kp])
+      .rpc();
+    await expectError(closeReceipt(receipt, buyer.kp).rpc(), "ReturnPending");
+  });
+});
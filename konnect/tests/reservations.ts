@@ -0,0 +1,164 @@
+import * as anchor from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { Market, exists, expectError, pda, sleep, wallet } from "./utils/market";
+
+type PublicKey = anchor.web3.PublicKey;
+type Buyer = { kp: anchor.web3.Keypair; ata: PublicKey };
+
+describe("inventory reservations", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  let m: Market;
+
+  before(async () => {
+    m = await Market.create();
+  });
+
+  const reservationOf = (listing: PublicKey, buyer: Buyer) =>
+    pda(Buffer.from("reservation"), listing.toBuffer(), buyer.kp.publicKey.toBuffer());
+
+  const reserve = (
+    listing: PublicKey,
+    buyer: Buyer,
+    quantity: number,
+    ttl: number,
+    allowlistProof: number[][] | null = null,
+    payer: PublicKey = buyer.kp.publicKey
+  ) =>
+    m.program.methods
+      .reserveInventory(quantity, new anchor.BN(ttl), allowlistProof)
+      .accountsPartial({
+        listing,
+        buyer: buyer.kp.publicKey,
+        payer,
+        reservation: reservationOf(listing, buyer),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([buyer.kp]);
+
+  // buy_now redeeming the buyer's hold, with its rent going back to `rentPayer`
+  const buyReserved = (listing: PublicKey, buyer: Buyer, quantity: number, rentPayer = buyer.kp.publicKey) =>
+    m.buyNow(listing, buyer, quantity, {
+      accounts: { reservation: reservationOf(listing, buyer), reservationRentPayer: rentPayer },
+    });
+
+  const release = (listing: PublicKey, buyer: Buyer, caller: anchor.web3.Keypair) =>
+    m.program.methods
+      .releaseReservation()
+      .accountsPartial({
+        reservation: reservationOf(listing, buyer),
+        listing,
+        rentPayer: buyer.kp.publicKey,
+        caller: caller.publicKey,
+      })
+      .signers([caller]);
+
+  it("Holds stock for the buyer", async () => {
+    const listing = await m.createListing(1_000_000, 5);
+    const buyer = await m.buyer(10_000_000);
+    await reserve(listing, buyer, 2, 600).rpc();
+
+    const l = await m.program.account.listing.fetch(listing);
+    expect(l.quantity).to.equal(3);
+    expect(l.reserved).to.equal(2);
+    const r = await m.program.account.reservation.fetch(reservationOf(listing, buyer));
+    expect(r.quantity).to.equal(2);
+
+    // other buyers only see what is left
+    const other = await m.buyer(10_000_000);
+    await expectError(m.buyNow(listing, other, 4).rpc(), "InvalidQuantity");
+  });
+
+  it("Validates the hold", async () => {
+    const listing = await m.createListing(1_000_000, 5);
+    const buyer = await m.buyer(10_000_000);
+    await expectError(reserve(listing, buyer, 1, 0).rpc(), "InvalidReservationTtl");
+    await expectError(reserve(listing, buyer, 1, 15 * 60 + 1).rpc(), "InvalidReservationTtl");
+    await expectError(reserve(listing, buyer, 6, 60).rpc(), "InvalidQuantity");
+    await expectError(reserve(listing, buyer, 0, 60).rpc(), "InvalidQuantity");
+
+    const service = await m.createListing(1_000_000, 5, true);
+    await expectError(reserve(service, buyer, 1, 60).rpc(), "WrongFlowForService");
+  });
+
+  it("Is consumed by the buyer's purchase", async () => {
+    const listing = await m.createListing(1_000_000, 5);
+    const buyer = await m.buyer(10_000_000);
+    const reservation = reservationOf(listing, buyer);
+    await reserve(listing, buyer, 3, 600).rpc();
+
+    await expectError(buyReserved(listing, buyer, 4).rpc(), "InvalidQuantity");
+    // buying fewer than held returns the rest to stock
+    await buyReserved(listing, buyer, 2).rpc();
+
+    const l = await m.program.account.listing.fetch(listing);
+    expect(l.quantity).to.equal(3);
+    expect(l.reserved).to.equal(0);
+    expect(await exists(reservation)).to.be.false;
+  });
+
+  it("Returns the rent to whoever paid for the hold", async () => {
+    const listing = await m.createListing(1_000_000, 5);
+    const buyer = await m.buyer(10_000_000);
+    // a relayer sponsors the hold, the buyer then pays for the purchase themselves
+    const relayer = m.authority.publicKey;
+    const reservation = reservationOf(listing, buyer);
+    await reserve(listing, buyer, 2, 600, null, relayer).rpc();
+    const rent = await m.connection.getBalance(reservation);
+
+    await expectError(buyReserved(listing, buyer, 2).rpc(), "InvalidAccount");
+
+    const before = await m.connection.getBalance(relayer);
+    await buyReserved(listing, buyer, 2, relayer).rpc();
+    expect(await exists(reservation)).to.be.false;
+    // the relayer is also the provider wallet paying the transaction fee
+    expect((await m.connection.getBalance(relayer)) - before).to.be.greaterThan(rent - 10_000);
+  });
+
+  it("Can't be used by another buyer", async () => {
+    const listing = await m.createListing(1_000_000, 5);
+    const buyer = await m.buyer(10_000_000);
+    const other = await m.buyer(10_000_000);
+    await reserve(listing, buyer, 2, 600).rpc();
+
+    await expectError(
+      m.buyNow(listing, other, 1, {
+        accounts: { reservation: reservationOf(listing, buyer), reservationRentPayer: buyer.kp.publicKey },
+      }).rpc(),
+      "ConstraintHasOne"
+    );
+  });
+
+  it("Is released by the buyer at any time and by anyone after expiry", async () => {
+    const listing = await m.createListing(1_000_000, 5);
+    const buyer = await m.buyer(10_000_000);
+    const stranger = await wallet();
+    await reserve(listing, buyer, 2, 2).rpc();
+
+    await expectError(release(listing, buyer, stranger).rpc(), "ReservationNotExpired");
+    await sleep(4_000);
+    await expectError(buyReserved(listing, buyer, 1).rpc(), "ReservationExpired");
+    await release(listing, buyer, stranger).rpc();
+
+    let l = await m.program.account.listing.fetch(listing);
+    expect([l.quantity, l.reserved]).to.deep.equal([5, 0]);
+
+    await reserve(listing, buyer, 1, 600).rpc();
+    await release(listing, buyer, buyer.kp).rpc();
+    l = await m.program.account.listing.fetch(listing);
+    expect([l.quantity, l.reserved]).to.deep.equal([5, 0]);
+  });
+
+  it("Keeps a fully reserved listing open and blocks closing it", async () => {
+    const listing = await m.createListing(1_000_000, 1);
+    const buyer = await m.buyer(10_000_000);
+    await reserve(listing, buyer, 1, 600).rpc();
+
+    expect((await m.program.account.listing.fetch(listing)).active).to.be.true;
+    await m.updateListing(listing, { active: false }).rpc();
+    await expectError(m.closeListing(listing).rpc(), "OutstandingReservations");
+
+    await release(listing, buyer, buyer.kp).rpc();
+    await m.closeListing(listing).rpc();
+  });
+});
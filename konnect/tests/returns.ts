@@ -0,0 +1,250 @@
+import * as anchor from "@coral-xyz/anchor";
+import { TOKEN_PROGRAM_ID, approve } from "@solana/spl-token";
+import { expect } from "chai";
+import { FEE_BPS, Market, balance, eventOf, exists, expectError, pda, u16 } from "./utils/market";
+
+type PublicKey = anchor.web3.PublicKey;
+type Buyer = { kp: anchor.web3.Keypair; ata: PublicKey };
+
+describe("returns and refunds", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const price = 1_000_000;
+  const fee = (price * FEE_BPS) / 10_000;
+  let m: Market;
+
+  before(async () => {
+    m = await Market.create();
+    await m.updateMarketplace({ returnWindowSecs: 3600, pointsBps: 100 }).rpc();
+  });
+
+  const returnOf = (receipt: PublicKey) => pda(Buffer.from("return"), receipt.toBuffer());
+
+  const purchase = async (
+    listing: PublicKey,
+    buyer: Buyer,
+    accounts: Record<string, PublicKey | null> = {}
+  ) => {
+    const reference = anchor.web3.Keypair.generate().publicKey;
+    const receipt = m.receiptOf(reference);
+    await m.buyNow(listing, buyer, 1, { reference, accounts: { receipt, ...accounts } }).rpc();
+    return receipt;
+  };
+
+  const requestReturn = (receipt: PublicKey, buyer: Buyer) =>
+    m.program.methods
+      .requestReturn(Array(32).fill(1))
+      .accountsPartial({
+        receipt,
+        marketplace: m.marketplace,
+        returnRequest: returnOf(receipt),
+        buyer: buyer.kp.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([buyer.kp]);
+
+  const refund = (
+    receipt: PublicKey,
+    buyer: Buyer,
+    listing: PublicKey,
+    restock: boolean,
+    accounts: Record<string, PublicKey | null> = {}
+  ) =>
+    m.program.methods
+      .refundOrder(restock)
+      .accountsPartial({
+        receipt,
+        returnRequest: returnOf(receipt),
+        marketplace: m.marketplace,
+        merchant: m.merchant,
+        buyerProfile: m.profileOf(buyer.kp.publicKey),
+        listing: restock ? listing : null,
+        variant: null,
+        seller: m.seller.publicKey,
+        buyer: buyer.kp.publicKey,
+        sellerAta: m.sellerAta,
+        refundAta: buyer.ata,
+        mint: m.mint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        ...accounts,
+      })
+      .signers([m.seller]);
+
+  it("Refunds the buyer and relists sold-out stock", async () => {
+    const listing = await m.createListing(price, 1);
+    const buyer = await m.buyer(price);
+    const receipt = await purchase(listing, buyer);
+    expect((await m.program.account.listing.fetch(listing)).active).to.be.false;
+    const merchantBefore = await m.program.account.merchant.fetch(m.merchant);
+
+    await requestReturn(receipt, buyer).rpc();
+    const sig = await refund(receipt, buyer, listing, true).rpc();
+
+    // the marketplace keeps its fee by default
+    expect(await balance(buyer.ata)).to.equal(price - fee);
+    const l = await m.program.account.listing.fetch(listing);
+    expect([l.quantity, l.active]).to.deep.equal([1, true]);
+    const merchant = await m.program.account.merchant.fetch(m.merchant);
+    expect(merchant.salesCount.toNumber()).to.equal(merchantBefore.salesCount.toNumber() - 1);
+    expect(merchant.salesVolume.toNumber()).to.equal(merchantBefore.salesVolume.toNumber() - price);
+    expect((await m.program.account.receipt.fetch(receipt)).status).to.deep.equal({ refunded: {} });
+    expect(await exists(returnOf(receipt))).to.be.false;
+
+    const refunded = await eventOf(sig, "orderRefunded");
+    expect([refunded.amount.toNumber(), refunded.feeRetained.toNumber(), refunded.restocked]).to.deep.equal([
+      price - fee,
+      fee,
+      1,
+    ]);
+  });
+
+  // a finance wallet pays for an order an employee places as its approved delegate
+  it("Refunds a delegated order to the paying wallet", async () => {
+    const listing = await m.createListing(price, 5);
+    const finance = await m.buyer(price);
+    const employee = await m.buyer(0);
+    await approve(m.connection, m.authority, finance.ata, employee.kp.publicKey, finance.kp, price);
+
+    const reference = anchor.web3.Keypair.generate().publicKey;
+    const receipt = m.receiptOf(reference);
+    const sig = await m
+      .buyNow(listing, { kp: employee.kp, ata: finance.ata }, 1, { reference, accounts: { receipt } })
+      .rpc();
+    const order = await eventOf(sig, "orderCompleted");
+    expect(order.buyer.toString()).to.equal(employee.kp.publicKey.toString());
+    expect(order.payer.toString()).to.equal(finance.kp.publicKey.toString());
+    const r = await m.program.account.receipt.fetch(receipt);
+    expect(r.buyer.toString()).to.equal(employee.kp.publicKey.toString());
+    expect(r.fundingAccount.toString()).to.equal(finance.ata.toString());
+
+    await requestReturn(receipt, employee).rpc();
+    await expectError(refund(receipt, employee, listing, false).rpc(), "InvalidAccount");
+    await refund(receipt, employee, listing, false, { refundAta: finance.ata }).rpc();
+    expect(await balance(finance.ata)).to.equal(price - fee);
+    expect(await balance(employee.ata)).to.equal(0);
+  });
+
+  it("Relists a sold-out variant", async () => {
+    const listing = await m.createListing(price, 0);
+    const variant = pda(Buffer.from("variant"), listing.toBuffer(), u16(3));
+    await m.program.methods
+      .addVariant(3, new anchor.BN(price), 1)
+      .accountsPartial({
+        listing,
+        seller: m.seller.publicKey,
+        variant,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([m.seller])
+      .rpc();
+    const buyer = await m.buyer(price);
+    const reference = anchor.web3.Keypair.generate().publicKey;
+    const receipt = m.receiptOf(reference);
+    await m.buyNow(listing, buyer, 1, { reference, variantId: 3, accounts: { receipt, variant } }).rpc();
+    expect((await m.program.account.variant.fetch(variant)).active).to.be.false;
+
+    await requestReturn(receipt, buyer).rpc();
+    await refund(receipt, buyer, listing, true, { variant }).rpc();
+    const v = await m.program.account.variant.fetch(variant);
+    expect([v.quantity, v.active]).to.deep.equal([1, true]);
+  });
+
+  it("Reverses the loyalty points of the order", async () => {
+    const listing = await m.createListing(price, 5);
+    const buyer = await m.buyer(price);
+    const buyerProfile = m.profileOf(buyer.kp.publicKey);
+    const receipt = await purchase(listing, buyer);
+    const earned = (price * 100) / 10_000;
+    expect((await m.program.account.buyerProfile.fetch(buyerProfile)).points.toNumber()).to.equal(earned);
+    expect((await m.program.account.receipt.fetch(receipt)).pointsEarned.toNumber()).to.equal(earned);
+
+    await requestReturn(receipt, buyer).rpc();
+    await expectError(
+      refund(receipt, buyer, listing, false, { buyerProfile: null }).rpc(),
+      "BuyerProfileRequired"
+    );
+    const sig = await refund(receipt, buyer, listing, false).rpc();
+
+    const p = await m.program.account.buyerProfile.fetch(buyerProfile);
+    expect([p.points.toNumber(), p.lifetimePoints.toNumber()]).to.deep.equal([0, 0]);
+    expect((await eventOf(sig, "orderRefunded")).pointsClawedBack.toNumber()).to.equal(earned);
+  });
+
+  it("Lets the seller reject and the buyer withdraw a request", async () => {
+    const listing = await m.createListing(price, 5);
+    const buyer = await m.buyer(2 * price);
+    const rejected = await purchase(listing, buyer);
+    const withdrawn = await purchase(listing, buyer);
+
+    await requestReturn(rejected, buyer).rpc();
+    const sig = await m.program.methods
+      .rejectReturn()
+      .accountsPartial({
+        receipt: rejected,
+        returnRequest: returnOf(rejected),
+        seller: m.seller.publicKey,
+        buyer: buyer.kp.publicKey,
+      })
+      .signers([m.seller])
+      .rpc();
+    expect((await m.program.account.receipt.fetch(rejected)).status).to.deep.equal({ returnRejected: {} });
+    expect(await exists(returnOf(rejected))).to.be.false;
+    expect((await eventOf(sig, "returnResolved")).rejected).to.be.true;
+    // a rejected return goes to the arbiter, not back to the seller
+    await expectError(requestReturn(rejected, buyer).rpc(), "InvalidReceiptStatus");
+
+    await requestReturn(withdrawn, buyer).rpc();
+    const withdraw = () =>
+      m.program.methods
+        .withdrawReturn()
+        .accountsPartial({
+          receipt: withdrawn,
+          returnRequest: returnOf(withdrawn),
+          buyer: buyer.kp.publicKey,
+        })
+        .signers([buyer.kp])
+        .rpc();
+    await withdraw();
+    expect((await m.program.account.receipt.fetch(withdrawn)).status).to.deep.equal({ completed: {} });
+    // the buyer may ask again while the window is open
+    await requestReturn(withdrawn, buyer).rpc();
+  });
+
+  it("Follows the marketplace fee refund policy", async () => {
+    await m.updateMarketplace({ feeRefundPolicy: { sellerCoversFee: {} } }).rpc();
+    try {
+      const listing = await m.createListing(price, 5);
+      const buyer = await m.buyer(price);
+      const receipt = await purchase(listing, buyer);
+      await requestReturn(receipt, buyer).rpc();
+      await refund(receipt, buyer, listing, false).rpc();
+      expect(await balance(buyer.ata)).to.equal(price);
+    } finally {
+      await m.updateMarketplace({ feeRefundPolicy: { retainFee: {} } }).rpc();
+    }
+  });
+
+  it("Refuses returns outside the window and for services", async () => {
+    const listing = await m.createListing(price, 5);
+    const buyer = await m.buyer(price);
+    const receipt = await purchase(listing, buyer);
+    await m.updateMarketplace({ returnWindowSecs: 0 }).rpc();
+    try {
+      await expectError(requestReturn(receipt, buyer).rpc(), "ReturnWindowClosed");
+    } finally {
+      await m.updateMarketplace({ returnWindowSecs: 3600 }).rpc();
+    }
+
+    const service = await m.createListing(price, 0, true);
+    const client = await m.buyer(price);
+    await m.createServiceOrder(service, client).rpc();
+    const [escrow] = m.escrowOf(service, client.kp.publicKey);
+    const { reference } = await m.program.account.escrow.fetch(escrow);
+    await (await m.releaseServiceOrder(escrow, m.authority)).rpc();
+    const serviceReceipt = m.receiptOf(reference, escrow);
+    expect((await m.program.account.receipt.fetch(serviceReceipt)).isService).to.be.true;
+    await expectError(requestReturn(serviceReceipt, client).rpc(), "WrongFlowForService");
+    expect(await exists(returnOf(serviceReceipt))).to.be.false;
+    expect((await m.program.account.receipt.fetch(serviceReceipt)).status).to.deep.equal({ completed: {} });
+  });
+});
@@ -0,0 +1,129 @@
+import * as anchor from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { Market, eventOf, expectError, pda, wallet } from "./utils/market";
+
+type PublicKey = anchor.web3.PublicKey;
+type Buyer = { kp: anchor.web3.Keypair; ata: PublicKey };
+
+describe("reviews", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const contentHash = Array(32).fill(5);
+  let m: Market;
+
+  before(async () => {
+    m = await Market.create();
+  });
+
+  const reviewOf = (receipt: PublicKey) => pda(Buffer.from("review"), receipt.toBuffer());
+
+  // a goods purchase that keeps a receipt
+  const purchase = async (listing: PublicKey, buyer: Buyer) => {
+    const reference = anchor.web3.Keypair.generate().publicKey;
+    const receipt = m.receiptOf(reference);
+    await m.buyNow(listing, buyer, 1, { reference, accounts: { receipt } }).rpc();
+    return { reference, receipt };
+  };
+
+  const review = (
+    buyer: anchor.web3.Keypair,
+    receipt: PublicKey,
+    listing: PublicKey,
+    rating = 4
+  ) =>
+    m.program.methods
+      .submitReview(rating, contentHash)
+      .accountsPartial({
+        receipt,
+        listing,
+        merchant: m.merchant,
+        review: reviewOf(receipt),
+        buyer: buyer.publicKey,
+        payer: buyer.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([buyer]);
+
+  it("Reviews a goods purchase and aggregates the rating", async () => {
+    const listing = await m.createListing(1_000_000, 5);
+    const buyer = await m.buyer(10_000_000);
+    const { reference, receipt } = await purchase(listing, buyer);
+    const merchantBefore = await m.program.account.merchant.fetch(m.merchant);
+
+    const sig = await review(buyer.kp, receipt, listing, 4).rpc();
+
+    const r = await m.program.account.review.fetch(reviewOf(receipt));
+    expect(r.rating).to.equal(4);
+    expect(r.reviewer.toString()).to.equal(buyer.kp.publicKey.toString());
+    expect(r.reference.toString()).to.equal(reference.toString());
+    const l = await m.program.account.listing.fetch(listing);
+    expect([l.ratingSum.toNumber(), l.ratingCount]).to.deep.equal([4, 1]);
+    const merchant = await m.program.account.merchant.fetch(m.merchant);
+    expect(merchant.ratingCount).to.equal(merchantBefore.ratingCount + 1);
+    expect((await eventOf(sig, "reviewSubmitted")).rating).to.equal(4);
+    // the receipt stays as proof of purchase
+    expect((await m.program.account.receipt.fetch(receipt)).status).to.deep.equal({ completed: {} });
+  });
+
+  it("Allows one review per order, and one per order for repeat orders", async () => {
+    const listing = await m.createListing(1_000_000, 5);
+    const buyer = await m.buyer(10_000_000);
+    const first = await purchase(listing, buyer);
+    await review(buyer.kp, first.receipt, listing).rpc();
+    await expectError(
+      review(buyer.kp, first.receipt, listing).rpc(),
+      "already in use"
+    );
+
+    const second = await purchase(listing, buyer);
+    await review(buyer.kp, second.receipt, listing, 2).rpc();
+    const l = await m.program.account.listing.fetch(listing);
+    expect([l.ratingSum.toNumber(), l.ratingCount]).to.deep.equal([6, 2]);
+  });
+
+  it("Reviews a released service order from its receipt", async () => {
+    const listing = await m.createListing(1_000_000, 0, true);
+    const buyer = await m.buyer(1_000_000);
+    await m.createServiceOrder(listing, buyer).rpc();
+    const [escrow] = m.escrowOf(listing, buyer.kp.publicKey);
+    const { reference } = await m.program.account.escrow.fetch(escrow);
+    await (await m.releaseServiceOrder(escrow, m.authority)).rpc();
+
+    const receipt = m.receiptOf(reference, escrow);
+    await review(buyer.kp, receipt, listing, 5).rpc();
+    expect((await m.program.account.review.fetch(reviewOf(receipt))).rating).to.equal(5);
+  });
+
+  it("Rejects bad ratings, other buyers and other listings", async () => {
+    const listing = await m.createListing(1_000_000, 5);
+    const other = await m.createListing(1_000_000, 5);
+    const buyer = await m.buyer(10_000_000);
+    const { receipt } = await purchase(listing, buyer);
+
+    for (const rating of [0, 6]) {
+      await expectError(review(buyer.kp, receipt, listing, rating).rpc(), "InvalidRating");
+    }
+    await expectError(review(await wallet(), receipt, listing).rpc(), "ConstraintHasOne");
+    await expectError(review(buyer.kp, receipt, other).rpc(), "ConstraintHasOne");
+  });
+
+  it("Lets only the seller respond", async () => {
+    const listing = await m.createListing(1_000_000, 5);
+    const buyer = await m.buyer(10_000_000);
+    const { reference, receipt } = await purchase(listing, buyer);
+    await review(buyer.kp, receipt, listing).rpc();
+
+    const respond = (seller: anchor.web3.Keypair) =>
+      m.program.methods
+        .respondToReview(Array(32).fill(8))
+        .accountsPartial({ review: reviewOf(receipt), seller: seller.publicKey })
+        .signers([seller])
+        .rpc();
+
+    await expectError(respond(buyer.kp), "ConstraintHasOne");
+    await respond(m.seller);
+    expect((await m.program.account.review.fetch(reviewOf(receipt))).responseHash).to.deep.equal(
+      Array(32).fill(8)
+    );
+  });
+});
@@ -0,0 +1,65 @@
+import * as anchor from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { Market, expectError, sleep } from "./utils/market";
+
+const { BN } = anchor;
+
+// boundaries against an exact clock are covered by programs/konnect/tests/sale_window.rs
+describe("sale windows", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  let m: Market;
+
+  before(async () => {
+    m = await Market.create();
+  });
+
+  const now = async () => {
+    const slot = await m.connection.getSlot();
+    return (await m.connection.getBlockTime(slot)) as number;
+  };
+
+  it("Holds a drop until it starts", async () => {
+    const start = (await now()) + 3;
+    const listing = await m.createListing(1_000_000, 5, false, {
+      saleWindow: { saleStart: new BN(start), saleEnd: null },
+    });
+    const buyer = await m.buyer(10_000_000);
+
+    await expectError(m.buyNow(listing, buyer, 1).rpc(), "SaleNotStarted");
+    while ((await now()) < start) {
+      await sleep(500);
+    }
+    await m.buyNow(listing, buyer, 1).rpc();
+  });
+
+  it("Ends a promo automatically", async () => {
+    const listing = await m.createListing(1_000_000, 5, false, {
+      saleWindow: { saleStart: null, saleEnd: new BN((await now()) - 1) },
+    });
+    const buyer = await m.buyer(10_000_000);
+    await expectError(m.buyNow(listing, buyer, 1).rpc(), "SaleEnded");
+
+    // the seller can reopen it
+    await m.updateListing(listing, { saleWindow: { saleStart: null, saleEnd: null } }).rpc();
+    await m.buyNow(listing, buyer, 1).rpc();
+  });
+
+  it("Applies to service orders", async () => {
+    const listing = await m.createListing(1_000_000, 0, true, {
+      saleWindow: { saleStart: new BN((await now()) + 3600), saleEnd: null },
+    });
+    const buyer = await m.buyer(1_000_000);
+    await expectError(m.createServiceOrder(listing, buyer).rpc(), "SaleNotStarted");
+  });
+
+  it("Rejects windows that end before they start", async () => {
+    const saleWindow = { saleStart: new BN(200), saleEnd: new BN(100) };
+    await expectError(m.createListing(1_000_000, 5, false, { saleWindow }), "InvalidSaleWindow");
+
+    const listing = await m.createListing(1_000_000, 5);
+    await expectError(m.updateListing(listing, { saleWindow }).rpc(), "InvalidSaleWindow");
+    const l = await m.program.account.listing.fetch(listing);
+    expect(l.saleWindow.saleStart).to.be.null;
+  });
+});
@@ -0,0 +1,151 @@
+import * as anchor from "@coral-xyz/anchor";
+import { TOKEN_PROGRAM_ID, approve, getAccount } from "@solana/spl-token";
+import { expect } from "chai";
+import { Market, balance, exists, expectError, pda, sleep } from "./utils/market";
+
+const { Keypair, SystemProgram } = anchor.web3;
+type PublicKey = anchor.web3.PublicKey;
+type Keypair = anchor.web3.Keypair;
+type Buyer = { kp: Keypair; ata: PublicKey };
+
+describe("sponsored purchases and session keys", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  let m: Market;
+  // the provider wallet stands in for the backend relayer
+  let relayer: PublicKey;
+
+  before(async () => {
+    m = await Market.create();
+    relayer = m.authority.publicKey;
+  });
+
+  // a buyer holding only tokens, no SOL
+  const tokenOnlyBuyer = async (amount: number): Promise<Buyer> => {
+    const kp = Keypair.generate();
+    const ata = await m.ata(m.mint, kp.publicKey);
+    await m.fund(ata, amount);
+    return { kp, ata };
+  };
+
+  const sessionOf = (buyer: PublicKey, signer: PublicKey) =>
+    pda(Buffer.from("session"), buyer.toBuffer(), signer.toBuffer());
+
+  const chainTime = async () => m.connection.getBlockTime(await m.connection.getSlot());
+
+  const createSession = async (buyer: Buyer, signer: PublicKey, spendLimit: number, ttl = 3_600) =>
+    m.program.methods
+      .createSessionKey(signer, new anchor.BN(spendLimit), new anchor.BN((await chainTime()) + ttl))
+      .accountsPartial({
+        buyer: buyer.kp.publicKey,
+        payer: relayer,
+        session: sessionOf(buyer.kp.publicKey, signer),
+        buyerAta: buyer.ata,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([buyer.kp])
+      .rpc();
+
+  // buy_now signed by a session key, with the relayer paying rent and fees
+  const buyWithSession = (listing: PublicKey, buyer: Buyer, key: Keypair, quantity: number, session?: PublicKey) =>
+    m.buyNow(listing, buyer, quantity, {
+      accounts: {
+        signer: key.publicKey,
+        session: session ?? sessionOf(buyer.kp.publicKey, key.publicKey),
+        payer: relayer,
+      },
+      signers: [key],
+    });
+
+  it("Lets a relayer pay for a buyer without SOL", async () => {
+    const listing = await m.createListing(1_000_000, 5);
+    const buyer = await tokenOnlyBuyer(5_000_000);
+
+    await m.buyNow(listing, buyer, 2, { accounts: { payer: relayer } }).rpc();
+
+    expect(await balance(buyer.ata)).to.equal(3_000_000);
+    expect(await m.connection.getBalance(buyer.kp.publicKey)).to.equal(0);
+  });
+
+  it("Lets a session key spend up to its cap", async () => {
+    const listing = await m.createListing(1_000_000, 10);
+    const buyer = await tokenOnlyBuyer(10_000_000);
+    const key = Keypair.generate();
+    await createSession(buyer, key.publicKey, 3_000_000);
+
+    await buyWithSession(listing, buyer, key, 2).rpc();
+    expect(await balance(buyer.ata)).to.equal(8_000_000);
+    const session = await m.program.account.sessionKey.fetch(sessionOf(buyer.kp.publicKey, key.publicKey));
+    expect(session.spent.toNumber()).to.equal(2_000_000);
+
+    await expectError(buyWithSession(listing, buyer, key, 2).rpc(), "SessionLimitExceeded");
+    await buyWithSession(listing, buyer, key, 1).rpc();
+    expect(await balance(buyer.ata)).to.equal(7_000_000);
+    expect(await m.connection.getBalance(buyer.kp.publicKey)).to.equal(0);
+  });
+
+  it("Rejects an expired session", async () => {
+    const listing = await m.createListing(1_000_000, 10);
+    const buyer = await tokenOnlyBuyer(10_000_000);
+    const key = Keypair.generate();
+    await createSession(buyer, key.publicKey, 5_000_000, 5);
+
+    await sleep(8_000);
+    await expectError(buyWithSession(listing, buyer, key, 1).rpc(), "InvalidSession");
+    expect(await balance(buyer.ata)).to.equal(10_000_000);
+  });
+
+  it("Only accepts the key the session was created for", async () => {
+    const listing = await m.createListing(1_000_000, 10);
+    const buyer = await tokenOnlyBuyer(10_000_000);
+    const key = Keypair.generate();
+    const other = Keypair.generate();
+    await createSession(buyer, key.publicKey, 5_000_000);
+
+    // another key presenting the buyer's session doesn't match its seeds
+    await expectError(
+      buyWithSession(listing, buyer, other, 1, sessionOf(buyer.kp.publicKey, key.publicKey)).rpc(),
+      "ConstraintSeeds"
+    );
+    // and without a session it is neither the buyer nor an approved delegate
+    await expectError(
+      m.buyNow(listing, buyer, 1, { accounts: { signer: other.publicKey, payer: relayer }, signers: [other] }).rpc(),
+      "UnauthorizedSigner"
+    );
+    expect(await balance(buyer.ata)).to.equal(10_000_000);
+  });
+
+  it("Revokes a session after the buyer approved another delegate", async () => {
+    const buyer = await tokenOnlyBuyer(10_000_000);
+    const key = Keypair.generate();
+    await createSession(buyer, key.publicKey, 5_000_000);
+    const session = sessionOf(buyer.kp.publicKey, key.publicKey);
+
+    // approving someone else replaces the session PDA as delegate
+    const delegate = Keypair.generate().publicKey;
+    await approve(m.connection, m.authority, buyer.ata, delegate, buyer.kp, 1_000);
+
+    const rent = await m.connection.getBalance(session);
+    const before = await m.connection.getBalance(relayer);
+    await m.program.methods
+      .revokeSessionKey()
+      .accountsPartial({
+        session,
+        buyer: buyer.kp.publicKey,
+        rentPayer: relayer,
+        buyerAta: buyer.ata,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([buyer.kp])
+      .rpc();
+
+    expect(await exists(session)).to.be.false;
+    // the later approval is left alone
+    const ata = await getAccount(m.connection, buyer.ata);
+    expect(ata.delegate.toString()).to.equal(delegate.toString());
+    expect(Number(ata.delegatedAmount)).to.equal(1_000);
+    // the relayer paid the fee and got the session rent back
+    expect((await m.connection.getBalance(relayer)) - before).to.be.greaterThan(rent - 10_000);
+  });
+});
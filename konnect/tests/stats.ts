@@ -0,0 +1,74 @@
+import * as anchor from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { Market } from "./utils/market";
+
+describe("marketplace stats", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  let m: Market;
+
+  before(async () => {
+    m = await Market.create();
+  });
+
+  // the counters a dashboard reads, across the marketplace, the mint's treasury and the seller
+  const stats = async () => {
+    const mp = await m.program.account.marketplace.fetch(m.marketplace);
+    const t = await m.program.account.treasury.fetch(m.treasury);
+    const merchant = await m.program.account.merchant.fetch(m.merchant);
+    return {
+      orders: mp.orderCount.toNumber(),
+      openEscrows: mp.openEscrowCount,
+      volume: t.volume.toNumber(),
+      locked: t.openEscrowAmount.toNumber(),
+      sales: merchant.salesCount.toNumber(),
+      salesVolume: merchant.salesVolume.toNumber(),
+    };
+  };
+
+  it("Counts goods orders and their volume", async () => {
+    const listing = await m.createListing(1_000_000, 5);
+    const buyer = await m.buyer(10_000_000);
+    const before = await stats();
+
+    await m.buyNow(listing, buyer, 2).rpc();
+
+    expect(await stats()).to.deep.equal({
+      ...before,
+      orders: before.orders + 1,
+      volume: before.volume + 2_000_000,
+      sales: before.sales + 1,
+      salesVolume: before.salesVolume + 2_000_000,
+    });
+  });
+
+  it("Tracks escrowed funds until service orders settle", async () => {
+    const price = 5_000_000;
+    const listing = await m.createListing(price, 0, true);
+    const [released, cancelled] = [await m.buyer(price), await m.buyer(price)];
+    const before = await stats();
+
+    for (const buyer of [released, cancelled]) {
+      await m.createServiceOrder(listing, buyer).rpc();
+    }
+    expect(await stats()).to.deep.equal({
+      ...before,
+      openEscrows: before.openEscrows + 2,
+      locked: before.locked + 2 * price,
+    });
+
+    // a released order is a sale, a cancelled one only gives back its funds
+    const [releasedEscrow] = m.escrowOf(listing, released.kp.publicKey);
+    const [cancelledEscrow] = m.escrowOf(listing, cancelled.kp.publicKey);
+    await (await m.releaseServiceOrder(releasedEscrow, released.kp)).rpc();
+    await (await m.cancelServiceOrder(cancelledEscrow, m.authority)).rpc();
+
+    expect(await stats()).to.deep.equal({
+      ...before,
+      orders: before.orders + 1,
+      volume: before.volume + price,
+      sales: before.sales + 1,
+      salesVolume: before.salesVolume + price,
+    });
+  });
+});
@@ -0,0 +1,135 @@
+import * as anchor from "@coral-xyz/anchor";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { expect } from "chai";
+import { FEE_BPS, Market, balance, eventOf, expectError, wallet } from "./utils/market";
+
+type PublicKey = anchor.web3.PublicKey;
+
+describe("treasury", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  let m: Market;
+
+  before(async () => {
+    m = await Market.create();
+  });
+
+  const withdraw = (amount: number, destination: PublicKey, signer = m.authority) =>
+    m.program.methods
+      .withdrawFees(new anchor.BN(amount))
+      .accountsPartial({
+        marketplace: m.marketplace,
+        treasury: m.treasury,
+        treasuryVault: m.treasuryVault,
+        treasuryAuthority: signer.publicKey,
+        destination,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([signer]);
+
+  const setSplits = (splits: { recipient: PublicKey; bps: number }[], signer = m.authority) =>
+    m.program.methods
+      .setFeeSplits(splits)
+      .accountsPartial({
+        marketplace: m.marketplace,
+        treasury: m.treasury,
+        treasuryAuthority: signer.publicKey,
+      })
+      .signers([signer]);
+
+  const distribute = (recipients: PublicKey[]) =>
+    m.program.methods
+      .distributeFees()
+      .accountsPartial({
+        treasury: m.treasury,
+        treasuryVault: m.treasuryVault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(recipients.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true })));
+
+  // fills the vault with the fee of one sale
+  const sell = async (price: number) => {
+    const listing = await m.createListing(price, 1);
+    await m.buyNow(listing, await m.buyer(price), 1).rpc();
+    return (price * FEE_BPS) / 10_000;
+  };
+
+  it("Collects fees in the program-owned vault and tracks them", async () => {
+    const before = await m.program.account.treasury.fetch(m.treasury);
+    const fee = await sell(10_000_000);
+
+    expect(await balance(m.treasuryVault)).to.be.at.least(fee);
+    const after = await m.program.account.treasury.fetch(m.treasury);
+    expect(after.totalCollected.sub(before.totalCollected).toNumber()).to.equal(fee);
+  });
+
+  it("Only lets the treasury authority withdraw", async () => {
+    await sell(10_000_000);
+    const ops = await wallet();
+    const opsAta = await m.ata(m.mint, ops.publicKey);
+
+    await expectError(withdraw(1, opsAta, ops).rpc(), "ConstraintHasOne");
+
+    // handing the role to a separate key takes it away from the authority
+    await m.updateMarketplace({ treasuryAuthority: ops.publicKey }).rpc();
+    await expectError(withdraw(1, opsAta).rpc(), "ConstraintHasOne");
+
+    const vault = await balance(m.treasuryVault);
+    await expectError(withdraw(vault + 1, opsAta, ops).rpc(), "InvalidAmount");
+    await expectError(withdraw(0, opsAta, ops).rpc(), "InvalidAmount");
+
+    const sig = await withdraw(vault, opsAta, ops).rpc();
+    expect(await balance(opsAta)).to.equal(vault);
+    expect(await balance(m.treasuryVault)).to.equal(0);
+    expect((await eventOf(sig, "feesWithdrawn")).amount.toNumber()).to.equal(vault);
+
+    await m.updateMarketplace({ treasuryAuthority: m.authority.publicKey }).rpc();
+  });
+
+  it("Validates fee splits", async () => {
+    const [a, b] = [anchor.web3.Keypair.generate().publicKey, anchor.web3.Keypair.generate().publicKey];
+    const stranger = await wallet();
+
+    await expectError(
+      setSplits([{ recipient: a, bps: 5_000 }, { recipient: b, bps: 4_999 }]).rpc(),
+      "InvalidFeeSplits"
+    );
+    await expectError(
+      setSplits(Array.from({ length: 6 }, () => ({ recipient: a, bps: 0 }))).rpc(),
+      "InvalidFeeSplits"
+    );
+    await expectError(setSplits([{ recipient: a, bps: 10_000 }], stranger).rpc(), "ConstraintHasOne");
+  });
+
+  it("Distributes the vault among the splits and keeps the dust", async () => {
+    const [ops, rewards] = [await wallet(), await wallet()];
+    const [opsAta, rewardsAta] = [
+      await m.ata(m.mint, ops.publicKey),
+      await m.ata(m.mint, rewards.publicKey),
+    ];
+
+    await setSplits([]).rpc();
+    await expectError(distribute([]).rpc(), "InvalidFeeSplits");
+
+    await setSplits([
+      { recipient: ops.publicKey, bps: 7_000 },
+      { recipient: rewards.publicKey, bps: 3_000 },
+    ]).rpc();
+    await sell(1_000_050);
+    const vault = await balance(m.treasuryVault);
+
+    // recipients must be passed in split order and owned by the split's wallet
+    await expectError(distribute([rewardsAta, opsAta]).rpc(), "InvalidAccount");
+    await expectError(distribute([opsAta]).rpc(), "InvalidFeeSplits");
+
+    const sig = await distribute([opsAta, rewardsAta]).rpc();
+    const [opsShare, rewardsShare] = [Math.floor((vault * 7_000) / 10_000), Math.floor((vault * 3_000) / 10_000)];
+    expect(await balance(opsAta)).to.equal(opsShare);
+    expect(await balance(rewardsAta)).to.equal(rewardsShare);
+    expect(await balance(m.treasuryVault)).to.equal(vault - opsShare - rewardsShare);
+    expect((await eventOf(sig, "feesDistributed")).amount.toNumber()).to.equal(opsShare + rewardsShare);
+
+    const t = await m.program.account.treasury.fetch(m.treasury);
+    expect(t.totalDistributed.toNumber()).to.equal(opsShare + rewardsShare);
+  });
+});
@@ -0,0 +1,453 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Konnect } from "../../target/types/konnect";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createMint,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import { createHash } from "crypto";
+
+const { PublicKey, Keypair, SystemProgram } = anchor.web3;
+type PublicKey = anchor.web3.PublicKey;
+type Keypair = anchor.web3.Keypair;
+
+export const FEE_BPS = 200;
+
+export const program = () => anchor.workspace.Konnect as Program<Konnect>;
+
+export const pda = (...seeds: (Buffer | Uint8Array)[]) =>
+  PublicKey.findProgramAddressSync(seeds, program().programId)[0];
+
+export const u16 = (n: number) => new anchor.BN(n).toArrayLike(Buffer, "le", 2);
+export const u64 = (n: number | anchor.BN) => new anchor.BN(n).toArrayLike(Buffer, "le", 8);
+
+// fails unless the promise rejects with the given anchor error code (e.g. "ListingStillActive")
+export const expectError = async (promise: Promise<unknown>, code: string) => {
+  try {
+    await promise;
+  } catch (e) {
+    const text =
+      e instanceof anchor.AnchorError ? e.error.errorCode.code : `${e}\n${(e.logs ?? []).join("\n")}`;
+    expect(text).to.contain(code);
+    return;
+  }
+  expect.fail(`expected ${code}`);
+};
+
+export const balance = async (account: PublicKey) =>
+  Number((await getAccount(anchor.getProvider().connection, account)).amount);
+
+export const exists = async (account: PublicKey) =>
+  (await anchor.getProvider().connection.getAccountInfo(account)) !== null;
+
+export const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+// events emitted by a confirmed transaction, by name
+export const eventsOf = async (signature: string) => {
+  const connection = anchor.getProvider().connection;
+  await connection.confirmTransaction(signature, "confirmed");
+  const tx = await connection.getTransaction(signature, {
+    commitment: "confirmed",
+    maxSupportedTransactionVersion: 0,
+  });
+  const parser = new anchor.EventParser(program().programId, program().coder);
+  return [...parser.parseLogs(tx.meta.logMessages)];
+};
+
+export const eventOf = async (signature: string, name: string) => {
+  const events = (await eventsOf(signature)).filter((e) => e.name === name);
+  expect(events, name).to.have.length(1);
+  return events[0].data as any;
+};
+
+// compute units a confirmed transaction consumed
+export const computeUnitsOf = async (signature: string) => {
+  const connection = anchor.getProvider().connection;
+  await connection.confirmTransaction(signature, "confirmed");
+  const tx = await connection.getTransaction(signature, {
+    commitment: "confirmed",
+    maxSupportedTransactionVersion: 0,
+  });
+  return tx.meta.computeUnitsConsumed;
+};
+
+export const wallet = async (sol = 2) => {
+  const connection = anchor.getProvider().connection;
+  const kp = Keypair.generate();
+  await connection.confirmTransaction(
+    await connection.requestAirdrop(kp.publicKey, sol * anchor.web3.LAMPORTS_PER_SOL),
+    "confirmed"
+  );
+  return kp;
+};
+
+const sha256 = (...parts: Buffer[]) => createHash("sha256").update(Buffer.concat(parts)).digest();
+
+// merkle allowlist matching PurchaseLimits::is_allowlisted: sha256 leaves, sorted pairs
+export const allowlist = (wallets: PublicKey[]) => {
+  const layers = [wallets.map((w) => sha256(w.toBuffer()))];
+  while (layers[layers.length - 1].length > 1) {
+    const level = layers[layers.length - 1];
+    const next: Buffer[] = [];
+    for (let i = 0; i < level.length; i += 2) {
+      const [a, b] = [level[i], level[i + 1] ?? level[i]];
+      next.push(Buffer.compare(a, b) <= 0 ? sha256(a, b) : sha256(b, a));
+    }
+    layers.push(next);
+  }
+  const proof = (wallet: PublicKey) => {
+    let index = wallets.findIndex((w) => w.equals(wallet));
+    return layers.slice(0, -1).map((level) => {
+      const sibling = level[index ^ 1] ?? level[index];
+      index >>= 1;
+      return Array.from(sibling);
+    });
+  };
+  return { root: Array.from(layers[layers.length - 1][0]), proof };
+};
+
+export const defaultListingArgs = () => ({
+  name: "Listing",
+  imageUrl: "",
+  priceFeed: null as PublicKey | null,
+  metadata: { category: 0, tags: [], uri: "", contentHash: Array(32).fill(0), images: [] },
+  saleWindow: { saleStart: null, saleEnd: null },
+  limits: { maxPerWallet: null, allowlistRoot: null },
+});
+
+export type ListingArgs = ReturnType<typeof defaultListingArgs>;
+
+// A marketplace with a 2% fee, a 6-decimal mint with its treasury and one registered seller.
+// Each instance gets its own marketplace id so test files don't share state.
+export class Market {
+  program = program();
+  connection = anchor.getProvider().connection;
+  authority = (anchor.getProvider() as anchor.AnchorProvider).wallet.payer as Keypair;
+  id = 2 + Math.floor(Math.random() * 60_000);
+  marketplace = pda(Buffer.from("marketplace"), this.authority.publicKey.toBuffer(), u16(this.id));
+  mint: PublicKey;
+  treasury: PublicKey;
+  treasuryVault: PublicKey;
+  seller: Keypair;
+  merchant: PublicKey;
+  sellerAta: PublicKey;
+
+  static async create() {
+    const m = new Market();
+    await m.program.methods
+      .initMarketplace(m.id, FEE_BPS, {
+        name: "Konnect test",
+        uri: "",
+        defaultMint: PublicKey.default,
+      })
+      .accountsPartial({
+        marketplace: m.marketplace,
+        authority: m.authority.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    m.mint = await createMint(m.connection, m.authority, m.authority.publicKey, null, 6);
+    [m.treasury, m.treasuryVault] = await m.initTreasury(m.mint);
+    m.seller = await wallet();
+    m.merchant = await m.registerMerchant(m.seller);
+    m.sellerAta = await m.ata(m.mint, m.seller.publicKey);
+    return m;
+  }
+
+  // marketplace settings, unset fields are left unchanged
+  updateMarketplace(changes: Record<string, unknown>) {
+    const c = changes as any;
+    return this.program.methods
+      .updateMarketplace(
+        c.feeBps ?? null,
+        c.authority ?? null,
+        c.returnWindowSecs === undefined ? null : new anchor.BN(c.returnWindowSecs),
+        c.feeRefundPolicy ?? null,
+        c.arbiter ?? null,
+        c.bond ?? null,
+        c.treasuryAuthority ?? null,
+        c.pointsBps ?? null,
+        c.oracleProgram ?? null
+      )
+      .accountsPartial({ marketplace: this.marketplace, authority: this.authority.publicKey });
+  }
+
+  treasuryOf(mint: PublicKey) {
+    const treasury = pda(Buffer.from("treasury"), this.marketplace.toBuffer(), mint.toBuffer());
+    return [treasury, anchor.utils.token.associatedAddress({ mint, owner: treasury })];
+  }
+
+  async initTreasury(mint: PublicKey) {
+    const [treasury, treasuryVault] = this.treasuryOf(mint);
+    await this.program.methods
+      .initTreasury()
+      .accountsPartial({
+        marketplace: this.marketplace,
+        authority: this.authority.publicKey,
+        treasury,
+        treasuryVault,
+        mint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    return [treasury, treasuryVault];
+  }
+
+  merchantOf(owner: PublicKey) {
+    return pda(Buffer.from("merchant"), this.marketplace.toBuffer(), owner.toBuffer());
+  }
+
+  async registerMerchant(owner: Keypair) {
+    const merchant = this.merchantOf(owner.publicKey);
+    await this.program.methods
+      .registerMerchant()
+      .accountsPartial({
+        marketplace: this.marketplace,
+        merchant,
+        owner: owner.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([owner])
+      .rpc();
+    return merchant;
+  }
+
+  async ata(mint: PublicKey, owner: PublicKey) {
+    return (await getOrCreateAssociatedTokenAccount(this.connection, this.authority, mint, owner, true))
+      .address;
+  }
+
+  async fund(ata: PublicKey, amount: number) {
+    await mintTo(this.connection, this.authority, this.mint, ata, this.authority, amount);
+  }
+
+  // a funded wallet holding `amount` of the market mint
+  async buyer(amount: number) {
+    const kp = await wallet();
+    const ata = await this.ata(this.mint, kp.publicKey);
+    if (amount > 0) {
+      await this.fund(ata, amount);
+    }
+    return { kp, ata };
+  }
+
+  async createListing(
+    price: number,
+    quantity: number,
+    isService = false,
+    overrides: Partial<ListingArgs> = {},
+    // replaces optional accounts, e.g. the merchant's bond
+    accounts: Record<string, PublicKey> = {}
+  ) {
+    const args = { ...defaultListingArgs(), ...overrides };
+    const nonce = (await this.program.account.merchant.fetch(this.merchant)).nextNonce;
+    const listing = pda(Buffer.from("listing"), this.merchant.toBuffer(), u64(nonce));
+    await this.program.methods
+      .createListing(
+        new anchor.BN(price),
+        quantity,
+        isService,
+        args.name,
+        args.imageUrl,
+        args.priceFeed,
+        args.metadata,
+        args.saleWindow,
+        args.limits
+      )
+      .accountsPartial({
+        marketplace: this.marketplace,
+        merchant: this.merchant,
+        owner: this.seller.publicKey,
+        listing,
+        mint: this.mint,
+        bond: null,
+        systemProgram: SystemProgram.programId,
+        ...accounts,
+      })
+      .signers([this.seller])
+      .rpc();
+    return listing;
+  }
+
+  updateListing(listing: PublicKey, changes: Record<string, unknown>) {
+    const c = changes as any;
+    return this.program.methods
+      .updateListing(
+        c.price === undefined ? null : new anchor.BN(c.price),
+        c.quantity ?? null,
+        c.active ?? null,
+        c.saleWindow ?? null,
+        c.limits ?? null,
+        c.priceFeed ?? null
+      )
+      .accountsPartial({ listing, seller: this.seller.publicKey })
+      .signers([this.seller]);
+  }
+
+  closeListing(listing: PublicKey) {
+    return this.program.methods
+      .closeListing()
+      .accountsPartial({ listing, seller: this.seller.publicKey })
+      .signers([this.seller]);
+  }
+
+  // buy_now with the buyer signing and paying for itself; optional accounts default to none
+  buyNow(
+    listing: PublicKey,
+    buyer: { kp: Keypair; ata: PublicKey },
+    quantity: number,
+    opts: {
+      reference?: PublicKey;
+      accounts?: Record<string, PublicKey | null>;
+      maxTotal?: number;
+      expectedRevision?: number;
+      priceGuard?: { expectedRate: anchor.BN; maxSlippageBps: number };
+      variantId?: number;
+      allowlistProof?: number[][];
+      signers?: Keypair[];
+      remaining?: anchor.web3.AccountMeta[];
+    } = {}
+  ) {
+    const reference = opts.reference ?? Keypair.generate().publicKey;
+    return this.program.methods
+      .buyNow(
+        quantity,
+        reference,
+        opts.priceGuard ?? null,
+        opts.expectedRevision ?? null,
+        opts.maxTotal === undefined ? null : new anchor.BN(opts.maxTotal),
+        opts.variantId ?? null,
+        opts.allowlistProof ?? null
+      )
+      .accountsPartial({
+        listing,
+        marketplace: this.marketplace,
+        buyer: buyer.kp.publicKey,
+        signer: buyer.kp.publicKey,
+        session: null,
+        payer: buyer.kp.publicKey,
+        buyerAta: buyer.ata,
+        sellerAta: this.sellerAta,
+        merchant: this.merchant,
+        treasury: this.treasury,
+        treasuryVault: this.treasuryVault,
+        mint: this.mint,
+        priceFeed: null,
+        reservation: null,
+        reservationRentPayer: null,
+        variant: null,
+        purchaseRecord: null,
+        receipt: null,
+        buyerProfile: this.profileOf(buyer.kp.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        ...opts.accounts,
+      })
+      .remainingAccounts(opts.remaining ?? [{ pubkey: reference, isSigner: false, isWritable: false }])
+      .signers(opts.signers ?? [buyer.kp]);
+  }
+
+  profileOf(buyer: PublicKey) {
+    return pda(Buffer.from("buyer"), this.marketplace.toBuffer(), buyer.toBuffer());
+  }
+
+  escrowOf(listing: PublicKey, buyer: PublicKey) {
+    const escrow = pda(Buffer.from("escrow"), listing.toBuffer(), buyer.toBuffer());
+    return [escrow, anchor.utils.token.associatedAddress({ mint: this.mint, owner: escrow })];
+  }
+
+  // buy_now receipts are keyed by the reference alone, service receipts by escrow and reference
+  receiptOf(reference: PublicKey, escrow?: PublicKey) {
+    return escrow
+      ? pda(Buffer.from("receipt"), escrow.toBuffer(), reference.toBuffer())
+      : pda(Buffer.from("receipt"), reference.toBuffer());
+  }
+
+  createServiceOrder(
+    listing: PublicKey,
+    buyer: { kp: Keypair; ata: PublicKey },
+    opts: {
+      reference?: PublicKey;
+      accounts?: Record<string, PublicKey | null>;
+      signers?: Keypair[];
+      remaining?: anchor.web3.AccountMeta[];
+    } = {}
+  ) {
+    const reference = opts.reference ?? Keypair.generate().publicKey;
+    const [escrow, vault] = this.escrowOf(listing, buyer.kp.publicKey);
+    return this.program.methods
+      .createServiceOrder(reference, null, null, null)
+      .accountsPartial({
+        marketplace: this.marketplace,
+        listing,
+        buyer: buyer.kp.publicKey,
+        signer: buyer.kp.publicKey,
+        session: null,
+        payer: buyer.kp.publicKey,
+        buyerAta: buyer.ata,
+        escrow,
+        vault,
+        treasury: this.treasury,
+        mint: this.mint,
+        priceFeed: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        ...opts.accounts,
+      })
+      .remainingAccounts(opts.remaining ?? [{ pubkey: reference, isSigner: false, isWritable: false }])
+      .signers(opts.signers ?? [buyer.kp]);
+  }
+
+  async releaseServiceOrder(escrow: PublicKey, caller: Keypair, accounts: Record<string, PublicKey | null> = {}) {
+    const e = await this.program.account.escrow.fetch(escrow);
+    return this.program.methods
+      .releaseServiceOrder()
+      .accountsPartial({
+        escrow,
+        marketplace: this.marketplace,
+        listing: e.listing,
+        merchant: this.merchantOf(e.seller),
+        payer: caller.publicKey,
+        receipt: this.receiptOf(e.reference, escrow),
+        rentPayer: e.rentPayer.equals(PublicKey.default) ? e.buyer : e.rentPayer,
+        sellerAta: anchor.utils.token.associatedAddress({ mint: e.mint, owner: e.seller }),
+        treasury: this.treasury,
+        treasuryVault: this.treasuryVault,
+        vault: anchor.utils.token.associatedAddress({ mint: e.mint, owner: escrow }),
+        mint: e.mint,
+        buyerProfile: this.profileOf(e.buyer),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        ...accounts,
+      })
+      .signers([caller]);
+  }
+
+  async cancelServiceOrder(escrow: PublicKey, caller: Keypair, accounts: Record<string, PublicKey | null> = {}) {
+    const e = await this.program.account.escrow.fetch(escrow);
+    return this.program.methods
+      .cancelServiceOrder()
+      .accountsPartial({
+        escrow,
+        marketplace: this.marketplace,
+        listing: e.listing,
+        payer: caller.publicKey,
+        rentPayer: e.rentPayer.equals(PublicKey.default) ? e.buyer : e.rentPayer,
+        refundAta: e.fundingAccount,
+        treasury: this.treasury,
+        vault: anchor.utils.token.associatedAddress({ mint: e.mint, owner: escrow }),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        ...accounts,
+      })
+      .signers([caller]);
+  }
+}
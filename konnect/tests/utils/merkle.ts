@@ -0,0 +1,87 @@
+import * as anchor from "@coral-xyz/anchor";
+import { keccak_256 } from "@noble/hashes/sha3";
+
+const { PublicKey, SystemProgram } = anchor.web3;
+type PublicKey = anchor.web3.PublicKey;
+
+// The few pieces of spl-account-compression the compressed listing tests need. The layout
+// follows spl-concurrent-merkle-tree: a header, then the tree's change log ring buffer.
+
+export const SPL_ACCOUNT_COMPRESSION_PROGRAM_ID = new PublicKey(
+  "cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK"
+);
+export const SPL_NOOP_PROGRAM_ID = new PublicKey("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+
+// account type, header version and the v1 header
+const HEADER_SIZE = 2 + 54;
+// sequence number, active index and buffer size
+const TREE_PREFIX_SIZE = 8 + 8 + 8;
+// a change log entry (root, path, index, padding) and the rightmost proof (path, leaf, index, padding)
+const pathSize = (maxDepth: number) => 32 + 32 * maxDepth + 4 + 4;
+
+// size of a tree account without a canopy
+export const treeAccountSize = (maxDepth: number, maxBufferSize: number) =>
+  HEADER_SIZE + TREE_PREFIX_SIZE + (maxBufferSize + 1) * pathSize(maxDepth);
+
+// creates the empty tree account, owned by the compression program, for init_listing_tree
+export const allocTreeIx = async (
+  connection: anchor.web3.Connection,
+  tree: PublicKey,
+  payer: PublicKey,
+  maxDepth: number,
+  maxBufferSize: number
+) => {
+  const space = treeAccountSize(maxDepth, maxBufferSize);
+  return SystemProgram.createAccount({
+    fromPubkey: payer,
+    newAccountPubkey: tree,
+    lamports: await connection.getMinimumBalanceForRentExemption(space),
+    space,
+    programId: SPL_ACCOUNT_COMPRESSION_PROGRAM_ID,
+  });
+};
+
+// root of the newest change log entry
+export const onChainRoot = async (connection: anchor.web3.Connection, tree: PublicKey, maxDepth: number) => {
+  const { data } = await connection.getAccountInfo(tree, "confirmed");
+  const active = Number(data.readBigUInt64LE(HEADER_SIZE + 8));
+  const offset = HEADER_SIZE + TREE_PREFIX_SIZE + active * pathSize(maxDepth);
+  return Buffer.from(data.subarray(offset, offset + 32));
+};
+
+const hash = (left: Buffer, right: Buffer) => Buffer.from(keccak_256(Buffer.concat([left, right])));
+
+// Keccak tree over the leaves appended so far, with every other leaf empty like the on-chain tree.
+export class MerkleTree {
+  private layers: Buffer[][] = [];
+  private empty: Buffer[] = [Buffer.alloc(32)];
+
+  constructor(leaves: (Buffer | undefined)[], depth: number) {
+    for (let level = 1; level <= depth; level++) {
+      this.empty.push(hash(this.empty[level - 1], this.empty[level - 1]));
+    }
+    let nodes = Array.from(leaves, (leaf) => leaf ?? this.empty[0]);
+    for (let level = 0; level < depth; level++) {
+      this.layers.push(nodes);
+      const parents: Buffer[] = [];
+      for (let i = 0; i < nodes.length; i += 2) {
+        parents.push(hash(nodes[i], this.nodeAt(level, i + 1)));
+      }
+      nodes = parents.length ? parents : [this.empty[level + 1]];
+    }
+    this.layers.push(nodes);
+  }
+
+  get root() {
+    return this.layers[this.layers.length - 1][0];
+  }
+
+  // siblings from the leaf up to the root
+  proof(index: number) {
+    return this.layers.slice(0, -1).map((_, level) => this.nodeAt(level, (index >> level) ^ 1));
+  }
+
+  private nodeAt(level: number, index: number) {
+    return this.layers[level][index] ?? this.empty[level];
+  }
+}
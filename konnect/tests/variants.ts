@@ -0,0 +1,127 @@
+import * as anchor from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { FEE_BPS, Market, balance, exists, expectError, pda, u16 } from "./utils/market";
+
+type PublicKey = anchor.web3.PublicKey;
+
+describe("listing variants", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  let m: Market;
+
+  before(async () => {
+    m = await Market.create();
+  });
+
+  const variantOf = (listing: PublicKey, id: number) =>
+    pda(Buffer.from("variant"), listing.toBuffer(), u16(id));
+
+  const addVariant = (listing: PublicKey, id: number, price: number, quantity: number) =>
+    m.program.methods
+      .addVariant(id, new anchor.BN(price), quantity)
+      .accountsPartial({
+        listing,
+        seller: m.seller.publicKey,
+        variant: variantOf(listing, id),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([m.seller]);
+
+  const updateVariant = (
+    listing: PublicKey,
+    id: number,
+    changes: { price?: number; quantity?: number; active?: boolean }
+  ) =>
+    m.program.methods
+      .updateVariant(
+        changes.price === undefined ? null : new anchor.BN(changes.price),
+        changes.quantity ?? null,
+        changes.active ?? null
+      )
+      .accountsPartial({ variant: variantOf(listing, id), listing, seller: m.seller.publicKey })
+      .signers([m.seller]);
+
+  const removeVariant = (listing: PublicKey, id: number) =>
+    m.program.methods
+      .removeVariant()
+      .accountsPartial({ variant: variantOf(listing, id), listing, seller: m.seller.publicKey })
+      .signers([m.seller]);
+
+  const buyVariant = (
+    listing: PublicKey,
+    buyer: { kp: anchor.web3.Keypair; ata: PublicKey },
+    id: number,
+    quantity: number,
+    variant = variantOf(listing, id)
+  ) => m.buyNow(listing, buyer, quantity, { variantId: id, accounts: { variant } });
+
+  it("Sells each variant at its own price and stock", async () => {
+    const listing = await m.createListing(1_000_000, 0);
+    await addVariant(listing, 1, 2_000_000, 3).rpc();
+    await addVariant(listing, 2, 5_000_000, 1).rpc();
+    const l = await m.program.account.listing.fetch(listing);
+    expect(l.variantCount).to.equal(2);
+
+    const buyer = await m.buyer(20_000_000);
+    const sellerBefore = await balance(m.sellerAta);
+    await buyVariant(listing, buyer, 1, 2).rpc();
+
+    const fee = (4_000_000 * FEE_BPS) / 10_000;
+    expect((await balance(m.sellerAta)) - sellerBefore).to.equal(4_000_000 - fee);
+    expect((await m.program.account.variant.fetch(variantOf(listing, 1))).quantity).to.equal(1);
+    expect((await m.program.account.variant.fetch(variantOf(listing, 2))).quantity).to.equal(1);
+
+    // selling out a variant only retires that variant
+    await buyVariant(listing, buyer, 2, 1).rpc();
+    expect((await m.program.account.variant.fetch(variantOf(listing, 2))).active).to.be.false;
+    expect((await m.program.account.listing.fetch(listing)).active).to.be.true;
+    await expectError(buyVariant(listing, buyer, 2, 1).rpc(), "VariantInactive");
+  });
+
+  it("Requires the right variant account", async () => {
+    const listing = await m.createListing(1_000_000, 0);
+    await addVariant(listing, 1, 2_000_000, 3).rpc();
+    await addVariant(listing, 2, 2_000_000, 3).rpc();
+    const buyer = await m.buyer(20_000_000);
+
+    await expectError(m.buyNow(listing, buyer, 1).rpc(), "VariantRequired");
+    await expectError(
+      m.buyNow(listing, buyer, 1, { accounts: { variant: variantOf(listing, 1) } }).rpc(),
+      "WrongVariant"
+    );
+    await expectError(buyVariant(listing, buyer, 1, 1, variantOf(listing, 2)).rpc(), "WrongVariant");
+    await expectError(buyVariant(listing, buyer, 1, 4).rpc(), "InvalidQuantity");
+
+    // a variant of another listing
+    const other = await m.createListing(1_000_000, 0);
+    await addVariant(other, 1, 1_000, 3).rpc();
+    await expectError(buyVariant(listing, buyer, 1, 1, variantOf(other, 1)).rpc(), "ConstraintHasOne");
+  });
+
+  it("Edits and removes variants, bumping the listing revision", async () => {
+    const listing = await m.createListing(1_000_000, 0);
+    await addVariant(listing, 7, 2_000_000, 3).rpc();
+    const { revision } = await m.program.account.listing.fetch(listing);
+
+    await updateVariant(listing, 7, { price: 2_500_000, quantity: 10, active: false }).rpc();
+    const v = await m.program.account.variant.fetch(variantOf(listing, 7));
+    expect([v.price.toNumber(), v.quantity, v.active]).to.deep.equal([2_500_000, 10, false]);
+    expect((await m.program.account.listing.fetch(listing)).revision).to.equal(revision + 1);
+    await expectError(updateVariant(listing, 7, { price: 0 }).rpc(), "InvalidAmount");
+
+    await m.updateListing(listing, { active: false }).rpc();
+    await expectError(m.closeListing(listing).rpc(), "VariantsRemaining");
+    await removeVariant(listing, 7).rpc();
+    expect(await exists(variantOf(listing, 7))).to.be.false;
+    expect((await m.program.account.listing.fetch(listing)).variantCount).to.equal(0);
+    await m.closeListing(listing).rpc();
+  });
+
+  it("Is only for goods listings", async () => {
+    const service = await m.createListing(1_000_000, 0, true);
+    await expectError(addVariant(service, 1, 1_000_000, 1).rpc(), "WrongFlowForService");
+
+    const listing = await m.createListing(1_000_000, 0);
+    await expectError(addVariant(listing, 1, 0, 1).rpc(), "InvalidAmount");
+  });
+});